@@ -21,8 +21,12 @@ impl IControl for Menu {}
 impl Menu {
     #[func]
     pub fn on_open_multiplayer(&self) {
-        self.to_gd()
+        if let Err(e) = self
+            .to_gd()
             .upcast::<Node>()
-            .change_scene_to_multiplayer_main();
+            .change_scene_to_multiplayer_main()
+        {
+            godot_error!("Failed to open multiplayer: {:?}", e);
+        }
     }
 }
@@ -1,28 +1,53 @@
+// @generated by zgrcg, do not edit by hand
 #![allow(dead_code)]
 #![allow(non_snake_case)]
 use godot::builtin::StringName;
 
 /// Maps to: `left_click` or `J`
+///
+/// See also [`InputActionInvocations::is_fire_pressed`](super::actions_invocations::InputActionInvocations::is_fire_pressed)
 pub fn FIRE() -> StringName { StringName::from("Fire") }
 /// Maps to: `left_click` or `J`
+///
+/// See also [`InputActionInvocations::is_fire_pressed`](super::actions_invocations::InputActionInvocations::is_fire_pressed)
 pub const FIRE_STR: &'static str = "Fire";
 /// Maps to: `SPACE`
+///
+/// See also [`InputActionInvocations::is_jump_pressed`](super::actions_invocations::InputActionInvocations::is_jump_pressed)
 pub fn JUMP() -> StringName { StringName::from("jump") }
 /// Maps to: `SPACE`
+///
+/// See also [`InputActionInvocations::is_jump_pressed`](super::actions_invocations::InputActionInvocations::is_jump_pressed)
 pub const JUMP_STR: &'static str = "jump";
 /// Maps to: `S`
+///
+/// See also [`InputActionInvocations::is_move_down_pressed`](super::actions_invocations::InputActionInvocations::is_move_down_pressed)
 pub fn MOVE_DOWN() -> StringName { StringName::from("move_down") }
 /// Maps to: `S`
+///
+/// See also [`InputActionInvocations::is_move_down_pressed`](super::actions_invocations::InputActionInvocations::is_move_down_pressed)
 pub const MOVE_DOWN_STR: &'static str = "move_down";
 /// Maps to: `A`
+///
+/// See also [`InputActionInvocations::is_move_left_pressed`](super::actions_invocations::InputActionInvocations::is_move_left_pressed)
 pub fn MOVE_LEFT() -> StringName { StringName::from("move_left") }
 /// Maps to: `A`
+///
+/// See also [`InputActionInvocations::is_move_left_pressed`](super::actions_invocations::InputActionInvocations::is_move_left_pressed)
 pub const MOVE_LEFT_STR: &'static str = "move_left";
 /// Maps to: `D`
+///
+/// See also [`InputActionInvocations::is_move_right_pressed`](super::actions_invocations::InputActionInvocations::is_move_right_pressed)
 pub fn MOVE_RIGHT() -> StringName { StringName::from("move_right") }
 /// Maps to: `D`
+///
+/// See also [`InputActionInvocations::is_move_right_pressed`](super::actions_invocations::InputActionInvocations::is_move_right_pressed)
 pub const MOVE_RIGHT_STR: &'static str = "move_right";
 /// Maps to: `W`
+///
+/// See also [`InputActionInvocations::is_move_up_pressed`](super::actions_invocations::InputActionInvocations::is_move_up_pressed)
 pub fn MOVE_UP() -> StringName { StringName::from("move_up") }
 /// Maps to: `W`
+///
+/// See also [`InputActionInvocations::is_move_up_pressed`](super::actions_invocations::InputActionInvocations::is_move_up_pressed)
 pub const MOVE_UP_STR: &'static str = "move_up";
\ No newline at end of file
@@ -1,3 +1,4 @@
+// @generated by zgrcg, do not edit by hand
 #![allow(dead_code)]
 use godot::classes::Input;
 
@@ -43,6 +44,11 @@ pub trait InputActionInvocations {
     fn is_move_up_just_pressed(&self) -> bool;
     /// Returns true when `W` is just released
     fn is_move_up_just_released(&self) -> bool;
+
+    /// Returns true when any of the actions is just pressed
+    fn any_action_just_pressed(&self) -> bool;
+    /// Returns the names of the actions currently pressed
+    fn pressed_actions(&self) -> Vec<&'static str>;
 }
 
 impl InputActionInvocations for Input {
@@ -69,4 +75,19 @@ impl InputActionInvocations for Input {
     fn is_move_up_pressed(&self) -> bool { self.is_action_pressed("move_up") }
     fn is_move_up_just_pressed(&self) -> bool { self.is_action_just_pressed("move_up") }
     fn is_move_up_just_released(&self) -> bool { self.is_action_just_released("move_up") }
+
+    fn any_action_just_pressed(&self) -> bool {
+        self.is_action_just_pressed("Fire")
+            || self.is_action_just_pressed("jump")
+            || self.is_action_just_pressed("move_down")
+            || self.is_action_just_pressed("move_left")
+            || self.is_action_just_pressed("move_right")
+            || self.is_action_just_pressed("move_up")
+    }
+    fn pressed_actions(&self) -> Vec<&'static str> {
+        ["Fire", "jump", "move_down", "move_left", "move_right", "move_up"]
+            .into_iter()
+            .filter(|action| self.is_action_pressed(*action))
+            .collect()
+    }
 }
\ No newline at end of file
@@ -1,12 +1,93 @@
+// @generated by zgrcg, do not edit by hand
 #![allow(dead_code)]
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
 pub enum Physics2d {
     COLLISIONS = 1,
     NONCOLLIDING = 2,
 }
 
+impl std::fmt::Display for Physics2d {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::COLLISIONS => write!(f, "COLLISIONS"),
+            Self::NONCOLLIDING => write!(f, "NONCOLLIDING"),
+        }
+    }
+}
+
+impl Physics2d {
+    /// Returns the layer for a single mask bit, e.g. `2` for the second layer.
+    pub fn from_bit(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(Self::COLLISIONS),
+            2 => Some(Self::NONCOLLIDING),
+            _ => None,
+        }
+    }
+
+    /// Returns the layers set in a mask, e.g. both layers for `3`.
+    pub fn from_bits(value: u32) -> Vec<Self> {
+        [Self::COLLISIONS, Self::NONCOLLIDING]
+            .into_iter()
+            .filter(|layer| value & *layer as u32 != 0)
+            .collect()
+    }
+}
+
+impl From<Physics2d> for u32 {
+    fn from(layer: Physics2d) -> Self {
+        layer as u32
+    }
+}
+
+impl From<Physics2d> for i64 {
+    fn from(layer: Physics2d) -> Self {
+        i64::from(layer as u32)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
 pub enum Render2d {
     GHOSTS = 1,
 }
+
+impl std::fmt::Display for Render2d {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GHOSTS => write!(f, "GHOSTS"),
+        }
+    }
+}
+
+impl Render2d {
+    /// Returns the layer for a single mask bit, e.g. `2` for the second layer.
+    pub fn from_bit(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(Self::GHOSTS),
+            _ => None,
+        }
+    }
+
+    /// Returns the layers set in a mask, e.g. both layers for `3`.
+    pub fn from_bits(value: u32) -> Vec<Self> {
+        [Self::GHOSTS]
+            .into_iter()
+            .filter(|layer| value & *layer as u32 != 0)
+            .collect()
+    }
+}
+
+impl From<Render2d> for u32 {
+    fn from(layer: Render2d) -> Self {
+        layer as u32
+    }
+}
+
+impl From<Render2d> for i64 {
+    fn from(layer: Render2d) -> Self {
+        i64::from(layer as u32)
+    }
+}
@@ -1,5 +1,5 @@
 pub mod actions_consts;
 pub mod actions_invocations;
 pub mod layer_consts;
-pub mod scene_consts;
 pub mod scene_actions;
+pub mod scene_consts;
@@ -1,32 +1,42 @@
+// @generated by zgrcg, do not edit by hand
 #![allow(dead_code)]
 use godot::{
+    builtin::GString,
     prelude::Node,
     global::Error
 };
 
+/// The reason a scene change could not be started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneChangeError {
+    /// The node is not inside a `SceneTree`.
+    NoSceneTree,
+    /// Godot returned an error from `change_scene_to_file`.
+    Godot(Error),
+}
+
 pub trait SceneActions {
-    fn change_scene_to(&self, scene_path: &str) -> Option<Error>;
+    /// Changes to the scene at `scene_path`, returning `Ok(())` when Godot reports `Error::OK`.
+    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError>;
     /// `res://scenes/LevelOne.tscn`
-    fn change_scene_to_level_one(&self) -> Option<Error>;
+    fn change_scene_to_level_one(&self) -> Result<(), SceneChangeError>;
     /// `res://scenes/Main.tscn`
-    fn change_scene_to_main(&self) -> Option<Error>;
+    fn change_scene_to_main(&self) -> Result<(), SceneChangeError>;
     /// `res://scenes/multiplayer/Main.tscn`
-    fn change_scene_to_multiplayer_main(&self) -> Option<Error>;
+    fn change_scene_to_multiplayer_main(&self) -> Result<(), SceneChangeError>;
 }
 
 impl SceneActions for Node {
-    fn change_scene_to(&self, scene_path: &str) -> Option<Error> {
-        let st = self.get_tree();
-        let mut err = None;
+    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError> {
+        let mut st = self.get_tree().ok_or(SceneChangeError::NoSceneTree)?;
 
-        if st.is_some() {
-            err = Some(st.unwrap().change_scene_to_file(scene_path));
+        match st.change_scene_to_file(&GString::from(scene_path)) {
+            Error::OK => Ok(()),
+            err => Err(SceneChangeError::Godot(err)),
         }
-
-        err
     }
 
-    fn change_scene_to_level_one(&self) -> Option<Error> { self.change_scene_to("res://scenes/LevelOne.tscn") }
-    fn change_scene_to_main(&self) -> Option<Error> { self.change_scene_to("res://scenes/Main.tscn") }
-    fn change_scene_to_multiplayer_main(&self) -> Option<Error> { self.change_scene_to("res://scenes/multiplayer/Main.tscn") }
+    fn change_scene_to_level_one(&self) -> Result<(), SceneChangeError> { self.change_scene_to("res://scenes/LevelOne.tscn") }
+    fn change_scene_to_main(&self) -> Result<(), SceneChangeError> { self.change_scene_to("res://scenes/Main.tscn") }
+    fn change_scene_to_multiplayer_main(&self) -> Result<(), SceneChangeError> { self.change_scene_to("res://scenes/multiplayer/Main.tscn") }
 }
\ No newline at end of file
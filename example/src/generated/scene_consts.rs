@@ -1,3 +1,4 @@
+// @generated by zgrcg, do not edit by hand
 #![allow(dead_code)]
 /// `res://scenes/LevelOne.tscn`
 pub const LEVEL_ONE: &'static str = "res://scenes/LevelOne.tscn";
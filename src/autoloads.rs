@@ -0,0 +1,246 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::{
+    mod_file::GeneratedModule,
+    projectgodot::ProjectGodot,
+    scenes::to_module_name,
+    tscn::Tscn,
+    utils::{format_lint_allows, is_engine_class, resolve_class_type, write_generated_file},
+};
+
+const MOD_AUTOLOADS: &str = "autoloads";
+
+/// Generates an accessor for each autoload in the `[autoload]` section of `project.godot`, returning it as the type of the root
+/// node of its scene, or `Node` for scripts and scenes whose root type can't be read or resolved with `custom_class_paths`.
+pub fn generate_typed_autoloads(
+    output_dir: &str,
+    resource_path: &str,
+    godot_project: &ProjectGodot,
+    custom_class_paths: &BTreeMap<String, String>,
+    strict_lints: bool,
+) -> io::Result<Vec<GeneratedModule>> {
+    let Some(autoload) = godot_project
+        .autoload
+        .as_ref()
+        .filter(|autoload| !autoload.autoloads.is_empty())
+    else {
+        println!(
+            "cargo::warning=No autoloads found in the project settings, skipping autoloads.rs generation"
+        );
+        return Ok(vec![]);
+    };
+
+    let mut accessors: Vec<(String, &str, &str, String)> = vec![];
+    for (name, path) in &autoload.autoloads {
+        // a leading `*` marks the autoload as a global singleton in GDScript
+        let path = path.trim_start_matches('*');
+        let function = to_module_name(name);
+        if accessors.iter().any(|(f, _, _, _)| *f == function) {
+            println!(
+                "cargo::warning=Autoload {} has the same accessor {} as another autoload, skipping",
+                name, function
+            );
+            continue;
+        }
+
+        let root_type = autoload_root_type(resource_path, path)
+            .map(|class| resolve_class_type(&class, custom_class_paths, path))
+            .unwrap_or_else(|| "Node".into());
+        accessors.push((function, name, path, root_type));
+    }
+
+    let autoloads_path = Path::new(output_dir).join(format!("{}.rs", MOD_AUTOLOADS));
+
+    write_generated_file(
+        &autoloads_path,
+        &format_autoloads_to_accessors(&accessors, strict_lints),
+    )?;
+
+    Ok(vec![GeneratedModule {
+        name: MOD_AUTOLOADS.to_string(),
+        path: autoloads_path,
+    }])
+}
+#[test]
+fn test_generate_typed_autoloads() {
+    let dir = crate::utils::TestDir::new("generate_typed_autoloads");
+    fs::create_dir_all(dir.join("godot").join("game")).unwrap();
+    fs::write(
+        dir.join("godot").join("game").join("GameState.tscn"),
+        "[gd_scene format=3]\n\n[node name=\"GameState\" type=\"GameState\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("godot").join("game").join("Hud.tscn"),
+        "[gd_scene format=3]\n\n[node name=\"Hud\" type=\"CanvasLayer\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("godot").join("game").join("Save.tscn"),
+        "[gd_scene format=3]\n\n[node name=\"Save\" type=\"SaveSystem\"]\n",
+    )
+    .unwrap();
+    let project = ProjectGodot::parse_from_str(
+        "[autoload]\n\nGameState=\"*res://game/GameState.tscn\"\nHud=\"*res://game/Hud.tscn\"\nSave=\"*res://game/Save.tscn\"\nmusic=\"*res://audio/music.gd\"\nMatch=\"*res://game/match.gd\"\n",
+    );
+
+    let modules = generate_typed_autoloads(
+        dir.join("generated").to_str().unwrap(),
+        dir.join("godot").to_str().unwrap(),
+        &project,
+        &BTreeMap::from([("GameState".to_string(), "crate::GameState".to_string())]),
+        false,
+    )
+    .unwrap();
+    assert_eq!(modules.len(), 1);
+
+    // only engine classes are imported, and user classes use their configured path
+    let content = fs::read_to_string(&modules[0].path).unwrap();
+    assert!(content.contains("use godot::classes::{CanvasLayer, Engine, Node, SceneTree};\n"));
+    assert!(content.contains(
+        "/// `res://game/GameState.tscn`\npub fn game_state() -> Option<Gd<crate::GameState>> {\n    autoload(\"GameState\")\n}\n"
+    ));
+    assert!(content.contains("pub fn hud() -> Option<Gd<CanvasLayer>> {\n"));
+    // a class without a configured path falls back to `Node`
+    assert!(content.contains("pub fn save() -> Option<Gd<Node>> {\n"));
+    // a script's type can't be read, so it falls back to `Node`
+    assert!(content.contains("pub fn music() -> Option<Gd<Node>> {\n    autoload(\"music\")\n}\n"));
+    // a keyword is escaped as a raw identifier
+    assert!(
+        content.contains("pub fn r#match() -> Option<Gd<Node>> {\n    autoload(\"Match\")\n}\n")
+    );
+}
+
+/// Returns the path of each autoload scene in the resource path, whose root type the accessors are generated from.
+pub fn autoload_scene_paths(resource_path: &str, godot_project: &ProjectGodot) -> Vec<String> {
+    godot_project
+        .autoload
+        .as_ref()
+        .map(|autoload| {
+            autoload
+                .autoloads
+                .values()
+                .map(|path| path.trim_start_matches('*'))
+                .filter(|path| path.ends_with(".tscn"))
+                .map(|path| {
+                    Path::new(resource_path)
+                        .join(path.trim_start_matches("res://"))
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+#[test]
+fn test_autoload_scene_paths() {
+    let project = ProjectGodot::parse_from_str(
+        "[autoload]\n\nGameState=\"*res://game/GameState.tscn\"\nmusic=\"*res://audio/music.gd\"\n",
+    );
+
+    assert_eq!(
+        autoload_scene_paths("godot", &project),
+        vec![
+            Path::new("godot")
+                .join("game/GameState.tscn")
+                .to_string_lossy()
+                .into_owned()
+        ]
+    );
+}
+
+/// Reads the type of the root node of the autoload's scene, if it is a scene with a typed root.
+fn autoload_root_type(resource_path: &str, autoload_path: &str) -> Option<String> {
+    if !autoload_path.ends_with(".tscn") {
+        return None;
+    }
+
+    let scene_path = Path::new(resource_path).join(autoload_path.trim_start_matches("res://"));
+    match fs::read_to_string(&scene_path) {
+        Ok(content) => Tscn::parse_from_str(&content)
+            .root()?
+            .node_type
+            .map(str::to_string),
+        Err(e) => {
+            println!(
+                "cargo::warning=Failed to read autoload scene {}: {}, typing it as Node",
+                scene_path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Formats `(function, autoload name, path, root type)` accessors into the content of the autoloads file.
+fn format_autoloads_to_accessors(
+    accessors: &[(String, &str, &str, String)],
+    strict_lints: bool,
+) -> String {
+    // user classes are named by their path rather than imported
+    let mut types = accessors
+        .iter()
+        .map(|(_, _, _, root_type)| root_type.as_str())
+        .filter(|root_type| is_engine_class(root_type))
+        .chain(["Engine", "Node", "SceneTree"])
+        .collect::<Vec<&str>>();
+    types.sort();
+    types.dedup();
+
+    format!(
+        r#"{}use godot::classes::{{{}}};
+use godot::obj::{{Gd, Inherits, Singleton}};
+
+/// Returns the autoload named `name` as a `T`, or `None` if the scene tree isn't running, or the autoload is missing or not a `T`.
+pub fn autoload<T: Inherits<Node>>(name: &str) -> Option<Gd<T>> {{
+    Engine::singleton()
+        .get_main_loop()?
+        .try_cast::<SceneTree>()
+        .ok()?
+        .get_root()?
+        .try_get_node_as::<T>(name)
+}}
+{}"#,
+        format_lint_allows(strict_lints, false),
+        types.join(", "),
+        accessors
+            .iter()
+            .map(|(function, name, path, root_type)| format!(
+                "\n/// `{}`\npub fn {}() -> Option<Gd<{}>> {{\n    autoload(\"{}\")\n}}\n",
+                path, function, root_type, name
+            ))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_autoloads_to_accessors() {
+    let accessors = vec![(
+        "gamestate".to_string(),
+        "gamestate",
+        "res://src/game/gamestate.tscn",
+        "Node2D".to_string(),
+    )];
+
+    assert_eq!(
+        format_autoloads_to_accessors(&accessors, false),
+        r#"#![allow(dead_code)]
+use godot::classes::{Engine, Node, Node2D, SceneTree};
+use godot::obj::{Gd, Inherits, Singleton};
+
+/// Returns the autoload named `name` as a `T`, or `None` if the scene tree isn't running, or the autoload is missing or not a `T`.
+pub fn autoload<T: Inherits<Node>>(name: &str) -> Option<Gd<T>> {
+    Engine::singleton()
+        .get_main_loop()?
+        .try_cast::<SceneTree>()
+        .ok()?
+        .get_root()?
+        .try_get_node_as::<T>(name)
+}
+
+/// `res://src/game/gamestate.tscn`
+pub fn gamestate() -> Option<Gd<Node2D>> {
+    autoload("gamestate")
+}
+"#
+    );
+}
@@ -0,0 +1,304 @@
+use std::{collections::BTreeMap, fs, io};
+
+use serde::Deserialize;
+
+use crate::{Generator, error::GeneratorError, utils::LineEnding};
+
+/// The content of a `zgrcg.toml`, with each key named after the builder method it calls.
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    output_dir: Option<String>,
+    project_godot_path: Option<String>,
+    override_cfg_path: Option<String>,
+    gdextension_path: Option<String>,
+    resource_path: Option<String>,
+    source_path: Option<String>,
+    #[serde(default)]
+    additional_source_paths: Vec<String>,
+    #[serde(default)]
+    ignore_source_globs: Vec<String>,
+    target_godot_version: Option<String>,
+    keybindings_markdown: Option<String>,
+    gdscript_consts: Option<String>,
+    #[serde(default)]
+    scene_allow: Vec<String>,
+    #[serde(default)]
+    scene_deny: Vec<String>,
+    scene_actions_impl_for: Option<String>,
+    #[serde(default)]
+    custom_class_paths: BTreeMap<String, String>,
+    single_file_output: Option<String>,
+    indent: Option<String>,
+    const_prefix: Option<String>,
+    line_ending: Option<LineEnding>,
+    #[serde(default)]
+    icon_source: Vec<IconSourceConfig>,
+    /// The features and options enabled with `true`, checked against `toggle` when applied.
+    #[serde(flatten)]
+    toggles: BTreeMap<String, toml::Value>,
+}
+
+/// An `[[icon_source]]` table of a `zgrcg.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IconSourceConfig {
+    prefix: String,
+    source: String,
+}
+
+impl Generator {
+    /// Create a generator from the settings in a TOML file, e.g. a `zgrcg.toml` checked in next to `build.rs`.
+    ///
+    /// Each top level key is named after the builder method it calls, e.g. `output_dir` for `set_output_dir`, and each `output_*`
+    /// feature or option is enabled with `true`, e.g. `layer_consts = true` or `strict_lints = true`. Each `[[icon_source]]` table
+    /// adds an icon source with its `prefix` and `source`, and the `[custom_class_paths]` table maps class names to their paths.
+    /// Paths are relative to the working directory, which is the package root in `build.rs`.
+    ///
+    /// ```toml
+    /// output_dir = "./src/generated"
+    /// project_godot_path = "../godot/project.godot"
+    /// layer_consts = true
+    /// ignore_source_globs = ["generated/**"]
+    ///
+    /// [[icon_source]]
+    /// prefix = "res://icons/gd/"
+    /// source = "https://example.com/icons/"
+    ///
+    /// [custom_class_paths]
+    /// GameState = "crate::game::GameState"
+    /// ```
+    ///
+    /// Fails with `GeneratorError::Parse` if the file has an unknown key or a value of the wrong type, and with
+    /// `GeneratorError::Validation` if the resulting configuration is invalid.
+    pub fn from_toml(path: &str) -> Result<Generator, GeneratorError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Failed to read {}: {}", path, e)))?;
+        let parse_error = |reason: String| GeneratorError::Parse {
+            file: path.to_string(),
+            reason,
+        };
+
+        let config = toml::from_str::<Config>(&content).map_err(|e| parse_error(e.to_string()))?;
+        let generator = apply_config(Generator::builder(), config).map_err(parse_error)?;
+
+        generator.validate().map_err(GeneratorError::Validation)?;
+        Ok(generator)
+    }
+}
+#[test]
+fn test_from_toml() {
+    let dir = crate::utils::TestDir::new("from_toml");
+    fs::create_dir_all(dir.join("generated")).unwrap();
+    fs::create_dir_all(dir.join("godot")).unwrap();
+    fs::write(dir.join("project.godot"), "config_version=5\n").unwrap();
+
+    let config_path = dir.join("zgrcg.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"# codegen settings
+output_dir = "{0}/generated"
+project_godot_path = "{0}/project.godot"
+resource_path = "{0}/godot"
+
+layer_consts = true
+action_consts = true
+scene_consts = false
+strict_lints = true
+line_ending = "crlf"
+ignore_source_globs = [
+    "generated/**", # generated code has no icons
+    "**/*.gen.rs",
+]
+
+[[icon_source]]
+prefix = "res://icons/gd/"
+source = "https://example.com/icons/"
+
+[custom_class_paths]
+GameState = "crate::game::GameState"
+"#,
+            dir.to_string_lossy().replace('\\', "/")
+        ),
+    )
+    .unwrap();
+
+    let generator = Generator::from_toml(config_path.to_str().unwrap()).unwrap();
+    assert!(generator.layer_consts);
+    assert!(generator.action_consts);
+    assert!(!generator.scene_consts);
+    assert!(generator.strict_lints);
+    assert_eq!(generator.line_ending, LineEnding::CrLf);
+    assert!(generator.project_godot_path_valid);
+    assert!(generator.project_godot_path.ends_with("/project.godot"));
+    assert!(generator.output_dir.ends_with("/generated"));
+    assert_eq!(
+        generator.ignore_source_globs,
+        vec!["generated/**".to_string(), "**/*.gen.rs".to_string()]
+    );
+    assert_eq!(
+        generator.icon_sources.get("res://icons/gd/"),
+        Some(&"https://example.com/icons/".to_string())
+    );
+
+    assert_eq!(
+        generator.custom_class_paths.get("GameState"),
+        Some(&"crate::game::GameState".to_string())
+    );
+
+    for invalid in [
+        "layer_consts = \"yes\"\n",
+        "unknown_feature = true\n",
+        "line_ending = \"cr\"\n",
+        "output_dir = 1\n",
+        "[[icon_source]]\nprefix = \"res://icons/\"\n",
+    ] {
+        fs::write(&config_path, invalid).unwrap();
+        assert!(
+            matches!(
+                Generator::from_toml(config_path.to_str().unwrap()),
+                Err(GeneratorError::Parse { .. })
+            ),
+            "{}",
+            invalid
+        );
+    }
+
+    // enabling a feature without its paths fails the usual validation
+    fs::write(&config_path, "layer_consts = true\n").unwrap();
+    assert!(matches!(
+        Generator::from_toml(config_path.to_str().unwrap()),
+        Err(GeneratorError::Validation(_))
+    ));
+}
+
+/// Calls the builder method for each key of the config, then enables each feature or option.
+fn apply_config(mut generator: Generator, config: Config) -> Result<Generator, String> {
+    type Setter = fn(Generator, &str) -> Generator;
+    let optional_setters: [(Option<String>, Setter); 13] = [
+        (config.output_dir, Generator::set_output_dir),
+        (config.project_godot_path, Generator::set_project_godot_path),
+        (config.override_cfg_path, Generator::set_override_cfg_path),
+        (config.gdextension_path, Generator::set_gdextension_path),
+        (config.resource_path, Generator::set_resource_path),
+        (config.source_path, Generator::set_source_path),
+        (
+            config.target_godot_version,
+            Generator::set_target_godot_version,
+        ),
+        (
+            config.keybindings_markdown,
+            Generator::output_keybindings_markdown,
+        ),
+        (config.gdscript_consts, Generator::output_gdscript_consts),
+        (
+            config.scene_actions_impl_for,
+            Generator::scene_actions_impl_for,
+        ),
+        (config.single_file_output, Generator::single_file_output),
+        (config.indent, Generator::set_indent),
+        (config.const_prefix, Generator::const_prefix),
+    ];
+    for (value, setter) in optional_setters {
+        if let Some(value) = value {
+            generator = setter(generator, &value);
+        }
+    }
+
+    if let Some(line_ending) = config.line_ending {
+        generator = generator.set_line_ending(line_ending);
+    }
+
+    for path in &config.additional_source_paths {
+        generator = generator.add_source_path(path);
+    }
+    fn as_strs(items: &[String]) -> Vec<&str> {
+        items.iter().map(String::as_str).collect()
+    }
+    generator = generator
+        .ignore_source_globs(&as_strs(&config.ignore_source_globs))
+        .scene_name_filter(&as_strs(&config.scene_allow), &as_strs(&config.scene_deny))
+        .custom_class_paths(
+            &config
+                .custom_class_paths
+                .iter()
+                .map(|(class, path)| (class.as_str(), path.as_str()))
+                .collect::<Vec<(&str, &str)>>(),
+        );
+
+    for icon_source in &config.icon_source {
+        generator = generator.add_icon_source(&icon_source.prefix, &icon_source.source);
+    }
+
+    // features and options, which are only ever enabled
+    for (key, value) in &config.toggles {
+        match (toggle(key), value) {
+            (Some(_), toml::Value::Boolean(false)) => {}
+            (Some(method), toml::Value::Boolean(true)) => generator = method(generator),
+            _ => return Err(format!("unknown key or invalid value for {}", key)),
+        }
+    }
+
+    Ok(generator)
+}
+
+/// The builder method enabling a feature or option, by its key in the config.
+fn toggle(key: &str) -> Option<fn(Generator) -> Generator> {
+    let method: fn(Generator) -> Generator = match key {
+        "layer_consts" => Generator::output_layer_consts,
+        "layers_fill_unnamed" => Generator::layers_fill_unnamed,
+        "layers_as_masks" => Generator::layers_as_masks,
+        "library_consts" => Generator::output_library_consts,
+        "group_consts" => Generator::output_group_consts,
+        "group_consts_string_names" => Generator::group_consts_string_names,
+        "typed_autoloads" => Generator::output_typed_autoloads,
+        "translation_keys" => Generator::output_translation_keys,
+        "action_consts" => Generator::output_action_consts,
+        "action_consts_cached" => Generator::action_consts_cached,
+        "action_consts_tests" => Generator::output_action_consts_tests,
+        "action_invocations" => Generator::output_action_invocations,
+        "action_registration" => Generator::output_action_registration,
+        "default_bindings" => Generator::output_default_bindings,
+        "action_enum" => Generator::output_action_enum,
+        "icon_comments" => Generator::output_icon_comments,
+        "scene_consts" => Generator::output_scene_consts,
+        "scene_consts_grouped" => Generator::scene_consts_grouped,
+        "scene_consts_use_uid" => Generator::scene_consts_use_uid,
+        "scan_godot_cache_dirs" => Generator::scan_godot_cache_dirs,
+        "scene_actions" => Generator::output_scene_actions,
+        "scene_actions_current_scene_predicates" => {
+            Generator::scene_actions_current_scene_predicates
+        }
+        "scene_nodes" => Generator::output_scene_nodes,
+        "scene_dependencies" => Generator::output_scene_dependencies,
+        "scene_node_accessors" => Generator::output_scene_node_accessors,
+        "scene_spawners" => Generator::output_scene_spawners,
+        "class_registry" => Generator::output_class_registry,
+        "reexport_generated" => Generator::reexport_generated,
+        "prelude" => Generator::output_prelude,
+        "clean_orphans" => Generator::clean_orphans,
+        "skip_unchanged" => Generator::skip_unchanged,
+        "verbose" => Generator::verbose,
+        "strict_lints" => Generator::strict_lints,
+        "export_env_vars" => Generator::export_env_vars,
+        "use_out_dir" => Generator::use_out_dir,
+        _ => return None,
+    };
+    Some(method)
+}
+#[test]
+fn test_toggle_covers_every_option() {
+    // every builder method taking no arguments is a feature or option, enabled by its name without `output_`
+    let lib = include_str!("lib.rs");
+    let methods = lib
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("pub fn "))
+        .filter_map(|line| line.strip_suffix("(mut self) -> Self {"))
+        .collect::<Vec<&str>>();
+    assert!(methods.len() > 30);
+
+    for method in methods {
+        let key = method.strip_prefix("output_").unwrap_or(method);
+        assert!(toggle(key).is_some(), "{} has no config key", method);
+    }
+}
@@ -0,0 +1,61 @@
+use std::{error, fmt, io};
+
+/// An error which stopped the generator, returned by `Generator::try_generate`.
+#[derive(Debug)]
+pub enum GeneratorError {
+    /// A file could not be read or written.
+    Io(io::Error),
+    /// A file could not be parsed.
+    Parse { file: String, reason: String },
+    /// The generator was configured incorrectly, with each problem found.
+    Validation(Vec<String>),
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::Io(e) => write!(f, "IO error: {}", e),
+            GeneratorError::Parse { file, reason } => {
+                write!(f, "Failed to parse {}: {}", file, reason)
+            }
+            GeneratorError::Validation(errors) => {
+                write!(f, "Invalid configuration: {}", errors.join("; "))
+            }
+        }
+    }
+}
+
+impl error::Error for GeneratorError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            GeneratorError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GeneratorError {
+    fn from(e: io::Error) -> Self {
+        GeneratorError::Io(e)
+    }
+}
+#[test]
+fn test_generator_error_display() {
+    assert_eq!(
+        GeneratorError::Io(io::Error::new(io::ErrorKind::NotFound, "missing")).to_string(),
+        "IO error: missing"
+    );
+    assert_eq!(
+        GeneratorError::Parse {
+            file: "rust.gdextension".into(),
+            reason: "no sections".into()
+        }
+        .to_string(),
+        "Failed to parse rust.gdextension: no sections"
+    );
+    assert_eq!(
+        GeneratorError::Validation(vec!["first".into(), "second".into()]).to_string(),
+        "Invalid configuration: first; second"
+    );
+    assert!(error::Error::source(&GeneratorError::from(io::Error::other("inner"))).is_some());
+}
@@ -3,9 +3,16 @@
 
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+};
+
+use crate::utils::GdValue;
 
 pub struct Gdextension<'a> {
+    /// Any content before the first section header, e.g. a leading comment block.
+    pub preamble: Option<&'a str>,
     pub configuration: Option<ConfigurationSection<'a>>,
     pub libraries: Option<LibrariesSection<'a>>,
     pub icons: Option<IconsSection<'a>>,
@@ -16,7 +23,10 @@ impl Gdextension<'_> {
     pub fn parse_from_str<'a>(content: &'a str) -> Gdextension<'a> {
         let mut gdextension = Gdextension::new();
 
-        let sections = Self::split_sections(content);
+        let (preamble, sections) = Self::split_sections(content);
+        if !preamble.trim().is_empty() {
+            gdextension.preamble = Some(preamble);
+        }
 
         for section in sections {
             if let Some(config) = ConfigurationSection::parse(&section) {
@@ -33,8 +43,52 @@ impl Gdextension<'_> {
         gdextension
     }
 
+    /// Reads and parses the `.gdextension` at `path`, passing it to `f` and returning its result.
+    ///
+    /// As the parsed `.gdextension` borrows from the file content, it is passed to a closure rather than returned, so copy out
+    /// anything needed afterwards, e.g. with `to_string`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use zgrcg::Gdextension;
+    ///
+    /// let windows_library = Gdextension::parse_from_file("./rust.gdextension", |gdextension| {
+    ///     gdextension
+    ///         .libraries
+    ///         .as_ref()
+    ///         .and_then(|l| l.libraries.get("windows.release.x86_64").map(|path| path.to_string()))
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn parse_from_file<R>(path: &str, f: impl FnOnce(&Gdextension) -> R) -> io::Result<R> {
+        let content = fs::read_to_string(path)?;
+        Ok(f(&Gdextension::parse_from_str(&content)))
+    }
+
+    /// Creates a minimal `.gdextension` with the given entry symbol and libraries, e.g. for a fresh project.
+    pub(crate) fn new_minimal<'a>(
+        entry_symbol: &'a str,
+        libraries: &[(&'a str, &'a str)],
+    ) -> Gdextension<'a> {
+        let mut gdextension = Gdextension::new();
+
+        gdextension.configuration = Some(ConfigurationSection {
+            entry_symbol: Some(entry_symbol),
+            compatibility_minimum: Some("4.1"),
+            compatibility_maximum: None,
+            reloadable: Some(true),
+            android_aar_plugin: None,
+        });
+        gdextension.libraries = Some(LibrariesSection {
+            libraries: libraries.iter().copied().collect(),
+        });
+
+        gdextension
+    }
+
     fn new() -> Self {
         Self {
+            preamble: None,
             configuration: None,
             libraries: None,
             icons: None,
@@ -42,38 +96,188 @@ impl Gdextension<'_> {
         }
     }
 
-    fn split_sections<'a>(file_content: &'a str) -> Vec<&'a str> {
+    /// Splits the file into the preamble before the first section header, and each section.
+    fn split_sections(file_content: &str) -> (&str, Vec<&str>) {
         lazy_static! {
             static ref SECTION_REGEX: Regex = Regex::new(r"(?m)^\[(\w+)\]$").unwrap();
         }
         let mut sections = Vec::new();
-        let mut last_index = 0;
+        let mut last_index: Option<usize> = None;
 
         for cap in SECTION_REGEX.find_iter(file_content) {
             let start = cap.start();
-            if last_index != 0 {
+            if let Some(last_index) = last_index {
                 sections.push(&file_content[last_index..start]);
             }
-            last_index = start;
+            last_index = Some(start);
         }
 
-        // Add the last section
-        if last_index != 0 {
-            sections.push(&file_content[last_index..]);
+        match last_index {
+            Some(last_index) => {
+                // Add the last section
+                sections.push(&file_content[last_index..]);
+
+                let preamble_end = SECTION_REGEX
+                    .find(file_content)
+                    .map_or(0, |first| first.start());
+                (&file_content[..preamble_end], sections)
+            }
+            // no section headers, so keep everything as the preamble
+            None => (file_content, sections),
+        }
+    }
+}
+/// Converts the Gdextension back to a string representation, re-emitting the preamble before the sections.
+impl fmt::Display for Gdextension<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = vec![];
+
+        if let Some(preamble) = self.preamble {
+            parts.push(preamble.trim_end().to_string() + "\n");
+        }
+        if let Some(configuration) = &self.configuration {
+            parts.push(configuration.to_string());
+        }
+        if let Some(libraries) = &self.libraries {
+            parts.push(libraries.to_string());
+        }
+        if let Some(icons) = &self.icons {
+            parts.push(icons.to_string());
+        }
+        if let Some(dependencies) = &self.dependencies {
+            parts.push(dependencies.to_string());
         }
 
-        sections
+        write!(f, "{}", parts.join("\n"))
     }
 }
 
+#[test]
+fn test_gdextension_preamble() {
+    let content = r#"; Generated by hand, do not remove
+; second comment line
+
+[configuration]
+entry_symbol="gdext_rust_init"
+
+[icons]
+Menu="res://icons/gd/Control.svg"
+"#;
+
+    let gdextension = Gdextension::parse_from_str(content);
+    assert_eq!(
+        gdextension.preamble,
+        Some("; Generated by hand, do not remove\n; second comment line\n\n")
+    );
+    assert!(gdextension.configuration.is_some());
+    assert!(gdextension.icons.is_some());
+    assert!(gdextension.to_string().starts_with(
+        "; Generated by hand, do not remove\n; second comment line\n\n[configuration]"
+    ));
+
+    let headerless = Gdextension::parse_from_str("; only a comment\n");
+    assert_eq!(headerless.preamble, Some("; only a comment\n"));
+    assert_eq!(headerless.to_string(), "; only a comment\n");
+
+    let no_preamble = Gdextension::parse_from_str("[configuration]\nreloadable=true\n");
+    assert_eq!(no_preamble.preamble, None);
+    assert!(no_preamble.configuration.is_some());
+}
+
+#[test]
+fn test_gdextension_new_minimal() {
+    let gdextension = Gdextension::new_minimal(
+        "gdext_rust_init",
+        &[(
+            "linux.debug.x86_64",
+            "res://../rust/target/debug/librust.so",
+        )],
+    );
+    let content = gdextension.to_string();
+
+    assert!(content.starts_with(
+        "[configuration]\nentry_symbol=\"gdext_rust_init\"\ncompatibility_minimum=\"4.1\"\n"
+    ));
+    assert!(
+        content.contains(
+            "[libraries]\nlinux.debug.x86_64=\"res://../rust/target/debug/librust.so\"\n"
+        )
+    );
+
+    let parsed = Gdextension::parse_from_str(&content);
+    assert_eq!(
+        parsed.configuration.and_then(|c| c.entry_symbol),
+        Some("gdext_rust_init")
+    );
+    assert_eq!(parsed.libraries.map(|l| l.libraries.len()), Some(1));
+    assert!(parsed.icons.is_none());
+
+    // the Godot 4.0 spelling is read, but written back as Godot 4.1+ requires
+    let legacy = Gdextension::parse_from_str(
+        "[configuration]\ncompatibility.minimum=\"4.1\"\ncompatibility.maximum=\"4.3\"\n",
+    );
+    assert_eq!(
+        legacy.configuration.unwrap().to_string(),
+        "[configuration]\ncompatibility_minimum=\"4.1\"\ncompatibility_maximum=\"4.3\"\n"
+    );
+}
+
+#[test]
+fn test_gdextension_parse_from_file() {
+    let (entry_symbol, windows_library, icon_count) =
+        Gdextension::parse_from_file("./example/rust.gdextension", |gdextension| {
+            (
+                gdextension
+                    .configuration
+                    .as_ref()
+                    .and_then(|c| c.entry_symbol)
+                    .map(str::to_string),
+                gdextension
+                    .libraries
+                    .as_ref()
+                    .and_then(|l| l.libraries.get("windows.release.x86_64"))
+                    .map(|path| path.to_string()),
+                gdextension.icons.as_ref().map(|i| i.icons.len()),
+            )
+        })
+        .unwrap();
+
+    assert_eq!(entry_symbol.as_deref(), Some("gdext_rust_init"));
+    assert_eq!(
+        windows_library.as_deref(),
+        Some("res://../rust/target/release/rust.dll")
+    );
+    assert_eq!(icon_count, Some(2));
+
+    assert!(Gdextension::parse_from_file("./does/not/exist.gdextension", |_| ()).is_err());
+}
+
+#[test]
+fn test_gdextension_sections_are_sorted() {
+    let gdextension = Gdextension::new_minimal(
+        "gdext_rust_init",
+        &[
+            ("windows.debug.x86_64", "res://rust.dll"),
+            ("linux.debug.x86_64", "res://librust.so"),
+            ("macos.debug", "res://librust.dylib"),
+        ],
+    );
+
+    assert_eq!(
+        gdextension.libraries.unwrap().to_string(),
+        "[libraries]\nlinux.debug.x86_64=\"res://librust.so\"\nmacos.debug=\"res://librust.dylib\"\nwindows.debug.x86_64=\"res://rust.dll\"\n"
+    );
+}
+
 /// Configuration section of the `.gdextension` file
 ///
-/// It has the following format:
+/// It has the following format, where the compatibility keys may also be written `compatibility.minimum` and
+/// `compatibility.maximum` as in Godot 4.0, but are always written back with underscores as Godot 4.1+ requires:
 /// ```text
 /// [configuration]
 /// entry_symbol="my_entry_symbol"
-/// compatibility.minimum="4.0"
-/// compatibility.maximum="4.5"
+/// compatibility_minimum="4.0"
+/// compatibility_maximum="4.5"
 /// reloadable=false
 /// android.aar_plugin=false
 /// ```
@@ -122,8 +326,8 @@ impl ConfigurationSection<'_> {
     /// #                 let value = value.trim().trim_matches('"');
     /// #                 match key {
     /// #                     "entry_symbol" => config.entry_symbol = Some(value),
-    /// #                     "compatibility.minimum" => config.compatibility_minimum = Some(value),
-    /// #                     "compatibility.maximum" => config.compatibility_maximum = Some(value),
+    /// #                     "compatibility.minimum" | "compatibility_minimum" => config.compatibility_minimum = Some(value),
+    /// #                     "compatibility.maximum" | "compatibility_maximum" => config.compatibility_maximum = Some(value),
     /// #                     "reloadable" => config.reloadable = Some(value.eq_ignore_ascii_case("true")),
     /// #                     "android.aar_plugin" => config.android_aar_plugin = Some(value.eq_ignore_ascii_case("true")),
     /// #                     _ => {}
@@ -136,8 +340,8 @@ impl ConfigurationSection<'_> {
     ///
     /// let content = r#"[configuration]
     /// entry_symbol="gdext_rust_init"
-    /// compatibility.minimum="4.1"
-    /// compatibility.maximum="4.2"
+    /// compatibility_minimum="4.1"
+    /// compatibility_maximum="4.2"
     /// reloadable=true
     /// android.aar_plugin=false
     /// "#;
@@ -175,11 +379,15 @@ impl ConfigurationSection<'_> {
 
                 match key {
                     "entry_symbol" => config.entry_symbol = Some(value),
-                    "compatibility.minimum" => config.compatibility_minimum = Some(value),
-                    "compatibility.maximum" => config.compatibility_maximum = Some(value),
-                    "reloadable" => config.reloadable = Some(value.eq_ignore_ascii_case("true")),
+                    "compatibility.minimum" | "compatibility_minimum" => {
+                        config.compatibility_minimum = Some(value)
+                    }
+                    "compatibility.maximum" | "compatibility_maximum" => {
+                        config.compatibility_maximum = Some(value)
+                    }
+                    "reloadable" => config.reloadable = GdValue::parse(value).as_bool(),
                     "android.aar_plugin" => {
-                        config.android_aar_plugin = Some(value.eq_ignore_ascii_case("true"))
+                        config.android_aar_plugin = GdValue::parse(value).as_bool()
                     }
                     _ => {}
                 }
@@ -213,12 +421,12 @@ impl ConfigurationSection<'_> {
     /// #                 "".to_string()
     /// #             },
     /// #             if let Some(compatibility_minimum) = self.compatibility_minimum {
-    /// #                 format!("\ncompatibility.minimum=\"{}\"", compatibility_minimum)
+    /// #                 format!("\ncompatibility_minimum=\"{}\"", compatibility_minimum)
     /// #             } else {
     /// #                 "".to_string()
     /// #             },
     /// #             if let Some(compatibility_maximum) = self.compatibility_maximum {
-    /// #                 format!("\ncompatibility.maximum=\"{}\"", compatibility_maximum)
+    /// #                 format!("\ncompatibility_maximum=\"{}\"", compatibility_maximum)
     /// #             } else {
     /// #                 "".to_string()
     /// #             },
@@ -247,10 +455,10 @@ impl ConfigurationSection<'_> {
     /// let output = config.to_string();
     /// assert!(output.contains("[configuration]"));
     /// assert!(output.contains("entry_symbol=\"gdext_rust_init\""));
-    /// assert!(output.contains("compatibility.minimum=\"4.1\""));
+    /// assert!(output.contains("compatibility_minimum=\"4.1\""));
     /// assert!(output.contains("reloadable=true"));
     /// assert!(output.contains("android.aar_plugin=false"));
-    /// assert!(!output.contains("compatibility.maximum"));
+    /// assert!(!output.contains("compatibility_maximum"));
     /// ```
     pub fn to_string(&self) -> String {
         format!(
@@ -261,12 +469,12 @@ impl ConfigurationSection<'_> {
                 "".to_string()
             },
             if let Some(compatibility_minimum) = self.compatibility_minimum {
-                format!("\ncompatibility.minimum=\"{}\"", compatibility_minimum)
+                format!("\ncompatibility_minimum=\"{}\"", compatibility_minimum)
             } else {
                 "".to_string()
             },
             if let Some(compatibility_maximum) = self.compatibility_maximum {
-                format!("\ncompatibility.maximum=\"{}\"", compatibility_maximum)
+                format!("\ncompatibility_maximum=\"{}\"", compatibility_maximum)
             } else {
                 "".to_string()
             },
@@ -285,6 +493,75 @@ impl ConfigurationSection<'_> {
             },
         )
     }
+
+    /// Compares `compatibility_minimum` against the Godot version the generated code targets,
+    /// returning a warning if the target is below the minimum, or if either version can't be parsed.
+    pub(crate) fn compatibility_warning(&self, target_version: &str) -> Option<String> {
+        let minimum = self.compatibility_minimum?;
+
+        match (
+            parse_godot_version(minimum),
+            parse_godot_version(target_version),
+        ) {
+            (Some(min), Some(target)) if target >= min => None,
+            (Some(_), Some(_)) => Some(format!(
+                "The target Godot version {} is below the .gdextension compatibility_minimum {}, generated code may not match the Godot API",
+                target_version, minimum
+            )),
+            _ => Some(format!(
+                "Unable to compare .gdextension compatibility_minimum {:?} with the target Godot version {:?}",
+                minimum, target_version
+            )),
+        }
+    }
+}
+#[test]
+fn test_configuration_section_compatibility_warning() {
+    let config = ConfigurationSection::parse(
+        "[configuration]\nentry_symbol = \"gdext_rust_init\"\ncompatibility_minimum = 4.5\n",
+    )
+    .unwrap();
+
+    assert_eq!(config.compatibility_warning("4.5"), None);
+    assert_eq!(config.compatibility_warning("4.5.0"), None);
+    assert_eq!(config.compatibility_warning("4.6"), None);
+    assert_eq!(
+        config.compatibility_warning("4.4.1"),
+        Some("The target Godot version 4.4.1 is below the .gdextension compatibility_minimum 4.5, generated code may not match the Godot API".to_string())
+    );
+    assert!(config.compatibility_warning("latest").is_some());
+
+    let without_minimum =
+        ConfigurationSection::parse("[configuration]\nentry_symbol=\"gdext_rust_init\"\n").unwrap();
+    assert_eq!(without_minimum.compatibility_warning("4.0"), None);
+}
+
+/// Parses a Godot version like `4.1` or `4.1.2` into its major, minor and patch numbers.
+pub(crate) fn parse_godot_version(version: &str) -> Option<[u32; 3]> {
+    let mut parts = [0; 3];
+    let mut numbers = version.trim().trim_matches('"').split('.');
+
+    for part in parts.iter_mut() {
+        match numbers.next() {
+            Some(n) => *part = n.parse().ok()?,
+            None => break,
+        }
+    }
+
+    if numbers.next().is_some() {
+        return None;
+    }
+
+    Some(parts)
+}
+#[test]
+fn test_parse_godot_version() {
+    assert_eq!(parse_godot_version("4.1"), Some([4, 1, 0]));
+    assert_eq!(parse_godot_version("\"4.2.1\""), Some([4, 2, 1]));
+    assert_eq!(parse_godot_version("4"), Some([4, 0, 0]));
+    assert_eq!(parse_godot_version("4.x"), None);
+    assert_eq!(parse_godot_version("4.1.2.3"), None);
+    assert_eq!(parse_godot_version(""), None);
 }
 
 /// Libraries section of the `.gdextension` file
@@ -305,22 +582,22 @@ pub struct LibrariesSection<'a> {
     /// becomes:
     ///
     /// ```"windows.debug.x86_64"``` => ```"res://path/to/library.dll"```
-    pub libraries: HashMap<&'a str, &'a str>,
+    pub libraries: BTreeMap<&'a str, &'a str>,
 }
 
 impl LibrariesSection<'_> {
     /// # Example
     /// ```
-    /// # use std::collections::HashMap;
+    /// # use std::collections::BTreeMap;
     /// # pub struct LibrariesSection<'a> {
-    /// #     pub libraries: std::collections::HashMap<&'a str, &'a str>,
+    /// #     pub libraries: std::collections::BTreeMap<&'a str, &'a str>,
     /// # }
     /// # impl LibrariesSection<'_> {
     /// #     pub fn parse<'a>(content: &'a str) -> Option<LibrariesSection<'a>> {
     /// #         if !content.trim().starts_with("[libraries]") {
     /// #             return None;
     /// #         }
-    /// #         let mut libraries = HashMap::new();
+    /// #         let mut libraries = BTreeMap::new();
     /// #         for line in content.lines() {
     /// #             let line = line.trim();
     /// #             if line.is_empty() || line.starts_with('#') {
@@ -351,7 +628,7 @@ impl LibrariesSection<'_> {
             return None;
         }
 
-        let mut libraries = HashMap::new();
+        let mut libraries = BTreeMap::new();
 
         for line in content.lines() {
             let line = line.trim();
@@ -373,9 +650,9 @@ impl LibrariesSection<'_> {
     ///
     /// # Example
     /// ```
-    /// # use std::collections::HashMap;
+    /// # use std::collections::BTreeMap;
     /// # pub struct LibrariesSection<'a> {
-    /// #     pub libraries: std::collections::HashMap<&'a str, &'a str>,
+    /// #     pub libraries: std::collections::BTreeMap<&'a str, &'a str>,
     /// # }
     /// # impl LibrariesSection<'_> {
     /// #     pub fn to_string(&self) -> String {
@@ -386,7 +663,7 @@ impl LibrariesSection<'_> {
     /// #         result
     /// #     }
     /// # }
-    /// let mut libraries_map = HashMap::new();
+    /// let mut libraries_map = BTreeMap::new();
     /// libraries_map.insert("windows.debug.x86_64", "res://path/to/library.dll");
     /// libraries_map.insert("linux.release.x86_64", "res://path/to/library.so");
     /// let libraries = LibrariesSection { libraries: libraries_map };
@@ -423,7 +700,7 @@ pub struct IconsSection<'a> {
     /// becomes:
     ///
     /// ```"MyClass"``` => ```"res://path/to/icon.png"```
-    pub icons: HashMap<&'a str, &'a str>,
+    pub icons: BTreeMap<&'a str, &'a str>,
 }
 
 impl IconsSection<'_> {
@@ -434,17 +711,17 @@ impl IconsSection<'_> {
     ///
     /// # Example
     /// ```
-    /// # use std::collections::HashMap;
+    /// # use std::collections::BTreeMap;
     /// # struct IconsSection<'a> {
     /// #     pub name: &'a str,
-    /// #     pub icons: HashMap<&'a str, &'a str>,
+    /// #     pub icons: BTreeMap<&'a str, &'a str>,
     /// # }
     /// # impl IconsSection<'_> {
     /// #     pub fn parse<'a>(content: &'a str) -> Option<IconsSection<'a>> {
     /// #         if !content.trim().starts_with("[icons]") {
     /// #             return None;
     /// #         }
-    /// #         let mut icons = HashMap::new();
+    /// #         let mut icons = BTreeMap::new();
     /// #         for line in content.lines() {
     /// #             let line = line.trim();
     /// #             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
@@ -478,7 +755,7 @@ impl IconsSection<'_> {
             return None;
         }
 
-        let mut icons = HashMap::new();
+        let mut icons = BTreeMap::new();
 
         for line in content.lines() {
             let line = line.trim();
@@ -506,10 +783,10 @@ impl IconsSection<'_> {
     ///
     /// # Example
     /// ```
-    /// # use std::collections::HashMap;
+    /// # use std::collections::BTreeMap;
     /// # struct IconsSection<'a> {
     /// #     pub name: &'a str,
-    /// #     pub icons: HashMap<&'a str, &'a str>,
+    /// #     pub icons: BTreeMap<&'a str, &'a str>,
     /// # }
     /// # impl IconsSection<'_> {
     /// #     pub fn to_string(&self) -> String {
@@ -521,7 +798,7 @@ impl IconsSection<'_> {
     /// #     }
     /// # }
     ///
-    /// let mut icons_map = HashMap::new();
+    /// let mut icons_map = BTreeMap::new();
     /// icons_map.insert("MyClass", "res://path/to/icon.png");
     /// icons_map.insert("MyOtherClass", "res://path/to/other_icon.png");
     /// let icons_section = IconsSection { name: "icons", icons: icons_map };
@@ -530,6 +807,8 @@ impl IconsSection<'_> {
     /// assert!(output.contains("[icons]"));
     /// assert!(output.contains("MyClass=\"res://path/to/icon.png\""));
     /// assert!(output.contains("MyOtherClass=\"res://path/to/other_icon.png\""));
+    /// // icons are written sorted by class name
+    /// assert!(output.find("MyClass=").unwrap() < output.find("MyOtherClass=").unwrap());
     /// ```
     pub fn to_string(&self) -> String {
         let mut result = String::from("[icons]\n");
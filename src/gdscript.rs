@@ -0,0 +1,134 @@
+use crate::{
+    input_actions::action_names,
+    projectgodot::ProjectGodot,
+    scenes::prefix_leading_digit,
+    utils::{GENERATED_MARKER, pascal_to_snake_case},
+};
+
+/// Generates the content of a `.gd` file with a const for each scene and action, named as their Rust consts are.
+///
+/// An action whose const would have the same name as a scene's is skipped with a warning, as GDScript consts share one namespace.
+pub fn generate_gdscript_consts(
+    scenes_and_paths: &[(String, String)],
+    godot_project: Option<&ProjectGodot>,
+    const_prefix: &str,
+) -> String {
+    let scene_consts = scenes_and_paths
+        .iter()
+        .map(|(name, path)| {
+            (
+                prefix_leading_digit(to_gdscript_const_name(name, const_prefix)),
+                path.as_str(),
+            )
+        })
+        .collect::<Vec<(String, &str)>>();
+
+    let action_consts = godot_project
+        .map(action_names)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|action| (to_gdscript_const_name(action, const_prefix), action))
+        .filter(|(const_name, action)| {
+            let collides = scene_consts.iter().any(|(scene, _)| scene == const_name);
+            if collides {
+                println!(
+                    "cargo::warning=Action {} has the same GDScript const name as a scene, {}, skipping it",
+                    action, const_name
+                );
+            }
+            !collides
+        })
+        .collect::<Vec<(String, &str)>>();
+
+    format_gdscript_consts(&scene_consts, &action_consts)
+}
+
+fn to_gdscript_const_name(name: &str, const_prefix: &str) -> String {
+    format!(
+        "{}{}",
+        const_prefix,
+        pascal_to_snake_case(name).to_uppercase()
+    )
+}
+
+/// Formats `(const name, path)` scenes as `String` consts and `(const name, action)` actions as `StringName` consts.
+fn format_gdscript_consts(
+    scene_consts: &[(String, &str)],
+    action_consts: &[(String, &str)],
+) -> String {
+    let mut content = format!(
+        "{}, do not edit by hand\n",
+        GENERATED_MARKER.replacen("//", "#", 1)
+    );
+
+    if !scene_consts.is_empty() {
+        content.push_str("\n# Scenes\n");
+        for (const_name, path) in scene_consts {
+            content.push_str(&format!("const {} := \"{}\"\n", const_name, path));
+        }
+    }
+
+    if !action_consts.is_empty() {
+        content.push_str("\n# Actions\n");
+        for (const_name, action) in action_consts {
+            content.push_str(&format!("const {} := &\"{}\"\n", const_name, action));
+        }
+    }
+
+    content
+}
+#[test]
+fn test_format_gdscript_consts() {
+    let scenes_and_paths = vec![
+        ("Main".to_string(), "res://scenes/Main.tscn".to_string()),
+        (
+            "LevelOne".to_string(),
+            "res://scenes/LevelOne.tscn".to_string(),
+        ),
+    ];
+    let project = ProjectGodot::parse_from_str(include_str!("../example/project.godot"));
+
+    assert_eq!(
+        generate_gdscript_consts(&scenes_and_paths, Some(&project), ""),
+        r#"# @generated by zgrcg, do not edit by hand
+
+# Scenes
+const MAIN := "res://scenes/Main.tscn"
+const LEVEL_ONE := "res://scenes/LevelOne.tscn"
+
+# Actions
+const FIRE := &"Fire"
+const JUMP := &"jump"
+const MOVE_DOWN := &"move_down"
+const MOVE_LEFT := &"move_left"
+const MOVE_RIGHT := &"move_right"
+const MOVE_UP := &"move_up"
+"#
+    );
+}
+#[test]
+fn test_generate_gdscript_consts_collision() {
+    let scenes_and_paths = vec![("Jump".to_string(), "res://scenes/Jump.tscn".to_string())];
+    let project = ProjectGodot::parse_from_str(include_str!("../example/project.godot"));
+
+    let content = generate_gdscript_consts(&scenes_and_paths, Some(&project), "GAME_");
+
+    assert!(content.contains("const GAME_JUMP := \"res://scenes/Jump.tscn\"\n"));
+    assert!(!content.contains("&\"jump\""));
+    assert!(content.contains("const GAME_FIRE := &\"Fire\"\n"));
+
+    // a scene starting with a digit is prefixed as its Rust const is
+    let scenes_and_paths = vec![(
+        "2dLevel".to_string(),
+        "res://2d_level/2dLevel.tscn".to_string(),
+    )];
+    assert!(
+        generate_gdscript_consts(&scenes_and_paths, None, "")
+            .contains("const _2D_LEVEL := \"res://2d_level/2dLevel.tscn\"\n")
+    );
+
+    assert_eq!(
+        generate_gdscript_consts(&[], None, ""),
+        "# @generated by zgrcg, do not edit by hand\n"
+    );
+}
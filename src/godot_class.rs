@@ -0,0 +1,447 @@
+// Allow dead code because to better represent the structure of the file, even if some fields are not used.
+#![allow(dead_code)]
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use crate::{
+    mod_file::GeneratedModule,
+    utils::{find_source_files, format_lint_allows, write_generated_file},
+};
+
+const MOD_CLASS_REGISTRY: &str = "class_registry";
+
+/// A struct deriving `GodotClass` found in a Rust source file, e.g:
+/// ```text
+/// #[derive(GodotClass)]
+/// #[class(init, base=Control)]
+/// pub struct Menu {
+///     base: Base<Control>,
+/// }
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct GodotClass<'a> {
+    pub name: &'a str,
+    /// The `base=` argument of the `#[class(...)]` attribute, if any.
+    pub base: Option<&'a str>,
+    /// Whether the `#[class(...)]` attribute contains `init`.
+    pub init: bool,
+}
+
+impl GodotClass<'_> {
+    /// Finds every struct deriving `GodotClass` in the given source, in order of declaration.
+    ///
+    /// Attributes may span multiple lines, and may be interleaved with comments.
+    pub fn parse_from_str<'a>(content: &'a str) -> Vec<GodotClass<'a>> {
+        let mut classes = Vec::new();
+        let mut attributes: Vec<&str> = Vec::new();
+        let mut rest = content;
+
+        loop {
+            rest = skip_whitespace_and_comments(rest);
+            if rest.is_empty() {
+                break;
+            }
+
+            if rest.starts_with("#[") {
+                let (attribute, after) = split_attribute(rest);
+                attributes.push(attribute);
+                rest = after;
+                continue;
+            }
+
+            if let Some(name) = parse_struct_name(rest)
+                && attributes.iter().any(|a| is_godot_class_derive(a))
+            {
+                let args = attributes
+                    .iter()
+                    .filter_map(|a| parse_class_attribute(a))
+                    .flatten()
+                    .collect::<HashMap<&str, Option<&str>>>();
+
+                classes.push(GodotClass {
+                    name,
+                    base: args.get("base").copied().flatten(),
+                    init: args.contains_key("init"),
+                });
+            }
+
+            attributes.clear();
+            rest = rest.split_once('\n').map_or("", |(_, r)| r);
+        }
+
+        classes
+    }
+}
+#[test]
+fn test_godot_class_parse_from_str() {
+    assert_eq!(
+        GodotClass::parse_from_str(include_str!("../example/src/Menu.rs")),
+        vec![GodotClass {
+            name: "Menu",
+            base: Some("Control"),
+            init: true,
+        }]
+    );
+    assert_eq!(
+        GodotClass::parse_from_str(include_str!("../example/src/GameRecorder.rs")),
+        vec![GodotClass {
+            name: "GameRecorder",
+            base: Some("Node"),
+            init: true,
+        }]
+    );
+}
+#[test]
+fn test_godot_class_parse_from_str_multiline() {
+    let content = r#"
+#[derive(GodotClass, Debug)]
+#[class(
+    base = Node2D,
+    tool,
+)]
+// zgrcg:icon="res://icons/gd/Node2D.svg"
+pub(crate) struct Spawner {
+    base: Base<Node2D>,
+}
+
+#[derive(Debug)]
+struct NotAClass;
+
+#[derive(godot::prelude::GodotClass)]
+struct Plain {}
+"#;
+
+    assert_eq!(
+        GodotClass::parse_from_str(content),
+        vec![
+            GodotClass {
+                name: "Spawner",
+                base: Some("Node2D"),
+                init: false,
+            },
+            GodotClass {
+                name: "Plain",
+                base: None,
+                init: false,
+            }
+        ]
+    );
+}
+
+/// Skips leading whitespace and `//` comments.
+fn skip_whitespace_and_comments(mut s: &str) -> &str {
+    loop {
+        s = s.trim_start();
+        if s.starts_with("//") {
+            s = s.split_once('\n').map_or("", |(_, r)| r);
+        } else {
+            return s;
+        }
+    }
+}
+
+/// Splits an attribute starting with `#[` from the rest of the source, returning its inner text.
+///
+/// e.g. `#[class(init)]\npub struct Menu` -> `("class(init)", "\npub struct Menu")`
+fn split_attribute(s: &str) -> (&str, &str) {
+    let mut depth = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => depth += 1,
+            ']' if !in_quotes => {
+                depth -= 1;
+                if depth == 0 {
+                    return (&s[2..i], &s[i + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (&s[2..], "")
+}
+#[test]
+fn test_split_attribute() {
+    assert_eq!(
+        split_attribute("#[class(init,base=Control)] // comment\npub struct Menu"),
+        ("class(init,base=Control)", " // comment\npub struct Menu")
+    );
+    assert_eq!(
+        split_attribute("#[doc = \"]\"]\nstruct A;"),
+        ("doc = \"]\"", "\nstruct A;")
+    );
+}
+
+/// Returns the name of the struct declared at the start of `s`, if any.
+fn parse_struct_name(s: &str) -> Option<&str> {
+    let mut s = s;
+    if let Some(rest) = s.strip_prefix("pub") {
+        s = rest.trim_start();
+        if s.starts_with('(') {
+            s = s.split_once(')')?.1.trim_start();
+        }
+    }
+
+    let rest = s.strip_prefix("struct")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+
+    if end == 0 { None } else { Some(&rest[..end]) }
+}
+#[test]
+fn test_parse_struct_name() {
+    assert_eq!(parse_struct_name("pub struct Menu {"), Some("Menu"));
+    assert_eq!(parse_struct_name("pub(crate) struct Menu;"), Some("Menu"));
+    assert_eq!(parse_struct_name("struct Menu<T> {"), Some("Menu"));
+    assert_eq!(parse_struct_name("pub fn structure() {"), None);
+    assert_eq!(parse_struct_name("pub enum Menu {"), None);
+}
+
+/// Whether the attribute is a `derive` including `GodotClass`.
+fn is_godot_class_derive(attribute: &str) -> bool {
+    attribute
+        .strip_prefix("derive")
+        .map(|a| a.trim_start())
+        .and_then(|a| a.strip_prefix('('))
+        .and_then(|a| a.trim_end().strip_suffix(')'))
+        .is_some_and(|derives| {
+            derives
+                .split(',')
+                .any(|d| d.trim().rsplit("::").next() == Some("GodotClass"))
+        })
+}
+#[test]
+fn test_is_godot_class_derive() {
+    assert!(is_godot_class_derive("derive(GodotClass)"));
+    assert!(is_godot_class_derive(
+        "derive(Debug, godot::prelude::GodotClass)"
+    ));
+    assert!(!is_godot_class_derive("derive(Debug)"));
+    assert!(!is_godot_class_derive("class(init)"));
+}
+
+/// Parses the arguments of a `#[class(...)]` attribute into a map of keys to optional values, in any order and spanning lines.
+///
+/// e.g. `class(init, base=Control)` -> `{"init": None, "base": Some("Control")}`
+fn parse_class_attribute(attribute: &str) -> Option<HashMap<&str, Option<&str>>> {
+    let args = attribute
+        .strip_prefix("class")?
+        .trim_start()
+        .strip_prefix('(')?
+        .trim_end()
+        .strip_suffix(')')?;
+
+    Some(
+        split_class_arguments(args)
+            .into_iter()
+            .map(|arg| arg.trim())
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| match arg.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim())),
+                None => (arg, None),
+            })
+            .collect(),
+    )
+}
+#[test]
+fn test_parse_class_attribute() {
+    let expected = HashMap::from([("init", None), ("base", Some("Control"))]);
+    assert_eq!(
+        parse_class_attribute("class(init,base=Control)"),
+        Some(expected.clone())
+    );
+    assert_eq!(
+        parse_class_attribute("class(base = Control, init)"),
+        Some(expected.clone())
+    );
+    assert_eq!(
+        parse_class_attribute("class(\n    base = Control,\n    init,\n)"),
+        Some(expected)
+    );
+    assert_eq!(
+        parse_class_attribute("class(base=Node, rename = \"My, Node\")"),
+        Some(HashMap::from([
+            ("base", Some("Node")),
+            ("rename", Some("\"My, Node\"")),
+        ]))
+    );
+    assert_eq!(parse_class_attribute("derive(GodotClass)"), None);
+}
+#[test]
+fn test_godot_class_parse_from_str_argument_order() {
+    let menu = include_str!("../example/src/Menu.rs");
+    let expected = vec![GodotClass {
+        name: "Menu",
+        base: Some("Control"),
+        init: true,
+    }];
+
+    for class_attribute in [
+        "#[class(base=Control,init)]",
+        "#[class( base = Control , init )]",
+        "#[class(\n    base = Control,\n    init,\n)]",
+    ] {
+        let content = menu.replace("#[class(init,base=Control)]", class_attribute);
+        assert_eq!(GodotClass::parse_from_str(&content), expected);
+    }
+}
+
+/// Splits the arguments of an attribute on the commas outside of strings and nested brackets.
+fn split_class_arguments(args: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut last_split = 0;
+
+    for (i, c) in args.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '(' | '[' if !in_quotes => depth += 1,
+            ')' | ']' if !in_quotes => depth -= 1,
+            ',' if !in_quotes && depth == 0 => {
+                parts.push(&args[last_split..i]);
+                last_split = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&args[last_split..]);
+
+    parts
+}
+
+/// Scans the Rust source files for classes deriving `GodotClass` and generates a registry of them.
+pub fn generate_class_registry(
+    output_dir: &str,
+    source_paths: &[&str],
+    ignore_source_globs: &[String],
+    strict_lints: bool,
+) -> io::Result<Vec<GeneratedModule>> {
+    let source_files = source_paths
+        .iter()
+        .flat_map(|source_path| find_source_files(Path::new(source_path), ignore_source_globs))
+        .collect::<Vec<_>>();
+    let mut sources = Vec::new();
+    for file_path in source_files {
+        match fs::read_to_string(&file_path) {
+            Ok(content) => sources.push(content),
+            Err(e) => println!(
+                "cargo::warning=Failed to read file {}: {}, skipping",
+                file_path.display(),
+                e
+            ),
+        }
+    }
+
+    let mut classes = sources
+        .iter()
+        .flat_map(|content| GodotClass::parse_from_str(content))
+        .collect::<Vec<GodotClass>>();
+    classes.sort_by(|a, b| a.name.cmp(b.name));
+
+    let registry_path = Path::new(output_dir).join(format!("{}.rs", MOD_CLASS_REGISTRY));
+
+    write_generated_file(
+        &registry_path,
+        &format_classes_to_registry(&classes, strict_lints),
+    )?;
+
+    Ok(vec![GeneratedModule {
+        name: MOD_CLASS_REGISTRY.to_string(),
+        path: registry_path,
+    }])
+}
+
+fn format_classes_to_registry(classes: &[GodotClass], strict_lints: bool) -> String {
+    format!(
+        r#"{}
+/// A struct deriving `GodotClass` in the source files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisteredClass {{
+    pub name: &'static str,
+    pub base: Option<&'static str>,
+    pub init: bool,
+}}
+
+pub const CLASSES: &[RegisteredClass] = &[
+{}];"#,
+        format_lint_allows(strict_lints, false),
+        classes
+            .iter()
+            .map(|c| format!("{}\n", format_class_to_registry_entry(c)))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_classes_to_registry() {
+    let expected = r#"#![allow(dead_code)]
+
+/// A struct deriving `GodotClass` in the source files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisteredClass {
+    pub name: &'static str,
+    pub base: Option<&'static str>,
+    pub init: bool,
+}
+
+pub const CLASSES: &[RegisteredClass] = &[
+    RegisteredClass { name: "GameRecorder", base: Some("Node"), init: true },
+    RegisteredClass { name: "Menu", base: Some("Control"), init: true },
+];"#;
+
+    assert_eq!(
+        format_classes_to_registry(
+            &[
+                GodotClass {
+                    name: "GameRecorder",
+                    base: Some("Node"),
+                    init: true,
+                },
+                GodotClass {
+                    name: "Menu",
+                    base: Some("Control"),
+                    init: true,
+                },
+            ],
+            false
+        ),
+        expected
+    );
+}
+
+fn format_class_to_registry_entry(class: &GodotClass) -> String {
+    format!(
+        "    RegisteredClass {{ name: \"{}\", base: {}, init: {} }},",
+        class.name,
+        class
+            .base
+            .map_or("None".to_string(), |b| format!("Some(\"{}\")", b)),
+        class.init
+    )
+}
+#[test]
+fn test_format_class_to_registry_entry() {
+    assert_eq!(
+        format_class_to_registry_entry(&GodotClass {
+            name: "Plain",
+            base: None,
+            init: false,
+        }),
+        "    RegisteredClass { name: \"Plain\", base: None, init: false },"
+    );
+}
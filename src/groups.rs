@@ -0,0 +1,159 @@
+use std::{io, path::Path};
+
+use crate::{
+    mod_file::GeneratedModule,
+    projectgodot::ProjectGodot,
+    utils::{format_item_allow, format_lint_allows, to_const_name, write_generated_file},
+};
+
+const MOD_GROUPS: &str = "group_consts";
+
+/// Generates a const for the name of each group in the `[global_group]` section of `project.godot`,
+/// optionally with a `StringName` accessor for each.
+pub fn generate_group_consts(
+    output_dir: &str,
+    godot_project: &ProjectGodot,
+    string_names: bool,
+    strict_lints: bool,
+) -> io::Result<Vec<GeneratedModule>> {
+    let Some(global_group) = godot_project
+        .global_group
+        .as_ref()
+        .filter(|global_group| !global_group.groups.is_empty())
+    else {
+        println!(
+            "cargo::warning=No global groups found in the project settings, skipping group_consts.rs generation"
+        );
+        return Ok(vec![]);
+    };
+
+    let mut const_names: Vec<String> = vec![];
+    let mut groups: Vec<(String, &str, &str)> = vec![];
+    // groups are sorted by name, so the first of any colliding groups is kept
+    for (group, description) in &global_group.groups {
+        let const_name = to_const_name(group);
+        if const_names.contains(&const_name) {
+            println!(
+                "cargo::warning=Global group '{}' has the same const name {} as another group, skipping",
+                group, const_name
+            );
+            continue;
+        }
+        const_names.push(const_name.clone());
+        groups.push((const_name, group, description));
+    }
+
+    let groups_path = Path::new(output_dir).join(format!("{}.rs", MOD_GROUPS));
+
+    write_generated_file(
+        &groups_path,
+        &format_groups_to_consts(&groups, string_names, strict_lints),
+    )?;
+
+    Ok(vec![GeneratedModule {
+        name: MOD_GROUPS.to_string(),
+        path: groups_path,
+    }])
+}
+
+/// Formats `(const name, group, description)` triples into the content of the group consts file.
+fn format_groups_to_consts(
+    groups: &[(String, &str, &str)],
+    string_names: bool,
+    strict_lints: bool,
+) -> String {
+    let header = if string_names {
+        format!(
+            "{}use godot::builtin::StringName;\n\n",
+            format_lint_allows(strict_lints, true)
+        )
+    } else {
+        format!("{}\n", format_lint_allows(strict_lints, false))
+    };
+
+    let consts = groups
+        .iter()
+        .map(|(const_name, group, description)| {
+            let doc_comment = if description.trim().is_empty() {
+                format!("/// The `{}` group", group)
+            } else {
+                format!("/// {}", description.replace('\n', " "))
+            };
+
+            let mut lines = format!(
+                "{}\npub const {}_GROUP: &str = {:?};\n",
+                doc_comment, const_name, group
+            );
+            if string_names {
+                lines.push_str(&format!(
+                    "{}\n{}pub fn {}_GROUP_NAME() -> StringName {{ StringName::from({:?}) }}\n",
+                    doc_comment,
+                    format_item_allow(strict_lints, "non_snake_case"),
+                    const_name,
+                    group
+                ));
+            }
+            lines
+        })
+        .collect::<String>();
+
+    format!("{}{}", header, consts)
+}
+#[test]
+fn test_format_groups_to_consts() {
+    let groups = [
+        ("ENEMIES".to_string(), "enemies", "Hostile units"),
+        ("PLAYER_ALLIES".to_string(), "player allies", ""),
+    ];
+
+    assert_eq!(
+        format_groups_to_consts(&groups, false, false),
+        r#"#![allow(dead_code)]
+
+/// Hostile units
+pub const ENEMIES_GROUP: &str = "enemies";
+/// The `player allies` group
+pub const PLAYER_ALLIES_GROUP: &str = "player allies";
+"#
+    );
+    assert_eq!(
+        format_groups_to_consts(&groups[..1], true, false),
+        r#"#![allow(dead_code)]
+#![allow(non_snake_case)]
+use godot::builtin::StringName;
+
+/// Hostile units
+pub const ENEMIES_GROUP: &str = "enemies";
+/// Hostile units
+pub fn ENEMIES_GROUP_NAME() -> StringName { StringName::from("enemies") }
+"#
+    );
+    assert_eq!(
+        format_groups_to_consts(&groups[..1], true, true),
+        r#"use godot::builtin::StringName;
+
+/// Hostile units
+pub const ENEMIES_GROUP: &str = "enemies";
+/// Hostile units
+#[allow(non_snake_case)]
+pub fn ENEMIES_GROUP_NAME() -> StringName { StringName::from("enemies") }
+"#
+    );
+    // quotes and backslashes are escaped to keep the generated file valid
+    assert_eq!(
+        format_groups_to_consts(
+            &[("QUOTED".to_string(), "say \"hi\" \\o/", "")],
+            true,
+            false
+        ),
+        r#"#![allow(dead_code)]
+#![allow(non_snake_case)]
+use godot::builtin::StringName;
+
+/// The `say "hi" \o/` group
+pub const QUOTED_GROUP: &str = "say \"hi\" \\o/";
+/// The `say "hi" \o/` group
+pub fn QUOTED_GROUP_NAME() -> StringName { StringName::from("say \"hi\" \\o/") }
+"#
+    );
+}
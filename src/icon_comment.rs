@@ -1,21 +1,31 @@
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::{collections::HashMap, fs, io::Write, path::Path};
-
-use crate::utils::to_resource_path;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::GeneratorError,
+    godot_class::GodotClass,
+    utils::{find_source_files, to_resource_path, write_atomically},
+};
 
 // Scan Rust source files for icon comments and download/apply icons to the .gdextension file
 //
 // NOTE: does not add to the mod.rs
+//
+// Returns the path of the .gdextension file if it was updated, or an error if it couldn't be read or written
 pub fn apply_icons_from_comments(
-    source_code: &str,
+    source_paths: &[&str],
+    ignore_source_globs: &[String],
     resource_path: &str,
     gdextension_path: &str,
-    icon_sources: &std::collections::HashMap<String, String>,
-) {
+    icon_sources: &BTreeMap<String, String>,
+    default_icons: &BTreeMap<String, String>,
+) -> Result<Option<PathBuf>, GeneratorError> {
     lazy_static! {
-        static ref ICON_COMMENT_REGEX: Regex = Regex::new(r#"zgrcg:icon="(.*?)""#).unwrap();
-        static ref ICON_CLASS_REGEX: Regex = Regex::new(r#"pub struct (\w+)"#).unwrap();
         static ref ICONS_SECTION_REGEX: Regex = Regex::new(
             r#"(?m)(?:; zgrcg - autogenerated icons from comments\r?\n)?\[icons\]\r?\n^(?:.+\r?\n)*"#
         )
@@ -23,62 +33,24 @@ pub fn apply_icons_from_comments(
     };
     if icon_sources.is_empty() {
         println!("cargo::warning=No icon sources configured, skipping icon comment parsing");
-        return;
+        return Ok(None);
     }
 
     let mut icons_to_apply = HashMap::<String, String>::new();
-    let source_path = Path::new(source_code);
+    let class_icons = scan_class_icons(source_paths, ignore_source_globs, default_icons);
 
-    if !source_path.exists() {
+    for prefix in unused_icon_sources(icon_sources, &class_icons) {
         println!(
-            "cargo::warning=Source path {} does not exist, skipping icon comment parsing",
-            source_code
+            "cargo::warning=Icon source {} is not used by any icon comment, check the prefix for typos or remove it",
+            prefix
         );
-        return;
     }
 
-    // recursively find all .rs files in source_path
-    let source_files = walkdir::WalkDir::new(source_path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map_or(false, |ext| ext == "rs"))
-        .collect::<Vec<walkdir::DirEntry>>();
-
-    for entry in source_files {
-        let file_path = entry.path();
-
-        let file_content = std::fs::read_to_string(file_path).unwrap_or_else(|_| {
-            println!(
-                "cargo::warning=Failed to read file {}, skipping",
-                file_path.display()
-            );
-            String::new()
-        });
-
-        let icon_comment = ICON_COMMENT_REGEX.captures(&file_content);
-        let icon_class = ICON_CLASS_REGEX.captures(&file_content);
-
-        let icon_comment = match icon_comment {
-            Some(caps) => caps.get(1).map_or("", |m| m.as_str()),
-            None => {
-                // println!("cargo::warning=No icon comment found in {}, skipping", file_path.display());
-                continue;
-            }
-        };
-
-        let icon_class = match icon_class {
-            Some(caps) => caps.get(1).map_or("", |m| m.as_str()),
-            None => {
-                // println!(
-                //     "cargo::warning=No struct definition found in {}, skipping",
-                //     file_path.display()
-                // );
-                continue;
-            }
-        };
+    for (icon_class, icon_comment) in class_icons {
+        let (icon_class, icon_comment) = (icon_class.as_str(), icon_comment.as_str());
 
         // get the icon name from the resource path, eg: for res://icons/fa/coffee.svg, icon name is coffee.svg
-        let icon_name = icon_comment.split('/').last().unwrap_or("");
+        let icon_name = icon_comment.split('/').next_back().unwrap_or("");
 
         // check if the icon already exists in the resource path
         let icon_path = icon_comment.replace("res:/", resource_path);
@@ -115,10 +87,10 @@ pub fn apply_icons_from_comments(
                 );
 
                 let response = reqwest::blocking::get(&download_url);
-                if response.is_err() {
+                if let Err(e) = &response {
                     println!(
-                        "cargo::warning=Failed to download icon {} from {}, skipping",
-                        icon_name, download_url
+                        "cargo::warning=Failed to download icon {} from {}: {}, skipping",
+                        icon_name, download_url, e
                     );
                     continue;
                 }
@@ -156,23 +128,16 @@ pub fn apply_icons_from_comments(
                     });
                 }
 
-                let mut file = fs::File::create(&icon_path).unwrap();
-
-                match file.write_all(&bytes) {
-                    Ok(_) => {
-                        println!(
-                            "cargo::warning=Icon {} downloaded and saved to {}",
-                            icon_name, icon_path
-                        );
-                    }
-                    Err(e) => {
-                        println!(
-                            "cargo::warning=Failed to write icon {} to {}, error: {}, skipping",
-                            icon_name, icon_path, e
-                        );
-                        continue;
-                    }
-                }
+                fs::write(&icon_path, &bytes).map_err(|e| {
+                    io::Error::new(
+                        e.kind(),
+                        format!("Failed to write icon {} to {}: {}", icon_name, icon_path, e),
+                    )
+                })?;
+                println!(
+                    "cargo::warning=Icon {} downloaded and saved to {}",
+                    icon_name, icon_path
+                );
             } else {
                 // assume it's a local path, find the file and copy it to the resource path
                 let local_icon_path = Path::new(icon_source_url)
@@ -242,26 +207,27 @@ pub fn apply_icons_from_comments(
                 "cargo::warning=Failed to backup .gdextension file {}, skipping icon application",
                 gdextension_path
             );
-            return;
+            return Ok(None);
         }
     } else if !Path::new(gdextension_path).exists() {
-        println!(
-            "cargo::error=.gdextension file {} does not exist, cannot apply icons",
-            gdextension_path
-        );
-        return;
-    }
-
-    let gdext_content = fs::read_to_string(gdextension_path);
-    if gdext_content.is_err() {
-        println!(
-            "cargo::error=Failed to read .gdextension file {}, skipping icon application",
-            gdextension_path
-        );
-        return;
+        return Err(GeneratorError::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                ".gdextension file {} does not exist, cannot apply icons",
+                gdextension_path
+            ),
+        )));
     }
 
-    let mut gdext_content = gdext_content.unwrap();
+    let mut gdext_content = fs::read_to_string(gdextension_path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "Failed to read .gdextension file {}: {}",
+                gdextension_path, e
+            ),
+        )
+    })?;
     let mut icon_assignments: Vec<String> = icons_to_apply
         .iter()
         .map(|(class, path)| format!("{} = \"{}\"", class, to_resource_path(path, resource_path)))
@@ -284,18 +250,409 @@ pub fn apply_icons_from_comments(
         gdext_content = format!("{}\n\n{}", gdext_content, new_icons_section);
     }
 
-    let result = fs::write(gdextension_path, gdext_content);
-    if result.is_err() {
-        println!(
-            "cargo::error=Failed to write updated .gdextension file {}, error: {}",
-            gdextension_path,
-            result.err().unwrap()
-        );
-        return;
-    }
+    write_atomically(Path::new(gdextension_path), &gdext_content).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "Failed to write updated .gdextension file {}: {}",
+                gdextension_path, e
+            ),
+        )
+    })?;
 
     println!(
         "cargo::warning=Icons applied to .gdextension file {}",
         gdextension_path
     );
+
+    Ok(Some(PathBuf::from(gdextension_path)))
+}
+
+/// Returns the prefixes of the `icon_sources` which no icon in `class_icons` starts with, sorted.
+fn unused_icon_sources<'a>(
+    icon_sources: &'a BTreeMap<String, String>,
+    class_icons: &[(String, String)],
+) -> Vec<&'a str> {
+    let mut unused = icon_sources
+        .keys()
+        .filter(|prefix| {
+            !class_icons
+                .iter()
+                .any(|(_, icon)| icon.starts_with(*prefix))
+        })
+        .map(|prefix| prefix.as_str())
+        .collect::<Vec<&str>>();
+    unused.sort();
+    unused
+}
+#[test]
+fn test_unused_icon_sources() {
+    let icon_sources = BTreeMap::from([
+        (
+            "res://icons/gd/".to_string(),
+            "https://example.com/icons/".to_string(),
+        ),
+        ("res://icons/old/".to_string(), "./old_icons".to_string()),
+    ]);
+    let class_icons = vec![("Menu".to_string(), "res://icons/gd/Control.svg".to_string())];
+
+    assert_eq!(
+        unused_icon_sources(&icon_sources, &class_icons),
+        vec!["res://icons/old/"]
+    );
+    assert_eq!(
+        unused_icon_sources(&icon_sources, &[]),
+        vec!["res://icons/gd/", "res://icons/old/"]
+    );
+}
+
+/// Scans the `.rs` files under each source path for classes to apply icons to, as `(class, icon)` pairs.
+///
+/// Source paths that do not exist are skipped with a warning, as are files matching any of the `ignore_source_globs`.
+fn scan_class_icons(
+    source_paths: &[&str],
+    ignore_source_globs: &[String],
+    default_icons: &BTreeMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut source_files = vec![];
+
+    for source_path in source_paths {
+        if !Path::new(source_path).exists() {
+            println!(
+                "cargo::warning=Source path {} does not exist, skipping icon comment parsing",
+                source_path
+            );
+            continue;
+        }
+
+        // recursively find all .rs files in source_path, e.g. skipping `.rs.bk` backups
+        source_files.extend(find_source_files(
+            Path::new(source_path),
+            ignore_source_globs,
+        ));
+    }
+
+    source_files
+        .iter()
+        .flat_map(|file_path| {
+            let file_content = std::fs::read_to_string(file_path).unwrap_or_else(|_| {
+                println!(
+                    "cargo::warning=Failed to read file {}, skipping",
+                    file_path.display()
+                );
+                String::new()
+            });
+
+            find_class_icons(&file_content, default_icons)
+        })
+        .collect()
+}
+#[test]
+fn test_scan_class_icons_multiple_source_paths() {
+    let dir = crate::utils::TestDir::new("scan_class_icons");
+    fs::create_dir_all(dir.join("core").join("src")).unwrap();
+    fs::create_dir_all(dir.join("nodes").join("src")).unwrap();
+    fs::write(
+        dir.join("core").join("src").join("recorder.rs"),
+        include_str!("../example/src/GameRecorder.rs"),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("nodes").join("src").join("menu.rs"),
+        include_str!("../example/src/Menu.rs"),
+    )
+    .unwrap();
+
+    let core = dir.join("core").join("src");
+    let nodes = dir.join("nodes").join("src");
+    let missing = dir.join("missing");
+    assert_eq!(
+        scan_class_icons(
+            &[
+                core.to_str().unwrap(),
+                nodes.to_str().unwrap(),
+                missing.to_str().unwrap()
+            ],
+            &[],
+            &BTreeMap::new()
+        ),
+        vec![
+            (
+                "GameRecorder".to_string(),
+                "res://icons/local/godot-rust.svg".to_string()
+            ),
+            ("Menu".to_string(), "res://icons/gd/Control.svg".to_string()),
+        ]
+    );
+}
+#[test]
+fn test_scan_class_icons_ignores_backups_and_globs() {
+    let dir = crate::utils::TestDir::new("scan_class_icons_ignores");
+    fs::create_dir_all(dir.join("generated")).unwrap();
+    fs::write(dir.join("menu.rs"), include_str!("../example/src/Menu.rs")).unwrap();
+    fs::write(
+        dir.join("recorder.rs.bk"),
+        include_str!("../example/src/GameRecorder.rs"),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("generated").join("recorder.rs"),
+        include_str!("../example/src/GameRecorder.rs"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        scan_class_icons(
+            &[dir.to_str().unwrap()],
+            &["generated/**".to_string()],
+            &BTreeMap::new()
+        ),
+        vec![("Menu".to_string(), "res://icons/gd/Control.svg".to_string())]
+    );
+}
+
+/// Finds the classes in a source file to apply icons to, as `(class, icon)` pairs.
+///
+/// Each `zgrcg:icon` comment applies to the struct immediately following it, whether the comment is on its own line or
+/// trailing an attribute of the struct, or to the struct declared earlier on the same line. Any other class deriving `GodotClass`
+/// whose base class is in `default_icons` is given that icon, without overriding explicit comments.
+fn find_class_icons(
+    file_content: &str,
+    default_icons: &BTreeMap<String, String>,
+) -> Vec<(String, String)> {
+    lazy_static! {
+        static ref ICON_COMMENT_REGEX: Regex = Regex::new(r#"zgrcg:icon="(.*?)""#).unwrap();
+    };
+
+    let mut icons: Vec<(String, String)> = Vec::new();
+
+    for caps in ICON_COMMENT_REGEX.captures_iter(file_content) {
+        let (Some(comment), Some(icon_comment)) = (caps.get(0), caps.get(1)) else {
+            continue;
+        };
+
+        if let Some(icon_class) = find_icon_comment_class(file_content, comment.start())
+            && !icons.iter().any(|(c, _)| c == icon_class)
+        {
+            icons.push((icon_class.to_string(), icon_comment.as_str().to_string()));
+        }
+    }
+
+    if !default_icons.is_empty() {
+        for class in GodotClass::parse_from_str(file_content) {
+            if icons.iter().any(|(c, _)| c == class.name) {
+                continue;
+            }
+
+            if let Some(icon) = class.base.and_then(|base| default_icons.get(base)) {
+                icons.push((class.name.to_string(), icon.clone()));
+            }
+        }
+    }
+
+    icons
+}
+#[test]
+fn test_find_class_icons() {
+    let default_icons = BTreeMap::from([
+        ("Node".to_string(), "res://icons/gd/Node.svg".to_string()),
+        (
+            "Control".to_string(),
+            "res://icons/gd/Control.svg".to_string(),
+        ),
+    ]);
+
+    // explicit comments are not overridden by the default for the base class
+    assert_eq!(
+        find_class_icons(
+            include_str!("../example/src/GameRecorder.rs"),
+            &default_icons
+        ),
+        vec![(
+            "GameRecorder".to_string(),
+            "res://icons/local/godot-rust.svg".to_string()
+        )]
+    );
+
+    let content = r#"
+#[derive(GodotClass)]
+#[class(init, base=Control)]
+pub struct Hud {
+    base: Base<Control>,
+}
+
+#[derive(GodotClass)]
+#[class(init, base=Node3D)]
+pub struct Unmapped {
+    base: Base<Node3D>,
+}
+"#;
+    assert_eq!(
+        find_class_icons(content, &default_icons),
+        vec![("Hud".to_string(), "res://icons/gd/Control.svg".to_string())]
+    );
+    assert_eq!(find_class_icons(content, &BTreeMap::new()), vec![]);
+}
+#[test]
+fn test_find_class_icons_multiple_classes() {
+    let content = r#"
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+// zgrcg:icon="res://icons/local/first.svg"
+pub struct First {
+    base: Base<Node>,
+}
+
+#[derive(GodotClass)]
+#[class(init, base=Control)] // zgrcg:icon="res://icons/local/second.svg"
+pub struct Second {
+    base: Base<Control>,
+}
+"#;
+
+    assert_eq!(
+        find_class_icons(content, &BTreeMap::new()),
+        vec![
+            (
+                "First".to_string(),
+                "res://icons/local/first.svg".to_string()
+            ),
+            (
+                "Second".to_string(),
+                "res://icons/local/second.svg".to_string()
+            ),
+        ]
+    );
+}
+
+/// Finds the struct an icon comment starting at `comment_start` applies to.
+///
+/// A struct declared earlier on the comment's line takes precedence, e.g. `pub struct Menu { // zgrcg:icon="..."`.
+/// Otherwise it is the first struct on a following line, skipping comment lines so a struct mentioned in a comment is not matched.
+fn find_icon_comment_class(file_content: &str, comment_start: usize) -> Option<&str> {
+    lazy_static! {
+        static ref ICON_CLASS_REGEX: Regex = Regex::new(r#"\bstruct\s+(\w+)"#).unwrap();
+    };
+
+    fn struct_name(code: &str) -> Option<&str> {
+        ICON_CLASS_REGEX
+            .captures(code)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str())
+    }
+
+    let line_start = file_content[..comment_start]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let line_code = &file_content[line_start..comment_start];
+    if !line_code.trim_start().starts_with("//")
+        && let Some(name) = struct_name(line_code)
+    {
+        return Some(name);
+    }
+
+    file_content[comment_start..]
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .find_map(struct_name)
+}
+#[test]
+fn test_find_icon_comment_class() {
+    // own line, above the attributes
+    let content = "// zgrcg:icon=\"res://a.svg\"\n#[derive(GodotClass)]\n#[class(init, base=Node)]\npub struct Above {}\n";
+    assert_eq!(find_icon_comment_class(content, 0), Some("Above"));
+
+    // own line, between the attributes and the struct, after a doc comment mentioning a struct
+    let content = "pub struct Previous {}\n\n#[derive(GodotClass)]\n// zgrcg:icon=\"res://a.svg\"\n/// A struct recording games\npub struct Recorder {}\n";
+    assert_eq!(
+        find_icon_comment_class(content, content.find("// zgrcg").unwrap()),
+        Some("Recorder")
+    );
+
+    // trailing an attribute, after a previous struct
+    let content = "pub struct Previous {}\n\n#[derive(GodotClass)]\n#[class(init,base=Control)] // zgrcg:icon=\"res://a.svg\"\npub struct Menu {}\n";
+    assert_eq!(
+        find_icon_comment_class(content, content.find("// zgrcg").unwrap()),
+        Some("Menu")
+    );
+
+    // trailing the struct itself
+    let content = "pub struct Inline { // zgrcg:icon=\"res://a.svg\"\n}\n\npub struct Next {}\n";
+    assert_eq!(
+        find_icon_comment_class(content, content.find("// zgrcg").unwrap()),
+        Some("Inline")
+    );
+
+    assert_eq!(
+        find_icon_comment_class("// zgrcg:icon=\"res://a.svg\"\n", 0),
+        None
+    );
+}
+#[test]
+fn test_find_class_icons_example_placements() {
+    // `Menu.rs` trails the attribute, `GameRecorder.rs` has the comment on its own line
+    assert_eq!(
+        find_class_icons(include_str!("../example/src/Menu.rs"), &BTreeMap::new()),
+        vec![("Menu".to_string(), "res://icons/gd/Control.svg".to_string())]
+    );
+    assert_eq!(
+        find_class_icons(
+            include_str!("../example/src/GameRecorder.rs"),
+            &BTreeMap::new()
+        ),
+        vec![(
+            "GameRecorder".to_string(),
+            "res://icons/local/godot-rust.svg".to_string()
+        )]
+    );
+}
+#[test]
+fn test_apply_icons_from_comments_multiple_classes() {
+    let dir = crate::utils::TestDir::new("apply_icons_multiple_classes");
+    let (src, icons, gd) = (dir.join("src"), dir.join("icons"), dir.join("gd"));
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&icons).unwrap();
+    fs::create_dir_all(&gd).unwrap();
+
+    fs::write(icons.join("first.svg"), "<svg/>").unwrap();
+    fs::write(icons.join("second.svg"), "<svg/>").unwrap();
+    fs::write(
+        src.join("classes.rs"),
+        r#"
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+// zgrcg:icon="res://icons/local/first.svg"
+pub struct First {}
+
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+// zgrcg:icon="res://icons/local/second.svg"
+pub struct Second {}
+"#,
+    )
+    .unwrap();
+    let gdextension = dir.join("rust.gdextension");
+    fs::write(
+        &gdextension,
+        "[configuration]\nentry_symbol=\"gdext_rust_init\"\n",
+    )
+    .unwrap();
+
+    apply_icons_from_comments(
+        &[src.to_str().unwrap()],
+        &[],
+        gd.to_str().unwrap(),
+        gdextension.to_str().unwrap(),
+        &BTreeMap::from([(
+            "res://icons/local/".to_string(),
+            icons.to_string_lossy().to_string(),
+        )]),
+        &BTreeMap::new(),
+    )
+    .unwrap();
+
+    let content = fs::read_to_string(&gdextension).unwrap();
+    assert!(content.contains("First = \"res://icons/local/first.svg\""));
+    assert!(content.contains("Second = \"res://icons/local/second.svg\""));
 }
@@ -1,96 +1,147 @@
-use std::{fs, io::Write, path::Path};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
 
 use crate::{
-    projectgodot::ProjectGodot,
-    utils::{make_path_if_not_exists, pascal_to_snake_case},
+    mod_file::GeneratedModule,
+    projectgodot::{Input, InputEvent, InputSection, KeyCombo, Modifier, ProjectGodot},
+    scenes::prefix_leading_digit,
+    utils::{
+        format_item_allow, format_lint_allows, pascal_to_snake_case, to_upper_camel_case,
+        write_generated_file,
+    },
 };
 
 const MOD_CONSTS: &str = "consts";
 const MOD_INVOCATIONS: &str = "invocations";
+const MOD_REGISTRATION: &str = "registration";
+const MOD_ENUM: &str = "enum";
+const MOD_DEFAULTS: &str = "defaults";
+/// Described in the docs of an action none of whose events can be resolved to a keystroke, e.g. only joypad events.
+const NO_RESOLVABLE_BINDING: &str = "(no resolvable binding)";
+/// The deadzone Godot gives new actions, which is omitted from the invocation docs.
+const DEFAULT_DEADZONE: f32 = 0.5;
 
 fn mod_name(t: &str) -> String {
     format!("actions_{}", t)
 }
 
+/// The action settings of a `Generator`, selecting which action modules `generate_actions` writes and how.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionOptions<'a> {
+    pub output_consts: bool,
+    pub consts_cached: bool,
+    /// The settings files the generated consts test checks the actions against, if the test is generated.
+    pub consts_tests_settings_paths: Option<&'a [&'a str]>,
+    pub output_invocations: bool,
+    pub output_registration: bool,
+    pub output_default_bindings: bool,
+    pub const_prefix: &'a str,
+    pub strict_lints: bool,
+    pub verbose: bool,
+}
+
 pub fn generate_actions(
     output_dir: &str,
-    output_consts: bool,
-    output_invocations: bool,
+    options: &ActionOptions,
     godot_project: &ProjectGodot,
-) -> Vec<String> {
-    if godot_project.input.is_none() || godot_project.input.as_ref().unwrap().inputs.len() == 0 {
+) -> io::Result<Vec<GeneratedModule>> {
+    let ActionOptions {
+        output_consts,
+        consts_cached,
+        consts_tests_settings_paths,
+        output_invocations,
+        output_registration,
+        output_default_bindings,
+        const_prefix,
+        strict_lints,
+        verbose,
+    } = *options;
+    let Some(inputs) = godot_project
+        .input
+        .as_ref()
+        .filter(|input| !input.inputs.is_empty())
+    else {
         println!(
-            "cargo::warning=No input actions found in project.godot, skipping actions.rs generation"
+            "cargo::warning=No input actions found in the project settings, skipping actions.rs generation"
         );
-    }
+        return Ok(vec![]);
+    };
 
-    let inputs = godot_project.input.as_ref().unwrap();
-    let mut actions = inputs
-        .inputs
-        .iter()
-        .map(|(name, input)| {
-            (
-                name.as_str(),
-                input
-                    .events
-                    .iter()
-                    .map(|e| e.get_key_string())
-                    .filter_map(|k| k)
-                    .collect::<Vec<String>>(),
-            )
-        })
-        .collect::<Vec<(&str, Vec<String>)>>();
-    actions.sort();
+    let mut actions = resolve_actions(inputs);
+    for (kept, removed) in remove_snake_case_collisions(&mut actions) {
+        println!(
+            "cargo::error=Input actions '{}' and '{}' both generate the name {}, rename one of them; skipping '{}'",
+            kept,
+            removed,
+            pascal_to_snake_case(kept),
+            removed
+        );
+    }
 
-    godot_project
-        .input
-        .as_ref()
-        .unwrap()
-        .inputs
-        .iter()
-        .for_each(|(_, input)| {
-            if input.events.len() == 0 {
-                println!(
-                    "cargo::warning=Input action '{}' has no events, skipping",
-                    input.name
-                );
-            } else {
-                for event in input.events.iter() {
-                    println!(
-                        "cargo::warning=Input action '{}' has event: {} = {} ({})",
-                        input.name,
-                        event.event_type,
-                        event.get_key_string().unwrap_or("unknown".to_string()),
-                        event.int_properties.get("unicode").unwrap_or(&0)
-                    );
-                }
-            }
-        });
+    for line in action_event_lines(inputs, verbose) {
+        println!("{}", line);
+    }
+    if output_registration || output_default_bindings {
+        for line in unsupported_event_lines(inputs) {
+            println!("{}", line);
+        }
+    }
 
-    let mut output_mods: Vec<String> = vec![];
+    let mut output_mods: Vec<GeneratedModule> = vec![];
 
     if output_consts {
         let input_actions = actions
             .iter()
             .map(|(action, events)| {
-                format_action_to_const(action, &get_action_keystroke_doc_comment(events))
+                format_action_to_const(
+                    action,
+                    &get_action_keystroke_doc_comment(events),
+                    consts_cached,
+                    output_invocations,
+                    const_prefix,
+                    strict_lints,
+                )
             })
             .collect::<Vec<String>>()
             .join("\n");
 
-        make_path_if_not_exists(get_action_mod_file(output_dir, MOD_CONSTS).as_str());
+        let consts_path = get_action_mod_file(output_dir, MOD_CONSTS);
 
-        let mut file = fs::File::create(get_action_mod_file(output_dir, MOD_CONSTS)).unwrap();
-        file.write_all(get_consts_file_content(input_actions.as_str()).as_bytes())
-            .unwrap();
+        write_generated_file(
+            Path::new(&consts_path),
+            &format!(
+                "{}{}",
+                get_consts_file_content(input_actions.as_str(), strict_lints),
+                consts_tests_settings_paths.map_or(String::new(), |paths| {
+                    format_action_consts_tests(
+                        &actions
+                            .iter()
+                            .map(|(action, _)| *action)
+                            .collect::<Vec<&str>>(),
+                        paths,
+                    )
+                })
+            ),
+        )?;
 
-        output_mods.push(mod_name(MOD_CONSTS));
+        output_mods.push(GeneratedModule {
+            name: mod_name(MOD_CONSTS),
+            path: PathBuf::from(&consts_path),
+        });
     }
 
     if output_invocations {
         let trait_defs = actions
             .iter()
-            .map(|(action, events)| format_action_to_invocation_trait(action, events))
+            .map(|(action, events)| {
+                format_action_to_invocation_trait(
+                    action,
+                    events,
+                    inputs.inputs.get(*action).and_then(|input| input.deadzone),
+                )
+            })
             .collect::<Vec<String>>()
             .join("\n\n");
         let impl_defs = actions
@@ -98,39 +149,716 @@ pub fn generate_actions(
             .map(|(action, _)| format_action_to_invocation_impl(action))
             .collect::<Vec<String>>()
             .join("\n\n");
+        let action_names = actions
+            .iter()
+            .map(|(action, _)| *action)
+            .collect::<Vec<&str>>();
+        let trait_defs = format!("{}\n\n{}", trait_defs, format_any_action_trait());
+        let impl_defs = format!("{}\n\n{}", impl_defs, format_any_action_impl(&action_names));
+
+        let invocations_path = get_action_mod_file(output_dir, MOD_INVOCATIONS);
+
+        write_generated_file(
+            Path::new(&invocations_path),
+            &get_invocations_file_content(&trait_defs, &impl_defs, strict_lints),
+        )?;
+
+        output_mods.push(GeneratedModule {
+            name: mod_name(MOD_INVOCATIONS),
+            path: PathBuf::from(&invocations_path),
+        });
+    }
+
+    if output_registration {
+        let mut inputs = inputs.inputs.values().collect::<Vec<&Input>>();
+        inputs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let registrations = inputs
+            .iter()
+            .map(|input| format_action_to_registration(input))
+            .collect::<Vec<String>>()
+            .join("\n\n");
+
+        let registration_path = get_action_mod_file(output_dir, MOD_REGISTRATION);
+
+        write_generated_file(
+            Path::new(&registration_path),
+            &get_registration_file_content(&registrations, strict_lints),
+        )?;
+
+        output_mods.push(GeneratedModule {
+            name: mod_name(MOD_REGISTRATION),
+            path: PathBuf::from(&registration_path),
+        });
+    }
+
+    if output_default_bindings {
+        let mut inputs = inputs.inputs.values().collect::<Vec<&Input>>();
+        inputs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let resets = inputs
+            .iter()
+            .map(|input| format_action_to_default_bindings(input))
+            .collect::<Vec<String>>()
+            .join("\n\n");
+
+        let defaults_path = get_action_mod_file(output_dir, MOD_DEFAULTS);
+
+        write_generated_file(
+            Path::new(&defaults_path),
+            &get_default_bindings_file_content(&resets, strict_lints),
+        )?;
+
+        output_mods.push(GeneratedModule {
+            name: mod_name(MOD_DEFAULTS),
+            path: PathBuf::from(&defaults_path),
+        });
+    }
+
+    Ok(output_mods)
+}
+#[test]
+fn test_generate_actions_without_input_section() {
+    let dir = crate::utils::TestDir::new("generate_actions_without_input");
+    let output_dir = dir.join("generated");
+    let output_dir = output_dir.to_str().unwrap();
+
+    let project = ProjectGodot::parse_from_str(
+        "config_version=5\n\n[application]\n\nconfig/name=\"Example\"\n",
+    );
+    assert!(project.input.is_none());
+    assert_eq!(
+        generate_actions(
+            output_dir,
+            &ActionOptions {
+                output_consts: true,
+                consts_cached: false,
+                consts_tests_settings_paths: None,
+                output_invocations: true,
+                output_registration: true,
+                output_default_bindings: true,
+                const_prefix: "",
+                strict_lints: false,
+                verbose: false,
+            },
+            &project
+        )
+        .unwrap(),
+        Vec::<GeneratedModule>::new()
+    );
+    assert!(!dir.join("generated").exists());
+}
+
+/// Formats a warning for each action without events, and if `verbose`, a line listing each event of every other action.
+fn action_event_lines(inputs: &InputSection, verbose: bool) -> Vec<String> {
+    let mut lines = vec![];
+    for input in inputs.inputs.values() {
+        if input.events.is_empty() {
+            lines.push(format!(
+                "cargo::warning=Input action '{}' has no events, skipping",
+                input.name
+            ));
+        } else if verbose {
+            for event in input.events.iter() {
+                lines.push(format!(
+                    "cargo::warning=Input action '{}' has event: {} = {} ({})",
+                    input.name,
+                    event.event_type,
+                    event.get_key_string().unwrap_or("unknown".to_string()),
+                    event.int_properties.get("unicode").unwrap_or(&0)
+                ));
+            }
+        }
+    }
+    lines
+}
+#[test]
+fn test_action_event_lines() {
+    let project = ProjectGodot::parse_from_str(
+        r#"[input]
+
+jump={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":32,"physical_keycode":0,"key_label":0,"unicode":32,"location":0,"echo":false,"script":null)
+]
+}
+unbound={
+"deadzone": 0.5,
+"events": []
+}
+"#,
+    );
+    let inputs = project.input.as_ref().unwrap();
+
+    // only genuine issues are reported by default
+    assert_eq!(
+        action_event_lines(inputs, false),
+        vec!["cargo::warning=Input action 'unbound' has no events, skipping"]
+    );
+
+    let lines = action_event_lines(inputs, true);
+    assert_eq!(lines.len(), 2);
+    assert!(lines.contains(
+        &"cargo::warning=Input action 'jump' has event: InputEventKey = SPACE (32)".to_string()
+    ));
+}
+
+/// Formats a warning for each event which the generated registration and default bindings cannot add to the `InputMap`.
+fn unsupported_event_lines(inputs: &InputSection) -> Vec<String> {
+    let mut inputs = inputs.inputs.values().collect::<Vec<&Input>>();
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut lines = vec![];
+    for input in inputs {
+        for event in input.events.iter() {
+            if format_event_to_registration(event).is_none() {
+                lines.push(format!(
+                    "cargo::warning=Input action '{}' has an event of type {} which cannot be registered yet, skipping",
+                    input.name, event.event_type
+                ));
+            }
+        }
+    }
+    lines
+}
+#[test]
+fn test_unsupported_event_lines() {
+    let project = ProjectGodot::parse_from_str(
+        r#"[input]
 
-        make_path_if_not_exists(&get_action_mod_file(output_dir, MOD_CONSTS).as_str());
+jump={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":32,"physical_keycode":0,"key_label":0,"unicode":32,"location":0,"echo":false,"script":null), Object(InputEventJoypadButton,"resource_local_to_scene":false,"resource_name":"","device":-1,"button_index":0,"pressure":0.0,"pressed":true,"script":null)
+]
+}
+"#,
+    );
 
-        let mut file = fs::File::create(get_action_mod_file(output_dir, MOD_INVOCATIONS)).unwrap();
-        file.write_all(get_invocations_file_content(&trait_defs, &impl_defs).as_bytes())
-            .unwrap();
+    assert_eq!(
+        unsupported_event_lines(project.input.as_ref().unwrap()),
+        vec![
+            "cargo::warning=Input action 'jump' has an event of type InputEventJoypadButton which cannot be registered yet, skipping"
+        ]
+    );
+}
 
-        output_mods.push(mod_name(MOD_INVOCATIONS));
+/// Generates an `InputAction` enum with a variant for each action, converting into a `StringName` and from the action's name.
+pub fn generate_action_enum(
+    output_dir: &str,
+    godot_project: &ProjectGodot,
+    strict_lints: bool,
+) -> io::Result<Vec<GeneratedModule>> {
+    let Some(inputs) = godot_project
+        .input
+        .as_ref()
+        .filter(|input| !input.inputs.is_empty())
+    else {
+        println!(
+            "cargo::warning=No input actions found in the project settings, skipping actions_enum.rs generation"
+        );
+        return Ok(vec![]);
+    };
+
+    let mut variants: Vec<(String, &str, String)> = vec![];
+    for (action, keystrokes) in resolve_actions(inputs) {
+        let variant = to_variant_name(action);
+        if variants.iter().any(|(v, _, _)| *v == variant) {
+            println!(
+                "cargo::warning=Input action '{}' has the same enum variant {} as another action, skipping",
+                action, variant
+            );
+            continue;
+        }
+        variants.push((
+            variant,
+            action,
+            get_action_keystroke_doc_comment(&keystrokes),
+        ));
     }
 
-    output_mods
+    let enum_path = Path::new(output_dir).join(format!("{}.rs", mod_name(MOD_ENUM)));
+
+    write_generated_file(&enum_path, &format_actions_to_enum(&variants, strict_lints))?;
+
+    Ok(vec![GeneratedModule {
+        name: mod_name(MOD_ENUM),
+        path: enum_path,
+    }])
 }
 
-fn get_action_keystroke_doc_comment(keystrokes: &Vec<String>) -> String {
+/// Converts an action name into an `InputAction` variant, e.g. `move_left` -> `MoveLeft` and `1st_jump` -> `_1stJump`.
+fn to_variant_name(action: &str) -> String {
+    let variant = to_upper_camel_case(&action.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+
+    if variant.is_empty() {
+        "Action".to_string()
+    } else {
+        prefix_leading_digit(variant)
+    }
+}
+#[test]
+fn test_to_variant_name() {
+    assert_eq!(to_variant_name("move_left"), "MoveLeft");
+    assert_eq!(to_variant_name("Fire: Alt"), "FireAlt");
+    assert_eq!(to_variant_name("1st_jump"), "_1stJump");
+    assert_eq!(to_variant_name("!!"), "Action");
+}
+
+/// Formats `(variant, action, doc comment)` triples into the `InputAction` enum, with `From<InputAction> for StringName`
+/// and `TryFrom<&str>` conversions using the action names from Godot.
+fn format_actions_to_enum(variants: &[(String, &str, String)], strict_lints: bool) -> String {
     format!(
-        "/// Maps to: `{}`",
-        keystrokes
+        r#"{}use godot::builtin::StringName;
+
+/// An action from the `[input]` section of `project.godot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputAction {{
+{}}}
+
+impl InputAction {{
+    /// Returns the name of the action in Godot.
+    pub fn as_str(&self) -> &'static str {{
+        match self {{
+{}        }}
+    }}
+}}
+
+impl From<InputAction> for StringName {{
+    fn from(action: InputAction) -> Self {{
+        StringName::from(action.as_str())
+    }}
+}}
+
+/// The error returned when converting a name which is not an action into an `InputAction`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownInputAction(pub String);
+
+impl TryFrom<&str> for InputAction {{
+    type Error = UnknownInputAction;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {{
+        match name {{
+{}            _ => Err(UnknownInputAction(name.to_string())),
+        }}
+    }}
+}}
+"#,
+        format_lint_allows(strict_lints, false),
+        variants
+            .iter()
+            .map(|(variant, _, doc_comment)| format!("    {}\n    {},\n", doc_comment, variant))
+            .collect::<String>(),
+        variants
             .iter()
-            .map(|k| k.as_str())
-            .collect::<Vec<&str>>()
-            .join("` or `")
+            .map(|(variant, action, _)| format!("            Self::{} => {:?},\n", variant, action))
+            .collect::<String>(),
+        variants
+            .iter()
+            .map(|(variant, action, _)| format!(
+                "            {:?} => Ok(Self::{}),\n",
+                action, variant
+            ))
+            .collect::<String>()
     )
 }
 #[test]
+fn test_format_actions_to_enum() {
+    let variants = [
+        (
+            "Fire".to_string(),
+            "Fire",
+            "/// Maps to: `left_click`".to_string(),
+        ),
+        (
+            "MoveLeft".to_string(),
+            "move_left",
+            "/// Maps to: `A`".to_string(),
+        ),
+    ];
+
+    assert_eq!(
+        format_actions_to_enum(&variants, false),
+        r#"#![allow(dead_code)]
+use godot::builtin::StringName;
+
+/// An action from the `[input]` section of `project.godot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    /// Maps to: `left_click`
+    Fire,
+    /// Maps to: `A`
+    MoveLeft,
+}
+
+impl InputAction {
+    /// Returns the name of the action in Godot.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fire => "Fire",
+            Self::MoveLeft => "move_left",
+        }
+    }
+}
+
+impl From<InputAction> for StringName {
+    fn from(action: InputAction) -> Self {
+        StringName::from(action.as_str())
+    }
+}
+
+/// The error returned when converting a name which is not an action into an `InputAction`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownInputAction(pub String);
+
+impl TryFrom<&str> for InputAction {
+    type Error = UnknownInputAction;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        match name {
+            "Fire" => Ok(Self::Fire),
+            "move_left" => Ok(Self::MoveLeft),
+            _ => Err(UnknownInputAction(name.to_string())),
+        }
+    }
+}
+"#
+    );
+}
+#[test]
+fn test_generate_action_enum() {
+    let dir = crate::utils::TestDir::new("generate_action_enum");
+
+    let project = ProjectGodot::parse_from_str(
+        r#"[input]
+
+move_left={
+"deadzone": 0.5,
+"events": []
+}
+MoveLeft={
+"deadzone": 0.5,
+"events": []
+}
+"#,
+    );
+    let modules = generate_action_enum(dir.to_str().unwrap(), &project, true).unwrap();
+
+    assert_eq!(
+        modules,
+        vec![GeneratedModule {
+            name: "actions_enum".to_string(),
+            path: dir.join("actions_enum.rs"),
+        }]
+    );
+    let content = std::fs::read_to_string(dir.join("actions_enum.rs")).unwrap();
+    // `MoveLeft` sorts first, so `move_left` is skipped as its variant collides
+    assert!(
+        content.contains("            \"MoveLeft\" => Ok(Self::MoveLeft),\n            _ => Err(")
+    );
+    assert!(!content.contains("move_left"));
+    assert!(!content.contains("#![allow("));
+}
+
+/// Formats the parsed `[input]` actions as JSON, with the name, deadzone and resolved keystrokes of each action, sorted by name.
+///
+/// e.g:
+/// ```json
+/// {
+///   "actions": [
+///     {
+///       "name": "jump",
+///       "deadzone": 0.5,
+///       "keystrokes": ["SPACE", "ctrl+J"]
+///     }
+///   ]
+/// }
+/// ```
+pub fn format_action_map_json(godot_project: &ProjectGodot) -> String {
+    let mut inputs = godot_project
+        .input
+        .as_ref()
+        .map(|input| input.inputs.values().collect::<Vec<&Input>>())
+        .unwrap_or_default();
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let actions = inputs
+        .iter()
+        .map(|input| {
+            format!(
+                "    {{\n      \"name\": {},\n      \"deadzone\": {},\n      \"keystrokes\": [{}]\n    }}",
+                json_string(&input.name),
+                input
+                    .deadzone
+                    .map_or("null".to_string(), |deadzone| deadzone.to_string()),
+                input
+                    .events
+                    .iter()
+                    .filter_map(|e| e.get_key_combo())
+                    .map(|combo| json_string(&combo.to_string()))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+        .collect::<Vec<String>>();
+
+    if actions.is_empty() {
+        "{\n  \"actions\": []\n}\n".to_string()
+    } else {
+        format!("{{\n  \"actions\": [\n{}\n  ]\n}}\n", actions.join(",\n"))
+    }
+}
+#[test]
+fn test_format_action_map_json() {
+    let project = ProjectGodot::parse_from_str(
+        r#"config_version=5
+
+[input]
+
+Jump={
+"deadzone": 0.2,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":0,"physical_keycode":32,"key_label":0,"unicode":32,"location":0,"echo":false,"script":null),Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":true,"meta_pressed":false,"pressed":false,"keycode":0,"physical_keycode":74,"key_label":0,"unicode":106,"location":0,"echo":false,"script":null)
+]
+}
+Crouch={
+"events": []
+}
+"#,
+    );
+
+    // ctrl+J is bound to device 1, which is only rendered in docs so tools can parse the keystroke
+    assert_eq!(
+        format_action_map_json(&project),
+        r#"{
+  "actions": [
+    {
+      "name": "Crouch",
+      "deadzone": null,
+      "keystrokes": []
+    },
+    {
+      "name": "Jump",
+      "deadzone": 0.2,
+      "keystrokes": ["SPACE", "ctrl+J"]
+    }
+  ]
+}
+"#
+    );
+    assert_eq!(
+        format_action_map_json(&ProjectGodot::parse_from_str("")),
+        "{\n  \"actions\": []\n}\n"
+    );
+}
+
+/// Quotes and escapes a string for use in JSON.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+#[test]
+fn test_json_string() {
+    assert_eq!(json_string("Jump"), "\"Jump\"");
+    assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+    assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+}
+
+/// Resolves the keystrokes of each action, sorted by action name, without duplicate keystrokes.
+fn resolve_actions(inputs: &InputSection) -> Vec<(&str, Vec<KeyCombo>)> {
+    let mut actions = inputs
+        .inputs
+        .iter()
+        .map(|(name, input)| {
+            let mut keystrokes: Vec<KeyCombo> = vec![];
+            for combo in input.events.iter().filter_map(|e| e.get_key_combo()) {
+                if !keystrokes.contains(&combo) {
+                    keystrokes.push(combo);
+                }
+            }
+            (name.as_str(), keystrokes)
+        })
+        .collect::<Vec<(&str, Vec<KeyCombo>)>>();
+    actions.sort();
+    actions
+}
+#[test]
+fn test_resolve_actions_devices() {
+    let project = ProjectGodot::parse_from_str(
+        r#"[input]
+
+jump={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":65,"physical_keycode":0,"key_label":0,"unicode":0,"location":0,"echo":false,"script":null),Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":65,"physical_keycode":0,"key_label":0,"unicode":0,"location":0,"echo":false,"script":null),Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":65,"physical_keycode":0,"key_label":0,"unicode":0,"location":0,"echo":false,"script":null)
+]
+}
+"#,
+    );
+    let actions = resolve_actions(project.input.as_ref().unwrap());
+
+    assert_eq!(actions[0].1.len(), 2);
+    assert_eq!(
+        get_action_keystroke_doc_comment(&actions[0].1),
+        "/// Maps to: `A` or `A (device 1)`"
+    );
+}
+
+/// Returns the names of the actions in `project.godot` which are generated, sorted by name, e.g. for the GDScript consts.
+pub fn action_names<'a>(godot_project: &'a ProjectGodot) -> Vec<&'a str> {
+    let Some(inputs) = &godot_project.input else {
+        return vec![];
+    };
+
+    let mut actions = resolve_actions(inputs);
+    remove_snake_case_collisions(&mut actions);
+    actions.into_iter().map(|(action, _)| action).collect()
+}
+
+/// Removes each action whose snake_case name collides with an earlier action, e.g. `move_left` after `MoveLeft`, as both would
+/// generate `is_move_left_pressed`.
+///
+/// Returns the `(kept, removed)` names of each collision.
+fn remove_snake_case_collisions<'a>(
+    actions: &mut Vec<(&'a str, Vec<KeyCombo>)>,
+) -> Vec<(&'a str, &'a str)> {
+    let mut collisions = vec![];
+    let mut kept: Vec<(&str, String)> = vec![];
+
+    actions.retain(|(action, _)| {
+        let snake_case = pascal_to_snake_case(action);
+        match kept.iter().find(|(_, name)| *name == snake_case) {
+            Some((other, _)) => {
+                collisions.push((*other, *action));
+                false
+            }
+            None => {
+                kept.push((action, snake_case));
+                true
+            }
+        }
+    });
+
+    collisions
+}
+#[test]
+fn test_remove_snake_case_collisions() {
+    let project = ProjectGodot::parse_from_str(
+        r#"[input]
+
+MoveLeft={
+"deadzone": 0.5,
+"events": []
+}
+move_left={
+"deadzone": 0.5,
+"events": []
+}
+jump={
+"deadzone": 0.5,
+"events": []
+}
+"#,
+    );
+    let mut actions = resolve_actions(project.input.as_ref().unwrap());
+
+    assert_eq!(
+        remove_snake_case_collisions(&mut actions),
+        vec![("MoveLeft", "move_left")]
+    );
+    assert_eq!(
+        actions
+            .iter()
+            .map(|(action, _)| *action)
+            .collect::<Vec<&str>>(),
+        vec!["MoveLeft", "jump"]
+    );
+}
+
+/// Formats the parsed `[input]` actions as a Markdown table of each action and the keystrokes it maps to, sorted by name.
+pub fn format_keybindings_markdown(godot_project: &ProjectGodot) -> String {
+    let rows = godot_project
+        .input
+        .as_ref()
+        .map(resolve_actions)
+        .unwrap_or_default()
+        .iter()
+        .map(|(action, keystrokes)| {
+            let maps_to = if keystrokes.is_empty() {
+                String::new()
+            } else {
+                format!("`{}`", join_keystrokes(keystrokes))
+            };
+            format!(
+                "|{}|{}|\n",
+                action.replace('|', "\\|"),
+                maps_to.replace('|', "\\|")
+            )
+        })
+        .collect::<String>();
+
+    format!("# Keybindings\n\n|Action|Maps to|\n|-|-|\n{}", rows)
+}
+#[test]
+fn test_format_keybindings_markdown() {
+    let content = include_str!("../example/project.godot");
+    let project = ProjectGodot::parse_from_str(content);
+
+    assert_eq!(
+        format_keybindings_markdown(&project),
+        r#"# Keybindings
+
+|Action|Maps to|
+|-|-|
+|Fire|`left_click` or `J`|
+|jump|`SPACE`|
+|move_down|`S`|
+|move_left|`A`|
+|move_right|`D`|
+|move_up|`W`|
+"#
+    );
+    assert_eq!(
+        format_keybindings_markdown(&ProjectGodot::parse_from_str("")),
+        "# Keybindings\n\n|Action|Maps to|\n|-|-|\n"
+    );
+}
+
+fn get_action_keystroke_doc_comment(keystrokes: &[KeyCombo]) -> String {
+    if keystrokes.is_empty() {
+        return format!("/// {}", NO_RESOLVABLE_BINDING);
+    }
+    format!("/// Maps to: `{}`", join_keystrokes(keystrokes))
+}
+#[test]
 fn test_get_action_keystroke_doc_comment() {
+    use crate::projectgodot::Modifier;
+
     assert_eq!(
-        get_action_keystroke_doc_comment(&vec!["Ctrl+A".into()]),
-        "/// Maps to: `Ctrl+A`"
+        get_action_keystroke_doc_comment(&[KeyCombo::new(&[Modifier::Ctrl], "A")]),
+        "/// Maps to: `ctrl+A`"
     );
     assert_eq!(
-        get_action_keystroke_doc_comment(&vec!["left_click".into(), "mouse_left".into()]),
+        get_action_keystroke_doc_comment(&[
+            KeyCombo::new(&[], "left_click"),
+            KeyCombo::new(&[], "mouse_left")
+        ]),
         "/// Maps to: `left_click` or `mouse_left`"
     );
+    assert_eq!(
+        get_action_keystroke_doc_comment(&[]),
+        "/// (no resolvable binding)"
+    );
 }
 
 fn get_action_mod_file(output_dir: &str, name: &str) -> String {
@@ -152,9 +880,10 @@ fn test_get_action_mod_file() {
     );
 }
 
-fn get_consts_file_content(consts: &str) -> String {
+fn get_consts_file_content(consts: &str, strict_lints: bool) -> String {
     format!(
-        "#![allow(dead_code)]\n#![allow(non_snake_case)]\nuse godot::builtin::StringName;\n\n{}",
+        "{}use godot::builtin::StringName;\n\n{}",
+        format_lint_allows(strict_lints, true),
         consts
     )
 }
@@ -162,35 +891,267 @@ fn get_consts_file_content(consts: &str) -> String {
 fn test_get_consts_file_content() {
     assert_eq!(
         get_consts_file_content(
-            "/// Maps to: `Ctrl+A`\npub fn CTRL_A() -> StringName { StringName::from(\"Ctrl+A\") }"
+            "/// Maps to: `Ctrl+A`\npub fn CTRL_A() -> StringName { StringName::from(\"Ctrl+A\") }",
+            false
         ),
         "#![allow(dead_code)]\n#![allow(non_snake_case)]\nuse godot::builtin::StringName;\n\n/// Maps to: `Ctrl+A`\npub fn CTRL_A() -> StringName { StringName::from(\"Ctrl+A\") }"
     );
 }
+#[test]
+fn test_get_consts_file_content_strict_lints() {
+    assert_eq!(
+        get_consts_file_content(
+            &format_action_to_const("Fire", "/// Maps to: `left_click`", false, false, "", true),
+            true
+        ),
+        "use godot::builtin::StringName;\n\n/// Maps to: `left_click`\n#[allow(non_snake_case)]\npub fn FIRE() -> StringName { StringName::from(\"Fire\") }\n/// Maps to: `left_click`\npub const FIRE_STR: &'static str = \"Fire\";"
+    );
+}
+
+/// Formats a test module checking each action is still in the project settings, the `project.godot` and any `override.cfg`
+/// layered on it, which are included when the tests are compiled, so running `cargo test` fails if an action was renamed or
+/// removed in Godot since the consts were generated.
+///
+/// A relative path in `settings_paths` is resolved from `CARGO_MANIFEST_DIR`, as `build.rs` runs in the package root.
+fn format_action_consts_tests(actions: &[&str], settings_paths: &[&str]) -> String {
+    let includes = settings_paths
+        .iter()
+        .map(|settings_path| {
+            let path = settings_path.replace('\\', "/");
+            if Path::new(settings_path).is_absolute() {
+                format!("        include_str!({:?}),\n", path)
+            } else {
+                format!(
+                    "        include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), {:?})),\n",
+                    format!("/{}", path.trim_start_matches("./"))
+                )
+            }
+        })
+        .collect::<String>();
+
+    format!(
+        r#"
+
+/// Checks each action above is still in the project settings, to catch consts left stale by renaming an action in Godot.
+#[cfg(test)]
+mod tests {{
+    const PROJECT_SETTINGS: &[&str] = &[
+{}    ];
+    const ACTIONS: &[&str] = &[
+{}    ];
+
+    #[test]
+    fn actions_exist_in_project_godot() {{
+        for action in ACTIONS {{
+            assert!(
+                PROJECT_SETTINGS
+                    .iter()
+                    .flat_map(|settings| settings.lines())
+                    .any(|line| {{
+                        // actions with spaces or symbols in their names are quoted by Godot
+                        line.starts_with(&format!("{{}}={{{{", action))
+                            || line.starts_with(&format!("{{:?}}={{{{", action))
+                    }}),
+                "action {{}} is not in the project settings, regenerate the action consts",
+                action
+            );
+        }}
+    }}
+}}"#,
+        includes,
+        actions
+            .iter()
+            .map(|action| format!("        {:?},\n", action))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_action_consts_tests() {
+    let expected = r#"
+
+/// Checks each action above is still in the project settings, to catch consts left stale by renaming an action in Godot.
+#[cfg(test)]
+mod tests {
+    const PROJECT_SETTINGS: &[&str] = &[
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../godot/project.godot")),
+    ];
+    const ACTIONS: &[&str] = &[
+        "Fire",
+        "MoveLeft",
+    ];
+
+    #[test]
+    fn actions_exist_in_project_godot() {
+        for action in ACTIONS {
+            assert!(
+                PROJECT_SETTINGS
+                    .iter()
+                    .flat_map(|settings| settings.lines())
+                    .any(|line| {
+                        // actions with spaces or symbols in their names are quoted by Godot
+                        line.starts_with(&format!("{}={{", action))
+                            || line.starts_with(&format!("{:?}={{", action))
+                    }),
+                "action {} is not in the project settings, regenerate the action consts",
+                action
+            );
+        }
+    }
+}"#;
+
+    assert_eq!(
+        format_action_consts_tests(&["Fire", "MoveLeft"], &["../godot/project.godot"]),
+        expected
+    );
+    assert!(
+        format_action_consts_tests(&["Fire"], &["./project.godot"])
+            .contains("include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/project.godot\"))")
+    );
+    assert!(
+        format_action_consts_tests(&["Fire"], &["/home/me/game/project.godot"])
+            .contains("include_str!(\"/home/me/game/project.godot\")")
+    );
+
+    // actions only in an override.cfg are found in it
+    assert!(
+        format_action_consts_tests(&["Fire"], &["./project.godot", "./override.cfg"]).contains(
+            "        include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/project.godot\")),\n        include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/override.cfg\")),\n    ];\n"
+        )
+    );
+}
+
+/// Formats an action into a `StringName` accessor and a `&str` const.
+///
+/// If `cached`, the `StringName` is created once per thread in a `thread_local!`, and the accessor returns a clone of it rather than
+/// allocating on every call. A `thread_local!` is used rather than a `static`, as that needs `StringName: Sync`.
+///
+/// If `link_invocations`, as the invocations are also generated, the docs link to the action's `is_<action>_pressed` method.
+fn format_action_to_const(
+    action: &str,
+    doc_comment: &str,
+    cached: bool,
+    link_invocations: bool,
+    const_prefix: &str,
+    strict_lints: bool,
+) -> String {
+    let const_name = format!(
+        "{}{}",
+        const_prefix,
+        pascal_to_snake_case(action).to_ascii_uppercase()
+    );
+    let doc_comment = if link_invocations {
+        let method = format!(
+            "InputActionInvocations::is_{}_pressed",
+            pascal_to_snake_case(action)
+        );
+        format!(
+            "{}\n///\n/// See also [`{}`](super::{}::{})",
+            doc_comment,
+            method,
+            mod_name(MOD_INVOCATIONS),
+            method
+        )
+    } else {
+        doc_comment.to_string()
+    };
+
+    let (cache, body) = if cached {
+        (
+            format!(
+                "thread_local! {{ static {}_STRING_NAME: StringName = StringName::from(\"{}\"); }}\n",
+                const_name, action
+            ),
+            format!("{}_STRING_NAME.with(StringName::clone)", const_name),
+        )
+    } else {
+        (String::new(), format!("StringName::from(\"{}\")", action))
+    };
 
-fn format_action_to_const(action: &str, doc_comment: &str) -> String {
     format!(
-        "{}\npub fn {}() -> StringName {{ StringName::from(\"{}\") }}\n{}\npub const {}_STR: &'static str = \"{}\";",
+        "{}{}\n{}pub fn {}() -> StringName {{ {} }}\n{}\npub const {}_STR: &'static str = \"{}\";",
+        cache,
         doc_comment,
-        pascal_to_snake_case(action).to_ascii_uppercase(),
-        action,
+        format_item_allow(strict_lints, "non_snake_case"),
+        const_name,
+        body,
         doc_comment,
-        pascal_to_snake_case(action).to_ascii_uppercase(),
+        const_name,
         action
     )
 }
 #[test]
 fn test_format_action_to_const() {
     assert_eq!(
-        format_action_to_const("Fire", "/// Maps to: `left_click`"),
+        format_action_to_const("Fire", "/// Maps to: `left_click`", false, false, "", false),
         "/// Maps to: `left_click`\npub fn FIRE() -> StringName { StringName::from(\"Fire\") }\n/// Maps to: `left_click`\npub const FIRE_STR: &'static str = \"Fire\";"
     );
 }
+#[test]
+fn test_format_action_to_const_link_invocations() {
+    let link = "/// See also [`InputActionInvocations::is_move_left_pressed`](super::actions_invocations::InputActionInvocations::is_move_left_pressed)";
 
-fn get_invocations_file_content(trait_defs: &str, impl_defs: &str) -> String {
+    assert_eq!(
+        format_action_to_const("MoveLeft", "/// Maps to: `A`", false, true, "", false),
+        format!(
+            "/// Maps to: `A`\n///\n{0}\npub fn MOVE_LEFT() -> StringName {{ StringName::from(\"MoveLeft\") }}\n/// Maps to: `A`\n///\n{0}\npub const MOVE_LEFT_STR: &'static str = \"MoveLeft\";",
+            link
+        )
+    );
+    assert!(
+        !format_action_to_const("MoveLeft", "/// Maps to: `A`", false, false, "", false)
+            .contains("See also")
+    );
+}
+#[test]
+fn test_format_action_to_const_prefix() {
+    assert_eq!(
+        format_action_to_const(
+            "Fire",
+            "/// Maps to: `left_click`",
+            false,
+            false,
+            "ACTION_",
+            false
+        ),
+        "/// Maps to: `left_click`\npub fn ACTION_FIRE() -> StringName { StringName::from(\"Fire\") }\n/// Maps to: `left_click`\npub const ACTION_FIRE_STR: &'static str = \"Fire\";"
+    );
+    assert!(
+        format_action_to_const(
+            "Fire",
+            "/// Maps to: `left_click`",
+            true,
+            false,
+            "ACTION_",
+            false
+        )
+        .starts_with("thread_local! { static ACTION_FIRE_STRING_NAME: StringName")
+    );
+}
+#[test]
+fn test_format_action_to_const_cached() {
+    assert_eq!(
+        get_consts_file_content(
+            &format_action_to_const("MoveLeft", "/// Maps to: `A`", true, false, "", false),
+            false
+        ),
+        r#"#![allow(dead_code)]
+#![allow(non_snake_case)]
+use godot::builtin::StringName;
+
+thread_local! { static MOVE_LEFT_STRING_NAME: StringName = StringName::from("MoveLeft"); }
+/// Maps to: `A`
+pub fn MOVE_LEFT() -> StringName { MOVE_LEFT_STRING_NAME.with(StringName::clone) }
+/// Maps to: `A`
+pub const MOVE_LEFT_STR: &'static str = "MoveLeft";"#
+    );
+}
+
+fn get_invocations_file_content(trait_defs: &str, impl_defs: &str, strict_lints: bool) -> String {
     format!(
-        "#![allow(dead_code)]\nuse godot::classes::Input;\n\npub trait InputActionInvocations {{\n{}\n}}\n\nimpl InputActionInvocations for Input {{\n{}\n}}",
-        trait_defs, impl_defs
+        "{}use godot::classes::Input;\n\npub trait InputActionInvocations {{\n{}\n}}\n\nimpl InputActionInvocations for Input {{\n{}\n}}",
+        format_lint_allows(strict_lints, false),
+        trait_defs,
+        impl_defs
     )
 }
 #[test]
@@ -198,47 +1159,76 @@ fn test_get_invocations_file_content() {
     assert_eq!(
         get_invocations_file_content(
             "    /// Returns true while left_click is pressed\n    fn is_fire_pressed(&self) -> bool;",
-            "    fn is_fire_pressed(&self) -> bool { self.is_action_pressed(\"Fire\") }"
+            "    fn is_fire_pressed(&self) -> bool { self.is_action_pressed(\"Fire\") }",
+            false
         ),
         "#![allow(dead_code)]\nuse godot::classes::Input;\n\npub trait InputActionInvocations {\n    /// Returns true while left_click is pressed\n    fn is_fire_pressed(&self) -> bool;\n}\n\nimpl InputActionInvocations for Input {\n    fn is_fire_pressed(&self) -> bool { self.is_action_pressed(\"Fire\") }\n}"
     );
 }
 
-fn join_keystrokes(keystrokes: &Vec<String>) -> String {
-    keystrokes.join("` or `")
+fn join_keystrokes(keystrokes: &[KeyCombo]) -> String {
+    keystrokes
+        .iter()
+        .map(|k| k.to_string_with_device())
+        .collect::<Vec<String>>()
+        .join("` or `")
 }
 #[test]
 fn test_join_keystrokes() {
-    assert_eq!(join_keystrokes(&vec!["Ctrl+A".into()]), "Ctrl+A");
+    use crate::projectgodot::Modifier;
+
+    assert_eq!(
+        join_keystrokes(&[KeyCombo::new(&[Modifier::Ctrl], "A")]),
+        "ctrl+A"
+    );
     assert_eq!(
-        join_keystrokes(&vec!["left_click".into(), "mouse_left".into()]),
+        join_keystrokes(&[
+            KeyCombo::new(&[], "left_click"),
+            KeyCombo::new(&[], "mouse_left")
+        ]),
         "left_click` or `mouse_left"
     );
 }
 
-fn format_action_to_invocation_trait(action: &str, keystrokes: &Vec<String>) -> String {
+fn format_action_to_invocation_trait(
+    action: &str,
+    keystrokes: &[KeyCombo],
+    deadzone: Option<f32>,
+) -> String {
     let sc = pascal_to_snake_case(action);
-    let joined_keystrokes = join_keystrokes(keystrokes);
-    let conjunction = if keystrokes[0].contains("+") {
+    // an action without resolvable keystrokes, e.g. only joypad events, is described by its name instead
+    let subject = if keystrokes.is_empty() {
+        format!("the `{}` action {}", action, NO_RESOLVABLE_BINDING)
+    } else {
+        format!("`{}`", join_keystrokes(keystrokes))
+    };
+    // each keystroke is an alternative, so "are" is only used when a keystroke is itself a combination of keys
+    let conjunction = if keystrokes.iter().any(|k| k.has_modifiers()) {
         "are"
     } else {
         "is"
     };
+    // the deadzone only changes when an analog input counts as pressed if it isn't the default
+    let deadzone_doc = deadzone
+        .filter(|deadzone| *deadzone != DEFAULT_DEADZONE)
+        .map_or(String::new(), |deadzone| {
+            format!("\n    ///\n    /// deadzone: {}", deadzone)
+        });
 
     vec![
         format!(
-            "    /// Returns true while `{}` {} pressed",
-            joined_keystrokes, conjunction
+            "    /// Returns true while {} {} pressed{}",
+            subject, conjunction, deadzone_doc
         ),
         format!("fn is_{}_pressed(&self) -> bool;", sc),
         format!(
-            "/// Returns true when `{}` {} just pressed",
-            joined_keystrokes, conjunction
+            "/// Returns true when {} {} just pressed{}",
+            subject, conjunction, deadzone_doc
         ),
         format!("fn is_{}_just_pressed(&self) -> bool;", sc),
         format!(
-            "/// Returns true when `{}` {} just released",
-            joined_keystrokes, conjunction
+            "/// Returns true when {} {} just released{}",
+            subject, conjunction, deadzone_doc
         ),
         format!("fn is_{}_just_released(&self) -> bool;", sc),
     ]
@@ -246,17 +1236,106 @@ fn format_action_to_invocation_trait(action: &str, keystrokes: &Vec<String>) ->
 }
 #[test]
 fn test_format_action_to_invocation_trait() {
+    use crate::projectgodot::Modifier;
+
     assert_eq!(
-        format_action_to_invocation_trait("Fire", &vec!["left_click".into()]),
+        format_action_to_invocation_trait("Fire", &[KeyCombo::new(&[], "left_click")], None),
         "    /// Returns true while `left_click` is pressed\n    fn is_fire_pressed(&self) -> bool;\n    /// Returns true when `left_click` is just pressed\n    fn is_fire_just_pressed(&self) -> bool;\n    /// Returns true when `left_click` is just released\n    fn is_fire_just_released(&self) -> bool;"
     );
     assert_eq!(
-        format_action_to_invocation_trait("CtrlA", &vec!["Ctrl+A".into()]),
-        "    /// Returns true while `Ctrl+A` are pressed\n    fn is_ctrl_a_pressed(&self) -> bool;\n    /// Returns true when `Ctrl+A` are just pressed\n    fn is_ctrl_a_just_pressed(&self) -> bool;\n    /// Returns true when `Ctrl+A` are just released\n    fn is_ctrl_a_just_released(&self) -> bool;"
+        format_action_to_invocation_trait("CtrlA", &[KeyCombo::new(&[Modifier::Ctrl], "A")], None),
+        "    /// Returns true while `ctrl+A` are pressed\n    fn is_ctrl_a_pressed(&self) -> bool;\n    /// Returns true when `ctrl+A` are just pressed\n    fn is_ctrl_a_just_pressed(&self) -> bool;\n    /// Returns true when `ctrl+A` are just released\n    fn is_ctrl_a_just_released(&self) -> bool;"
+    );
+    assert_eq!(
+        format_action_to_invocation_trait(
+            "MultiKey",
+            &[
+                KeyCombo::new(&[Modifier::Shift, Modifier::Ctrl, Modifier::Alt], "X"),
+                KeyCombo::new(&[], "Y")
+            ],
+            None
+        ),
+        "    /// Returns true while `ctrl+alt+shift+X` or `Y` are pressed\n    fn is_multi_key_pressed(&self) -> bool;\n    /// Returns true when `ctrl+alt+shift+X` or `Y` are just pressed\n    fn is_multi_key_just_pressed(&self) -> bool;\n    /// Returns true when `ctrl+alt+shift+X` or `Y` are just released\n    fn is_multi_key_just_released(&self) -> bool;"
+    );
+    assert_eq!(
+        format_action_to_invocation_trait("Plus", &[KeyCombo::new(&[], "+")], None),
+        "    /// Returns true while `+` is pressed\n    fn is_plus_pressed(&self) -> bool;\n    /// Returns true when `+` is just pressed\n    fn is_plus_just_pressed(&self) -> bool;\n    /// Returns true when `+` is just released\n    fn is_plus_just_released(&self) -> bool;"
+    );
+}
+#[test]
+fn test_format_action_to_invocation_trait_deadzone() {
+    assert_eq!(
+        format_action_to_invocation_trait("Steer", &[KeyCombo::new(&[], "A")], Some(0.2)),
+        "    /// Returns true while `A` is pressed\n    ///\n    /// deadzone: 0.2\n    fn is_steer_pressed(&self) -> bool;\n    /// Returns true when `A` is just pressed\n    ///\n    /// deadzone: 0.2\n    fn is_steer_just_pressed(&self) -> bool;\n    /// Returns true when `A` is just released\n    ///\n    /// deadzone: 0.2\n    fn is_steer_just_released(&self) -> bool;"
+    );
+    // the default deadzone is omitted
+    assert_eq!(
+        format_action_to_invocation_trait("Steer", &[KeyCombo::new(&[], "A")], Some(0.5)),
+        format_action_to_invocation_trait("Steer", &[KeyCombo::new(&[], "A")], None)
+    );
+}
+#[test]
+fn test_format_action_to_invocation_trait_empty() {
+    assert_eq!(
+        format_action_to_invocation_trait("Fire", &[], None),
+        "    /// Returns true while the `Fire` action (no resolvable binding) is pressed\n    fn is_fire_pressed(&self) -> bool;\n    /// Returns true when the `Fire` action (no resolvable binding) is just pressed\n    fn is_fire_just_pressed(&self) -> bool;\n    /// Returns true when the `Fire` action (no resolvable binding) is just released\n    fn is_fire_just_released(&self) -> bool;"
+    );
+}
+#[test]
+fn test_format_action_with_unsupported_event() {
+    let project = ProjectGodot::parse_from_str(
+        r#"[input]
+
+Accept={
+"deadzone": 0.5,
+"events": [Object(InputEventJoypadButton,"resource_local_to_scene":false,"resource_name":"","device":-1,"button_index":0,"pressure":0.0,"pressed":false,"script":null)
+]
+}
+"#,
+    );
+    let actions = resolve_actions(project.input.as_ref().unwrap());
+    assert_eq!(actions, vec![("Accept", vec![])]);
+
+    let (action, keystrokes) = &actions[0];
+    assert_eq!(
+        format_action_to_const(
+            action,
+            &get_action_keystroke_doc_comment(keystrokes),
+            false,
+            false,
+            "",
+            false
+        ),
+        "/// (no resolvable binding)\npub fn ACCEPT() -> StringName { StringName::from(\"Accept\") }\n/// (no resolvable binding)\npub const ACCEPT_STR: &'static str = \"Accept\";"
+    );
+    assert!(
+        format_action_to_invocation_trait(action, keystrokes, None)
+            .contains("fn is_accept_just_released(&self) -> bool;")
+    );
+}
+#[test]
+fn test_format_action_to_invocation_trait_mixed() {
+    use crate::projectgodot::Modifier;
+
+    // the combo is not the first keystroke, but still makes the keystrokes plural
+    assert_eq!(
+        format_action_to_invocation_trait(
+            "Save",
+            &[
+                KeyCombo::new(&[], "F5"),
+                KeyCombo::new(&[Modifier::Ctrl], "S")
+            ],
+            None
+        ),
+        "    /// Returns true while `F5` or `ctrl+S` are pressed\n    fn is_save_pressed(&self) -> bool;\n    /// Returns true when `F5` or `ctrl+S` are just pressed\n    fn is_save_just_pressed(&self) -> bool;\n    /// Returns true when `F5` or `ctrl+S` are just released\n    fn is_save_just_released(&self) -> bool;"
     );
     assert_eq!(
-        format_action_to_invocation_trait("MultiKey", &vec!["Shift+Ctrl+Alt+X".into(), "Y".into()]),
-        "    /// Returns true while `Shift+Ctrl+Alt+X` or `Y` are pressed\n    fn is_multi_key_pressed(&self) -> bool;\n    /// Returns true when `Shift+Ctrl+Alt+X` or `Y` are just pressed\n    fn is_multi_key_just_pressed(&self) -> bool;\n    /// Returns true when `Shift+Ctrl+Alt+X` or `Y` are just released\n    fn is_multi_key_just_released(&self) -> bool;"
+        format_action_to_invocation_trait(
+            "Jump",
+            &[KeyCombo::new(&[], "SPACE"), KeyCombo::new(&[], "W")],
+            None
+        ),
+        "    /// Returns true while `SPACE` or `W` is pressed\n    fn is_jump_pressed(&self) -> bool;\n    /// Returns true when `SPACE` or `W` is just pressed\n    fn is_jump_just_pressed(&self) -> bool;\n    /// Returns true when `SPACE` or `W` is just released\n    fn is_jump_just_released(&self) -> bool;"
     );
 }
 
@@ -286,3 +1365,371 @@ fn test_format_action_to_invocation_impl() {
         "    fn is_fire_pressed(&self) -> bool { self.is_action_pressed(\"Fire\") }\n    fn is_fire_just_pressed(&self) -> bool { self.is_action_just_pressed(\"Fire\") }\n    fn is_fire_just_released(&self) -> bool { self.is_action_just_released(\"Fire\") }"
     );
 }
+
+fn format_any_action_trait() -> String {
+    [
+        "    /// Returns true when any of the actions is just pressed",
+        "fn any_action_just_pressed(&self) -> bool;",
+        "/// Returns the names of the actions currently pressed",
+        "fn pressed_actions(&self) -> Vec<&'static str>;",
+    ]
+    .join("\n    ")
+}
+#[test]
+fn test_format_any_action_trait() {
+    assert_eq!(
+        format_any_action_trait(),
+        "    /// Returns true when any of the actions is just pressed\n    fn any_action_just_pressed(&self) -> bool;\n    /// Returns the names of the actions currently pressed\n    fn pressed_actions(&self) -> Vec<&'static str>;"
+    );
+}
+
+fn format_any_action_impl(actions: &[&str]) -> String {
+    let any_just_pressed = if actions.is_empty() {
+        "false".to_string()
+    } else {
+        actions
+            .iter()
+            .map(|action| format!("self.is_action_just_pressed(\"{}\")", action))
+            .collect::<Vec<String>>()
+            .join("\n            || ")
+    };
+    let names = actions
+        .iter()
+        .map(|action| format!("\"{}\"", action))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        r#"    fn any_action_just_pressed(&self) -> bool {{
+        {}
+    }}
+    fn pressed_actions(&self) -> Vec<&'static str> {{
+        [{}]
+            .into_iter()
+            .filter(|action| self.is_action_pressed(*action))
+            .collect()
+    }}"#,
+        any_just_pressed, names
+    )
+}
+#[test]
+fn test_format_any_action_impl() {
+    let actions = ["Fire", "Jump", "MoveLeft"];
+    let generated = format_any_action_impl(&actions);
+
+    for action in actions {
+        assert!(generated.contains(&format!("self.is_action_just_pressed(\"{}\")", action)));
+    }
+    assert_eq!(
+        generated,
+        r#"    fn any_action_just_pressed(&self) -> bool {
+        self.is_action_just_pressed("Fire")
+            || self.is_action_just_pressed("Jump")
+            || self.is_action_just_pressed("MoveLeft")
+    }
+    fn pressed_actions(&self) -> Vec<&'static str> {
+        ["Fire", "Jump", "MoveLeft"]
+            .into_iter()
+            .filter(|action| self.is_action_pressed(*action))
+            .collect()
+    }"#
+    );
+}
+
+fn get_registration_file_content(registrations: &str, strict_lints: bool) -> String {
+    format!(
+        r#"{}{}
+/// Adds each action from `project.godot` which is missing from the `InputMap`, along with its keyboard and mouse button events.
+pub fn register_all_actions() {{
+    let mut input_map = InputMap::singleton();
+
+{}
+}}"#,
+        format_lint_allows(strict_lints, false),
+        format_input_map_imports(registrations),
+        registrations
+    )
+}
+#[test]
+fn test_get_registration_file_content() {
+    assert_eq!(
+        get_registration_file_content("    // registrations", false),
+        r#"#![allow(dead_code)]
+use godot::classes::InputMap;
+use godot::obj::Singleton;
+
+/// Adds each action from `project.godot` which is missing from the `InputMap`, along with its keyboard and mouse button events.
+pub fn register_all_actions() {
+    let mut input_map = InputMap::singleton();
+
+    // registrations
+}"#
+    );
+}
+
+fn format_action_to_registration(input: &Input) -> String {
+    let mut lines = vec![format!("input_map.add_action(\"{}\");", input.name)];
+    lines.extend(format_action_to_event_lines(input));
+
+    format!(
+        "    if !input_map.has_action(\"{}\") {{\n{}\n    }}",
+        input.name,
+        lines
+            .iter()
+            .map(|l| format!("        {}", l))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+#[test]
+fn test_format_action_to_registration() {
+    let project = ProjectGodot::parse_from_str(
+        r#"config_version=5
+
+[input]
+
+Fire={
+"deadzone": 0.5,
+"events": [Object(InputEventMouseButton,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"button_mask":0,"position":Vector2(0, 0),"global_position":Vector2(0, 0),"factor":1.0,"button_index":1,"canceled":false,"pressed":false,"double_click":false,"script":null),Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":true,"meta_pressed":false,"pressed":false,"keycode":0,"physical_keycode":74,"key_label":0,"unicode":106,"location":0,"echo":false,"script":null)
+]
+}
+"#,
+    );
+    let input = project.input.as_ref().unwrap().inputs.get("Fire").unwrap();
+
+    assert_eq!(
+        format_action_to_registration(input),
+        r#"    if !input_map.has_action("Fire") {
+        input_map.add_action("Fire");
+        input_map.action_set_deadzone("Fire", 0.5);
+        let mut event = InputEventMouseButton::new_gd();
+        event.set_device(-1);
+        event.set_button_index(MouseButton::from_ord(1));
+        input_map.action_add_event("Fire", &event);
+        let mut event = InputEventKey::new_gd();
+        event.set_device(-1);
+        event.set_ctrl_pressed(true);
+        event.set_physical_keycode(Key::from_ord(74));
+        event.set_unicode(106);
+        input_map.action_add_event("Fire", &event);
+    }"#
+    );
+}
+
+/// Formats the imports used by the generated `InputMap` code in `body`, e.g. leaving out `InputEventMouseButton` and
+/// `MouseButton` when no action has a mouse button event.
+fn format_input_map_imports(body: &str) -> String {
+    let mut classes = ["InputEventKey", "InputEventMouseButton"]
+        .into_iter()
+        .filter(|class| body.contains(&format!("{}::new_gd()", class)))
+        .collect::<Vec<&str>>();
+    let mut objects = vec![];
+    if !classes.is_empty() {
+        objects.push("NewGd");
+    }
+    classes.push("InputMap");
+
+    let globals = ["Key", "MouseButton"]
+        .into_iter()
+        .filter(|global| body.contains(&format!("({}::from_ord(", global)))
+        .collect::<Vec<&str>>();
+    if !globals.is_empty() {
+        objects.insert(0, "EngineEnum");
+    }
+    objects.push("Singleton");
+
+    [("classes", classes), ("global", globals), ("obj", objects)]
+        .iter()
+        .map(|(module, imports)| match imports.as_slice() {
+            [] => String::new(),
+            [import] => format!("use godot::{}::{};\n", module, import),
+            _ => format!("use godot::{}::{{{}}};\n", module, imports.join(", ")),
+        })
+        .collect()
+}
+#[test]
+fn test_format_input_map_imports() {
+    assert_eq!(
+        format_input_map_imports(""),
+        "use godot::classes::InputMap;\nuse godot::obj::Singleton;\n"
+    );
+    assert_eq!(
+        format_input_map_imports(
+            "let mut event = InputEventKey::new_gd();\nevent.set_keycode(Key::from_ord(65));"
+        ),
+        "use godot::classes::{InputEventKey, InputMap};\nuse godot::global::Key;\nuse godot::obj::{EngineEnum, NewGd, Singleton};\n"
+    );
+    assert_eq!(
+        format_input_map_imports(
+            "let mut event = InputEventMouseButton::new_gd();\nevent.set_button_index(MouseButton::from_ord(1));\nlet mut event = InputEventKey::new_gd();\nevent.set_keycode(Key::from_ord(65));"
+        ),
+        "use godot::classes::{InputEventKey, InputEventMouseButton, InputMap};\nuse godot::global::{Key, MouseButton};\nuse godot::obj::{EngineEnum, NewGd, Singleton};\n"
+    );
+}
+
+/// Returns the lines setting the deadzone of an action and adding each of its supported events to the `InputMap`, the
+/// unsupported events are reported once by [`unsupported_event_lines`].
+fn format_action_to_event_lines(input: &Input) -> Vec<String> {
+    let mut lines = vec![];
+
+    if let Some(deadzone) = input.deadzone {
+        lines.push(format!(
+            "input_map.action_set_deadzone(\"{}\", {:?});",
+            input.name, deadzone
+        ));
+    }
+
+    for event_lines in input.events.iter().filter_map(format_event_to_registration) {
+        lines.extend(event_lines);
+        lines.push(format!(
+            "input_map.action_add_event(\"{}\", &event);",
+            input.name
+        ));
+    }
+
+    lines
+}
+
+fn get_default_bindings_file_content(resets: &str, strict_lints: bool) -> String {
+    format!(
+        r#"{}{}
+/// Restores the keyboard and mouse button events of each action to its defaults from `project.godot`, adding any missing actions.
+///
+/// Other events of the actions, e.g. joypad events, are erased and not restored.
+pub fn restore_default_bindings() {{
+    let mut input_map = InputMap::singleton();
+
+{}
+}}"#,
+        format_lint_allows(strict_lints, false),
+        format_input_map_imports(resets),
+        resets
+    )
+}
+
+fn format_action_to_default_bindings(input: &Input) -> String {
+    let mut lines = vec![
+        format!("if !input_map.has_action(\"{}\") {{", input.name),
+        format!("    input_map.add_action(\"{}\");", input.name),
+        "}".to_string(),
+        format!("input_map.action_erase_events(\"{}\");", input.name),
+    ];
+    lines.extend(format_action_to_event_lines(input));
+
+    lines
+        .iter()
+        .map(|l| format!("    {}", l))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+#[test]
+fn test_format_action_to_default_bindings() {
+    let project = ProjectGodot::parse_from_str(
+        r#"config_version=5
+
+[input]
+
+Jump={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":0,"physical_keycode":32,"key_label":0,"unicode":32,"location":0,"echo":false,"script":null)
+]
+}
+"#,
+    );
+    let input = project.input.as_ref().unwrap().inputs.get("Jump").unwrap();
+
+    assert_eq!(
+        format_action_to_default_bindings(input),
+        r#"    if !input_map.has_action("Jump") {
+        input_map.add_action("Jump");
+    }
+    input_map.action_erase_events("Jump");
+    input_map.action_set_deadzone("Jump", 0.5);
+    let mut event = InputEventKey::new_gd();
+    event.set_device(-1);
+    event.set_physical_keycode(Key::from_ord(32));
+    event.set_unicode(32);
+    input_map.action_add_event("Jump", &event);"#
+    );
+    assert!(
+        get_default_bindings_file_content(&format_action_to_default_bindings(input), true)
+            .contains("pub fn restore_default_bindings() {\n    let mut input_map = InputMap::singleton();\n\n    if !input_map.has_action(\"Jump\") {")
+    );
+}
+
+/// Returns the lines creating an `event` variable equivalent to the parsed event, if the event type is supported.
+fn format_event_to_registration(event: &InputEvent) -> Option<Vec<String>> {
+    let mut lines = match event.event_type.as_str() {
+        "InputEventKey" => vec!["let mut event = InputEventKey::new_gd();".to_string()],
+        "InputEventMouseButton" => {
+            vec!["let mut event = InputEventMouseButton::new_gd();".to_string()]
+        }
+        _ => return None,
+    };
+
+    lines.push(format!(
+        "event.set_device({});",
+        event.int_properties.get("device").copied().unwrap_or(-1)
+    ));
+
+    if let Some(combo) = event.get_key_combo() {
+        for modifier in Modifier::CANONICAL_ORDER {
+            if combo.modifiers.contains(&modifier) {
+                lines.push(format!("event.set_{}_pressed(true);", modifier.as_str()));
+            }
+        }
+    }
+
+    let int_property = |name: &str| {
+        event
+            .int_properties
+            .get(name)
+            .copied()
+            .filter(|value| *value != 0)
+    };
+
+    if event.event_type == "InputEventKey" {
+        if let Some(keycode) = int_property("keycode") {
+            lines.push(format!("event.set_keycode(Key::from_ord({}));", keycode));
+        }
+        if let Some(physical_keycode) = int_property("physical_keycode") {
+            lines.push(format!(
+                "event.set_physical_keycode(Key::from_ord({}));",
+                physical_keycode
+            ));
+        }
+        if let Some(unicode) = int_property("unicode") {
+            lines.push(format!("event.set_unicode({});", unicode));
+        }
+    } else {
+        if let Some(button_index) = int_property("button_index") {
+            lines.push(format!(
+                "event.set_button_index(MouseButton::from_ord({}));",
+                button_index
+            ));
+        }
+        if event
+            .bool_properties
+            .get("double_click")
+            .copied()
+            .unwrap_or(false)
+        {
+            lines.push("event.set_double_click(true);".to_string());
+        }
+    }
+
+    Some(lines)
+}
+#[test]
+fn test_format_event_to_registration_unsupported() {
+    let event = InputEvent {
+        event_type: "InputEventJoypadButton".to_string(),
+        str_properties: Default::default(),
+        bool_properties: Default::default(),
+        int_properties: Default::default(),
+        float_properties: Default::default(),
+        vec2_properties: Default::default(),
+    };
+
+    assert_eq!(format_event_to_registration(&event), None);
+}
@@ -1,32 +1,35 @@
-use std::{collections::HashMap, fs, io::Write, path::Path};
+use std::{collections::BTreeMap, io, path::Path};
 
-use crate::{projectgodot::ProjectGodot, utils::to_upper_camel_case};
+use crate::{
+    mod_file::GeneratedModule,
+    projectgodot::ProjectGodot,
+    utils::{format_item_allow, format_lint_allows, to_upper_camel_case, write_generated_file},
+};
 
 const MOD_LAYERS: &str = "layer_consts";
+/// The number of layers in a layer bitfield.
+const LAYER_COUNT: i32 = 32;
 
-pub fn generate_layers_consts(output_dir: &str, godot_project: &ProjectGodot) -> Vec<String> {
-    if !Path::new(output_dir).exists() {
-        fs::create_dir_all(output_dir).unwrap();
-    }
-
-    if !godot_project.layer_names.is_some()
-        || godot_project
-            .layer_names
-            .as_ref()
-            .unwrap()
-            .layers
-            .is_empty()
-    {
+pub fn generate_layers_consts(
+    output_dir: &str,
+    godot_project: &ProjectGodot,
+    fill_unnamed: bool,
+    as_masks: bool,
+    const_prefix: &str,
+    strict_lints: bool,
+) -> io::Result<Vec<GeneratedModule>> {
+    let Some(layer_names) = godot_project
+        .layer_names
+        .as_ref()
+        .filter(|layer_names| !layer_names.layers.is_empty())
+    else {
         println!(
-            "cargo::warning=No layer names found in project.godot, skipping layers.rs generation"
+            "cargo::warning=No layer names found in the project settings, skipping layers.rs generation"
         );
-        return vec![];
-    }
+        return Ok(vec![]);
+    };
 
-    let mut layers = godot_project
-        .layer_names
-        .as_ref()
-        .unwrap()
+    let layers = layer_names
         .layers
         .iter()
         .map(|l| extract_group_data(l.0, l.1))
@@ -34,68 +37,217 @@ pub fn generate_layers_consts(output_dir: &str, godot_project: &ProjectGodot) ->
         .map(|l| l.unwrap())
         .collect::<Vec<(String, i32, String)>>();
 
-    // sort by group name, then by layer number
-    layers.sort_by(|a, b| {
-        if a.0 == b.0 {
-            a.1.cmp(&b.1)
-        } else {
-            a.0.cmp(&b.0)
-        }
-    });
-
-    let layers_by_group: HashMap<String, Vec<(i32, String)>> = {
-        let mut map: HashMap<String, Vec<(i32, String)>> = HashMap::new();
-        for (group, number, name) in layers {
-            map.entry(group)
-                .or_insert_with(Vec::new)
-                .push((number, name));
-        }
-        map
-    };
-
-    let mut rendered_groups = layers_by_group
+    let rendered_groups = group_layers(layers, fill_unnamed)
         .iter()
-        .map(|(group, layers)| format_group_to_enum(group, layers))
+        .map(|(group, layers)| {
+            if as_masks {
+                format_group_to_mask(group, layers, const_prefix)
+            } else {
+                format_group_to_enum(group, layers, const_prefix, strict_lints)
+            }
+        })
         .collect::<Vec<String>>();
 
-    rendered_groups.sort();
-
-    let output_lines = format!("#![allow(dead_code)]\n\n{}", rendered_groups.join("\n"));
+    let output_lines = format!(
+        "{}\n{}",
+        format_lint_allows(strict_lints, false),
+        rendered_groups.join("\n")
+    );
 
     let layers_path = Path::new(output_dir).join(format!("{}.rs", MOD_LAYERS));
 
-    let mut file = fs::File::create(layers_path).unwrap();
-    file.write_all(output_lines.as_bytes()).unwrap();
+    write_generated_file(&layers_path, &output_lines)?;
+
+    Ok(vec![GeneratedModule {
+        name: MOD_LAYERS.to_string(),
+        path: layers_path,
+    }])
+}
+
+/// Groups `(group, number, name)` layers by group name, sorting each group's layers by number.
+///
+/// The order is decided here rather than by the rendered enums, so adding a layer doesn't reorder the other groups.
+fn group_layers(
+    layers: Vec<(String, i32, String)>,
+    fill_unnamed: bool,
+) -> BTreeMap<String, Vec<(i32, String)>> {
+    let mut layers_by_group: BTreeMap<String, Vec<(i32, String)>> = BTreeMap::new();
+    for (group, number, name) in layers {
+        layers_by_group
+            .entry(group)
+            .or_default()
+            .push((number, name));
+    }
+
+    for layers in layers_by_group.values_mut() {
+        layers.sort_by_key(|(number, _)| *number);
+        if fill_unnamed {
+            fill_unnamed_layers(layers);
+        }
+    }
+    layers_by_group
+}
+#[test]
+fn test_group_layers() {
+    let layers = vec![
+        ("Render2d".to_string(), 3, "ghosts".to_string()),
+        ("Physics2d".to_string(), 2, "layer two".to_string()),
+        ("Render2d".to_string(), 1, "walls".to_string()),
+        ("Physics2d".to_string(), 1, "player".to_string()),
+        ("Avoidance".to_string(), 1, "agents".to_string()),
+    ];
+
+    let grouped = group_layers(layers, false);
 
-    vec![MOD_LAYERS.to_string()]
+    assert_eq!(
+        grouped.keys().collect::<Vec<&String>>(),
+        ["Avoidance", "Physics2d", "Render2d"]
+    );
+    assert_eq!(
+        grouped["Physics2d"],
+        [(1, "player".to_string()), (2, "layer two".to_string())]
+    );
+    assert_eq!(
+        grouped["Render2d"],
+        [(1, "walls".to_string()), (3, "ghosts".to_string())]
+    );
 }
 
-/// Formats a group of layers into a Rust enum string.
+/// Adds a `LAYER_<n>` placeholder for each layer number in `1..=32` without a name, keeping the layers sorted by number.
+///
+/// If another layer is already named `layer_<n>`, the placeholder is `UNNAMED_LAYER_<n>` instead, so every number is filled
+/// without a duplicate variant.
+fn fill_unnamed_layers(layers: &mut Vec<(i32, String)>) {
+    for number in 1..=LAYER_COUNT {
+        if layers.iter().any(|(n, _)| *n == number) {
+            continue;
+        }
+
+        let placeholder = format!("LAYER_{}", number);
+        let placeholder = if layers
+            .iter()
+            .any(|(_, name)| name.to_uppercase().replace(" ", "_") == placeholder)
+        {
+            format!("UNNAMED_{}", placeholder)
+        } else {
+            placeholder
+        };
+        layers.push((number, placeholder));
+    }
+    layers.sort_by_key(|(number, _)| *number);
+}
+#[test]
+fn test_fill_unnamed_layers() {
+    let mut layers = vec![(5, "walls".to_string()), (1, "player".to_string())];
+    fill_unnamed_layers(&mut layers);
+
+    assert_eq!(layers.len(), 32);
+    assert_eq!(
+        layers[..6],
+        [
+            (1, "player".to_string()),
+            (2, "LAYER_2".to_string()),
+            (3, "LAYER_3".to_string()),
+            (4, "LAYER_4".to_string()),
+            (5, "walls".to_string()),
+            (6, "LAYER_6".to_string()),
+        ]
+    );
+    assert_eq!(layers[31], (32, "LAYER_32".to_string()));
+
+    let mut layers = vec![(1, "layer_2".to_string())];
+    fill_unnamed_layers(&mut layers);
+    assert_eq!(layers.len(), 32);
+    assert_eq!(layers[1], (2, "UNNAMED_LAYER_2".to_string()));
+}
+
+/// Formats a group of layers into a Rust enum string, with a `Display` impl, `from_bit` and `from_bits` constructors, and
+/// conversions into `u32` and `i64` masks.
+///
+/// The enum is `#[repr(u32)]`, so the 32nd layer (`1 << 31`) is a valid discriminant on every target.
 ///
 /// e.g. for group `"Physics2D"` and layers `[(1, "Layer1"), (2, "Layer2")]`, it returns:
 ///
-/// ```no_run
+/// ```
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// #[repr(u32)]
 /// pub enum Physics2D {
 ///   LAYER1 = 1,
 ///   LAYER2 = 2,
 /// }
+///
+/// impl std::fmt::Display for Physics2D {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         match self {
+///             Self::LAYER1 => write!(f, "LAYER1"),
+///             Self::LAYER2 => write!(f, "LAYER2"),
+///         }
+///     }
+/// }
+///
+/// impl Physics2D {
+///     /// Returns the layer for a single mask bit, e.g. `2` for the second layer.
+///     pub fn from_bit(value: u32) -> Option<Self> {
+///         match value {
+///             1 => Some(Self::LAYER1),
+///             2 => Some(Self::LAYER2),
+///             _ => None,
+///         }
+///     }
+///
+///     /// Returns the layers set in a mask, e.g. both layers for `3`.
+///     pub fn from_bits(value: u32) -> Vec<Self> {
+///         [Self::LAYER1, Self::LAYER2]
+///             .into_iter()
+///             .filter(|layer| value & *layer as u32 != 0)
+///             .collect()
+///     }
+/// }
+///
+/// impl From<Physics2D> for u32 {
+///     fn from(layer: Physics2D) -> Self {
+///         layer as u32
+///     }
+/// }
+///
+/// impl From<Physics2D> for i64 {
+///     fn from(layer: Physics2D) -> Self {
+///         i64::from(layer as u32)
+///     }
+/// }
+/// # assert_eq!(Physics2D::from_bit(2), Some(Physics2D::LAYER2));
+/// # assert_eq!(Physics2D::from_bits(3), vec![Physics2D::LAYER1, Physics2D::LAYER2]);
+/// # assert_eq!(u32::from(Physics2D::LAYER1) | u32::from(Physics2D::LAYER2), 3);
+/// # assert_eq!(i64::from(Physics2D::LAYER1) | i64::from(Physics2D::LAYER2), 3);
 /// ```
-fn format_group_to_enum(group: &str, layers: &Vec<(i32, String)>) -> String {
+fn format_group_to_enum(
+    group: &str,
+    layers: &[(i32, String)],
+    const_prefix: &str,
+    strict_lints: bool,
+) -> String {
+    let variants = group_variants(layers, const_prefix);
+
+    // `UPPER_SNAKE_CASE` variants are not camel case, e.g. `LAYER_2`
+    let lint_allow = if variants.iter().any(|(variant, _)| variant.contains('_')) {
+        format_item_allow(strict_lints, "non_camel_case_types")
+    } else {
+        String::new()
+    };
+
     let mut enum_str = format!(
-        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum {} {{\n",
-        group
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n{}#[repr(u32)]\npub enum {} {{\n",
+        lint_allow, group
     );
 
-    for (number, name) in layers {
-        enum_str.push_str(&format!(
-            "    {} = {},\n",
-            name.to_uppercase().replace(" ", "_"),
-            1 << (number - 1)
-        ));
+    for (variant, bit) in &variants {
+        enum_str.push_str(&format!("    {} = {},\n", variant, bit));
     }
 
     enum_str.push_str("}\n");
+    enum_str.push_str(&format_group_to_display_impl(group, &variants));
+    enum_str.push_str(&format_group_to_from_bit_impl(group, &variants));
+    enum_str.push_str(&format_group_to_from_impls(group));
     enum_str
 }
 
@@ -104,12 +256,348 @@ fn test_format_group_to_enum() {
     let group = "Physics2D";
     let layers = vec![(1, "Layer1".to_string()), (2, "Layer2".to_string())];
     let expected = r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
 pub enum Physics2D {
     LAYER1 = 1,
     LAYER2 = 2,
 }
+
+impl std::fmt::Display for Physics2D {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LAYER1 => write!(f, "LAYER1"),
+            Self::LAYER2 => write!(f, "LAYER2"),
+        }
+    }
+}
+
+impl Physics2D {
+    /// Returns the layer for a single mask bit, e.g. `2` for the second layer.
+    pub fn from_bit(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(Self::LAYER1),
+            2 => Some(Self::LAYER2),
+            _ => None,
+        }
+    }
+
+    /// Returns the layers set in a mask, e.g. both layers for `3`.
+    pub fn from_bits(value: u32) -> Vec<Self> {
+        [Self::LAYER1, Self::LAYER2]
+            .into_iter()
+            .filter(|layer| value & *layer as u32 != 0)
+            .collect()
+    }
+}
+
+impl From<Physics2D> for u32 {
+    fn from(layer: Physics2D) -> Self {
+        layer as u32
+    }
+}
+
+impl From<Physics2D> for i64 {
+    fn from(layer: Physics2D) -> Self {
+        i64::from(layer as u32)
+    }
+}
 "#;
-    assert_eq!(format_group_to_enum(group, &layers), expected);
+    assert_eq!(format_group_to_enum(group, &layers, "", false), expected);
+    assert_eq!(format_group_to_enum(group, &layers, "", true), expected);
+}
+#[test]
+fn test_format_group_to_enum_strict_lints() {
+    let layers = vec![(2, "player hitbox".to_string())];
+
+    assert!(
+        format_group_to_enum("Physics2D", &layers, "", false)
+            .starts_with("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n#[repr(u32)]\npub enum Physics2D {\n    PLAYER_HITBOX = 2,\n")
+    );
+    assert!(
+        format_group_to_enum("Physics2D", &layers, "", true)
+            .starts_with("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n#[allow(non_camel_case_types)]\n#[repr(u32)]\npub enum Physics2D {\n    PLAYER_HITBOX = 2,\n")
+    );
+}
+#[test]
+fn test_format_group_to_enum_prefix() {
+    let layers = vec![(1, "player".to_string()), (2, "enemy".to_string())];
+    let content = format_group_to_enum("Physics2D", &layers, "LAYER_", false);
+
+    assert!(
+        content.contains("pub enum Physics2D {\n    LAYER_PLAYER = 1,\n    LAYER_ENEMY = 2,\n}")
+    );
+    assert!(content.contains("            2 => Some(Self::LAYER_ENEMY),\n"));
+}
+
+fn format_group_to_display_impl(group: &str, variants: &[(String, u32)]) -> String {
+    format!(
+        "\nimpl std::fmt::Display for {} {{\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n        match self {{\n{}        }}\n    }}\n}}\n",
+        group,
+        variants
+            .iter()
+            .map(|(variant, _)| format!(
+                "            Self::{} => write!(f, \"{}\"),\n",
+                variant, variant
+            ))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_group_to_display_impl() {
+    assert_eq!(
+        format_group_to_display_impl("Render2d", &[("GHOSTS".to_string(), 1)]),
+        r#"
+impl std::fmt::Display for Render2d {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GHOSTS => write!(f, "GHOSTS"),
+        }
+    }
+}
+"#
+    );
+}
+
+fn format_group_to_from_bit_impl(group: &str, variants: &[(String, u32)]) -> String {
+    format!(
+        "\nimpl {} {{\n    /// Returns the layer for a single mask bit, e.g. `2` for the second layer.\n    pub fn from_bit(value: u32) -> Option<Self> {{\n        match value {{\n{}            _ => None,\n        }}\n    }}\n\n    /// Returns the layers set in a mask, e.g. both layers for `3`.\n    pub fn from_bits(value: u32) -> Vec<Self> {{\n        [{}]\n            .into_iter()\n            .filter(|layer| value & *layer as u32 != 0)\n            .collect()\n    }}\n}}\n",
+        group,
+        variants
+            .iter()
+            .map(|(variant, bit)| format!("            {} => Some(Self::{}),\n", bit, variant))
+            .collect::<String>(),
+        variants
+            .iter()
+            .map(|(variant, _)| format!("Self::{}", variant))
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+#[test]
+fn test_format_group_to_from_bit_impl() {
+    assert_eq!(
+        format_group_to_from_bit_impl("Render2d", &[("GHOSTS".to_string(), 4)]),
+        r#"
+impl Render2d {
+    /// Returns the layer for a single mask bit, e.g. `2` for the second layer.
+    pub fn from_bit(value: u32) -> Option<Self> {
+        match value {
+            4 => Some(Self::GHOSTS),
+            _ => None,
+        }
+    }
+
+    /// Returns the layers set in a mask, e.g. both layers for `3`.
+    pub fn from_bits(value: u32) -> Vec<Self> {
+        [Self::GHOSTS]
+            .into_iter()
+            .filter(|layer| value & *layer as u32 != 0)
+            .collect()
+    }
+}
+"#
+    );
+}
+
+/// Formats `From` impls converting a layer into its `u32` and `i64` mask bit, for godot APIs taking either.
+fn format_group_to_from_impls(group: &str) -> String {
+    format!(
+        "\nimpl From<{0}> for u32 {{\n    fn from(layer: {0}) -> Self {{\n        layer as u32\n    }}\n}}\n\nimpl From<{0}> for i64 {{\n    fn from(layer: {0}) -> Self {{\n        i64::from(layer as u32)\n    }}\n}}\n",
+        group
+    )
+}
+#[test]
+fn test_format_group_to_from_impls() {
+    let layers = vec![
+        (1, "walls".to_string()),
+        (2, "enemies".to_string()),
+        (32, "last".to_string()),
+    ];
+    let content = format_group_to_enum("Physics2D", &layers, "", false);
+
+    assert!(content.contains("    LAST = 2147483648,\n"));
+    assert!(content.contains("impl From<Physics2D> for u32 {\n    fn from(layer: Physics2D) -> Self {\n        layer as u32\n"));
+    assert!(content.contains("impl From<Physics2D> for i64 {\n    fn from(layer: Physics2D) -> Self {\n        i64::from(layer as u32)\n"));
+}
+
+/// Names each layer of a group as an `UPPER_SNAKE_CASE` variant, with its mask bit.
+fn group_variants(layers: &[(i32, String)], const_prefix: &str) -> Vec<(String, u32)> {
+    layers
+        .iter()
+        .map(|(number, name)| {
+            (
+                format!("{}{}", const_prefix, name.to_uppercase().replace(" ", "_")),
+                1u32 << (number - 1),
+            )
+        })
+        .collect()
+}
+
+/// Formats a group of layers into a `<Group>Mask` newtype over a `u32` mask, with an associated const for each layer, set
+/// operations, and conversions into `u32` and `i64` masks. Used instead of the enum by `layers_as_masks`.
+///
+/// e.g. for group `"Physics2D"` and layers `[(1, "Layer1"), (2, "Layer2")]`, it returns:
+///
+/// ```
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// pub struct Physics2DMask(pub u32);
+///
+/// impl Physics2DMask {
+///     pub const LAYER1: Self = Self(1);
+///     pub const LAYER2: Self = Self(2);
+///
+///     /// Returns the mask with no layers set.
+///     pub const fn empty() -> Self {
+///         Self(0)
+///     }
+///
+///     /// Sets the layers of `other` in the mask.
+///     pub fn insert(&mut self, other: Self) {
+///         self.0 |= other.0;
+///     }
+///
+///     /// Clears the layers of `other` from the mask.
+///     pub fn remove(&mut self, other: Self) {
+///         self.0 &= !other.0;
+///     }
+///
+///     /// Returns whether every layer of `other` is set in the mask.
+///     pub const fn contains(self, other: Self) -> bool {
+///         self.0 & other.0 == other.0
+///     }
+/// }
+///
+/// impl std::ops::BitOr for Physics2DMask {
+///     type Output = Self;
+///
+///     fn bitor(self, other: Self) -> Self {
+///         Self(self.0 | other.0)
+///     }
+/// }
+///
+/// impl std::ops::BitAnd for Physics2DMask {
+///     type Output = Self;
+///
+///     fn bitand(self, other: Self) -> Self {
+///         Self(self.0 & other.0)
+///     }
+/// }
+///
+/// impl From<Physics2DMask> for u32 {
+///     fn from(mask: Physics2DMask) -> Self {
+///         mask.0
+///     }
+/// }
+///
+/// impl From<Physics2DMask> for i64 {
+///     fn from(mask: Physics2DMask) -> Self {
+///         i64::from(mask.0)
+///     }
+/// }
+/// # let mut mask = Physics2DMask::LAYER1 | Physics2DMask::LAYER2;
+/// # assert_eq!(u32::from(mask), 3);
+/// # assert!(mask.contains(Physics2DMask::LAYER2));
+/// # mask.remove(Physics2DMask::LAYER2);
+/// # assert_eq!(mask, Physics2DMask::LAYER1);
+/// # assert!(!mask.contains(Physics2DMask::LAYER1 | Physics2DMask::LAYER2));
+/// # mask.insert(Physics2DMask::LAYER2);
+/// # assert_eq!(i64::from(mask & Physics2DMask::LAYER2), 2);
+/// # assert_eq!(Physics2DMask::empty(), Physics2DMask::default());
+/// ```
+fn format_group_to_mask(group: &str, layers: &[(i32, String)], const_prefix: &str) -> String {
+    format!(
+        r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct {0}Mask(pub u32);
+
+impl {0}Mask {{
+{1}
+    /// Returns the mask with no layers set.
+    pub const fn empty() -> Self {{
+        Self(0)
+    }}
+
+    /// Sets the layers of `other` in the mask.
+    pub fn insert(&mut self, other: Self) {{
+        self.0 |= other.0;
+    }}
+
+    /// Clears the layers of `other` from the mask.
+    pub fn remove(&mut self, other: Self) {{
+        self.0 &= !other.0;
+    }}
+
+    /// Returns whether every layer of `other` is set in the mask.
+    pub const fn contains(self, other: Self) -> bool {{
+        self.0 & other.0 == other.0
+    }}
+}}
+
+impl std::ops::BitOr for {0}Mask {{
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {{
+        Self(self.0 | other.0)
+    }}
+}}
+
+impl std::ops::BitAnd for {0}Mask {{
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {{
+        Self(self.0 & other.0)
+    }}
+}}
+
+impl From<{0}Mask> for u32 {{
+    fn from(mask: {0}Mask) -> Self {{
+        mask.0
+    }}
+}}
+
+impl From<{0}Mask> for i64 {{
+    fn from(mask: {0}Mask) -> Self {{
+        i64::from(mask.0)
+    }}
+}}
+"#,
+        group,
+        group_variants(layers, const_prefix)
+            .iter()
+            .map(|(variant, bit)| format!(
+                "    pub const {}: Self = Self({});
+",
+                variant, bit
+            ))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_group_to_mask() {
+    let layers = vec![
+        (1, "walls".to_string()),
+        (2, "player hitbox".to_string()),
+        (32, "last".to_string()),
+    ];
+    let content = format_group_to_mask("Physics2d", &layers, "");
+
+    assert!(content.starts_with(
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]\npub struct Physics2dMask(pub u32);\n\nimpl Physics2dMask {\n    pub const WALLS: Self = Self(1);\n    pub const PLAYER_HITBOX: Self = Self(2);\n    pub const LAST: Self = Self(2147483648);\n\n    /// Returns the mask with no layers set.\n"
+    ));
+    assert!(content.contains(
+        "    pub fn insert(&mut self, other: Self) {\n        self.0 |= other.0;\n    }\n"
+    ));
+    assert!(content.contains(
+        "    pub fn remove(&mut self, other: Self) {\n        self.0 &= !other.0;\n    }\n"
+    ));
+    assert!(content.contains("    pub const fn contains(self, other: Self) -> bool {\n        self.0 & other.0 == other.0\n    }\n"));
+    assert!(content.contains("impl std::ops::BitOr for Physics2dMask {\n    type Output = Self;\n\n    fn bitor(self, other: Self) -> Self {\n        Self(self.0 | other.0)\n"));
+    assert!(content.contains("impl std::ops::BitAnd for Physics2dMask {\n    type Output = Self;\n\n    fn bitand(self, other: Self) -> Self {\n        Self(self.0 & other.0)\n"));
+    assert!(content.contains("impl From<Physics2dMask> for i64 {\n    fn from(mask: Physics2dMask) -> Self {\n        i64::from(mask.0)\n"));
+
+    assert!(
+        format_group_to_mask("Physics2d", &layers, "LAYER_")
+            .contains("    pub const LAYER_WALLS: Self = Self(1);\n")
+    );
 }
 
 /// Extracts group name and group number from a layer group string.
@@ -122,13 +610,26 @@ fn extract_group_data(group: &str, name: &str) -> Option<(String, i32, String)>
         return None;
     }
 
+    let number = match parts.last().unwrap().replace("layer_", "").parse::<i32>() {
+        Ok(number) => number,
+        Err(e) => {
+            println!(
+                "cargo::warning=Invalid layer number in layer_names key {}: {}, skipping",
+                group, e
+            );
+            return None;
+        }
+    };
+
+    if !(1..=LAYER_COUNT).contains(&number) {
+        println!(
+            "cargo::warning=Layer number {} in layer_names key {} is outside 1..={}, skipping",
+            number, group, LAYER_COUNT
+        );
+        return None;
+    }
+
     let group = to_upper_camel_case(reorder_group_name(parts.first().unwrap()).as_str());
-    let number = parts
-        .last()
-        .unwrap()
-        .replace("layer_", "")
-        .parse::<i32>()
-        .unwrap();
 
     Some((group, number, name.to_string()))
 }
@@ -140,6 +641,17 @@ fn test_extract_group_data() {
     let expected = Some(("Physics2d".to_string(), 1, "Layer1".to_string()));
     assert_eq!(extract_group_data(input_group, input_name), expected);
 }
+#[test]
+fn test_extract_group_data_malformed_number() {
+    assert_eq!(extract_group_data("2d_physics/layer_x", "Layer1"), None);
+    assert_eq!(extract_group_data("2d_physics/layer_", "Layer1"), None);
+}
+#[test]
+fn test_extract_group_data_out_of_range_number() {
+    assert_eq!(extract_group_data("2d_physics/layer_0", "Layer0"), None);
+    assert_eq!(extract_group_data("2d_physics/layer_33", "Layer33"), None);
+    assert!(extract_group_data("2d_physics/layer_32", "Layer32").is_some());
+}
 
 /// Reorders a group name by reversing the order of its parts.
 /// This ensures that groups like "2d_physics" are converted to "physics_2d",
@@ -1,34 +1,96 @@
 use crate::{
-    icon_comment::apply_icons_from_comments, mod_file::write_mod_file, projectgodot::ProjectGodot,
+    gdextension::parse_godot_version,
+    icon_comment::apply_icons_from_comments,
+    mod_file::{
+        GeneratedModule, remove_orphans, write_include_mod_file, write_mod_file, write_prelude,
+        write_single_file,
+    },
+    stamp::Stamp,
+    utils::{
+        DEFAULT_INDENT, GODOT_CACHE_DIRS, apply_code_style, find_files_with_extension,
+        find_source_files, is_engine_class, make_path_if_not_exists, normalize_resource_path,
+        to_const_name,
+    },
+};
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub use crate::{
+    error::GeneratorError,
+    gdextension::{
+        ConfigurationSection, DependenciesSection, Gdextension, IconsSection, LibrariesSection,
+    },
+    projectgodot::{OwnedProjectGodot, ProjectGodot},
+    scenes::SceneConstKind,
+    utils::LineEnding,
 };
-use std::{collections::HashMap, fs, path::Path};
 
+mod autoloads;
+mod config;
+mod error;
 mod gdextension;
+mod gdscript;
+mod godot_class;
+mod groups;
 mod icon_comment;
 mod input_actions;
 mod layers;
+mod libraries;
 mod mod_file;
-mod projectgodot;
+pub mod projectgodot;
 mod scenes;
+mod stamp;
+mod translations;
+mod tscn;
 mod utils;
 
+/// The directory in Cargo's `OUT_DIR` generated into with `use_out_dir`.
+const OUT_DIR_GENERATED: &str = "generated";
+
+/// A callback run after generating, with the path of every file written, set with `Generator::on_complete`.
+pub type OnCompleteCallback = dyn Fn(&[PathBuf]) + Send + Sync;
+
+#[derive(Clone)]
+struct OnComplete(Arc<OnCompleteCallback>);
+
+impl fmt::Debug for OnComplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OnComplete(..)")
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Generator {
     /// Path to output generated files to.
     output_dir: String,
     /// Default: false
     output_dir_valid: bool,
+    use_out_dir: bool,
     /// Path to the `.gdextension` file to parse for icon comments.
     gdextension_path: String,
     /// Default: false
     gdextension_path_valid: bool,
+    /// Entry symbol and libraries to create the `.gdextension` file with, if it does not exist.
+    new_gdextension: Option<(String, Vec<(String, String)>)>,
+    /// The Godot version the generated code targets, compared against the `.gdextension` `compatibility_minimum`.
+    target_godot_version: Option<String>,
     /// Path to the `project.godot` file to parse for action and layer constants.
     project_godot_path: String,
     /// Default: false
     project_godot_path_valid: bool,
+    override_cfg_path: Option<String>,
     /// Path to the Rust source files. Typically `./src`.
     source_path: String,
     /// Default: true
     source_path_valid: bool,
+    /// Additional paths to Rust source files, scanned along with `source_path`.
+    additional_source_paths: Vec<String>,
+    /// Globs of source files to skip, relative to each source path.
+    ignore_source_globs: Vec<String>,
     /// Path to the godot res:// root. Typically `../godot`.
     resource_path: String,
     /// Default: true
@@ -36,13 +98,57 @@ pub struct Generator {
 
     validation_errors: Vec<String>,
 
-    icon_sources: HashMap<String, String>,
+    icon_sources: BTreeMap<String, String>,
+    default_class_icons: BTreeMap<String, String>,
     layer_consts: bool,
+    layers_fill_unnamed: bool,
+    layers_as_masks: bool,
+    library_consts: bool,
+    group_consts: bool,
+    group_consts_string_names: bool,
+    typed_autoloads: bool,
+    translation_keys: bool,
     action_consts: bool,
+    action_consts_cached: bool,
+    action_consts_tests: bool,
     action_invocations: bool,
+    action_registration: bool,
+    default_bindings: bool,
+    action_enum: bool,
+    /// Path to write a Markdown table of the actions and their keystrokes to, if any.
+    keybindings_markdown_path: Option<String>,
+    gdscript_consts_path: Option<String>,
     icon_comments: bool,
     scene_consts: bool,
+    scene_const_kinds: Vec<SceneConstKind>,
+    scene_consts_grouped: bool,
+    scene_consts_use_uid: bool,
+    scene_allow_globs: Vec<String>,
+    scene_deny_globs: Vec<String>,
+    scan_godot_cache_dirs: bool,
     scene_actions: bool,
+    scene_actions_impl_for: String,
+    custom_class_paths: BTreeMap<String, String>,
+    scene_actions_predicates: bool,
+    scene_nodes: bool,
+    scene_dependencies: bool,
+    scene_node_accessors: bool,
+    scene_spawners: bool,
+    class_registry: bool,
+    reexport_generated: bool,
+    prelude: bool,
+    /// Path to write every generated module to as a single file, instead of a file per module and a `mod.rs`.
+    single_file_output: Option<String>,
+    clean_orphans: bool,
+    skip_unchanged: bool,
+    verbose: bool,
+    strict_lints: bool,
+    /// Prefix of generated scene and action const names and layer variants, e.g. `SCENE_`, or empty for none.
+    const_prefix: String,
+    export_env_vars: bool,
+    indent: String,
+    line_ending: LineEnding,
+    on_complete: Option<OnComplete>,
 }
 
 impl Generator {
@@ -50,42 +156,139 @@ impl Generator {
         Self {
             output_dir: "".into(),
             output_dir_valid: false,
+            use_out_dir: false,
             gdextension_path: "".into(),
             gdextension_path_valid: false,
+            new_gdextension: None,
+            target_godot_version: None,
             project_godot_path: "".into(),
             project_godot_path_valid: false,
+            override_cfg_path: None,
             source_path: "./src".into(),
             source_path_valid: true,
+            additional_source_paths: vec![],
+            ignore_source_globs: vec![],
             resource_path: "../godot".into(),
             resource_path_valid: true,
             validation_errors: vec![],
-            icon_sources: HashMap::new(),
+            icon_sources: BTreeMap::new(),
+            default_class_icons: BTreeMap::new(),
             layer_consts: false,
+            layers_fill_unnamed: false,
+            layers_as_masks: false,
+            library_consts: false,
+            group_consts: false,
+            group_consts_string_names: false,
+            typed_autoloads: false,
+            translation_keys: false,
             action_consts: false,
+            action_consts_cached: false,
+            action_consts_tests: false,
             action_invocations: false,
+            action_registration: false,
+            default_bindings: false,
+            action_enum: false,
+            keybindings_markdown_path: None,
+            gdscript_consts_path: None,
             icon_comments: false,
             scene_consts: false,
+            scene_const_kinds: vec![SceneConstKind::Str],
+            scene_consts_grouped: false,
+            scene_consts_use_uid: false,
+            scene_allow_globs: vec![],
+            scene_deny_globs: vec![],
+            scan_godot_cache_dirs: false,
             scene_actions: false,
+            scene_actions_impl_for: "Node".into(),
+            custom_class_paths: BTreeMap::new(),
+            scene_actions_predicates: false,
+            scene_nodes: false,
+            scene_dependencies: false,
+            scene_node_accessors: false,
+            scene_spawners: false,
+            class_registry: false,
+            reexport_generated: false,
+            prelude: false,
+            single_file_output: None,
+            clean_orphans: false,
+            skip_unchanged: false,
+            verbose: false,
+            strict_lints: false,
+            const_prefix: String::new(),
+            export_env_vars: false,
+            indent: DEFAULT_INDENT.into(),
+            line_ending: LineEnding::Lf,
+            on_complete: None,
         }
     }
 
+    /// Run the generator, printing any errors as `cargo::error` lines for cargo to report.
     pub fn generate(&self) {
-        if !self.validation_errors.is_empty() {
-            for err in &self.validation_errors {
-                println!("cargo::error={}", err);
+        match self.try_generate() {
+            Ok(_) => {}
+            Err(GeneratorError::Validation(errors)) => {
+                for err in errors {
+                    println!("cargo::error={}", err);
+                }
+            }
+            Err(e) => println!("cargo::error={}", e),
+        }
+    }
+
+    /// Run the generator, returning the path of every file written this run, including the `mod.rs` and `.gdextension`.
+    ///
+    /// Fails with `GeneratorError::Validation` if the configuration is invalid, or `GeneratorError::Io` if
+    /// `project.godot`, the `.gdextension` or a generated file can't be read or written.
+    ///
+    /// The configuration is validated first, so an enabled feature missing a path it requires fails here rather than
+    /// being skipped with a warning as it was before `validate` existed.
+    pub fn try_generate(&self) -> Result<Vec<PathBuf>, GeneratorError> {
+        self.validate().map_err(GeneratorError::Validation)?;
+
+        if self.use_out_dir {
+            return self.with_out_dir()?.generate_validated();
+        }
+        self.generate_validated()
+    }
+
+    /// Generates with a configuration which has passed `validate`.
+    fn generate_validated(&self) -> Result<Vec<PathBuf>, GeneratorError> {
+        if self.skip_unchanged
+            && let Some(stamp) = stamp::read_stamp(&self.output_dir)
+            && stamp.is_current(self.inputs_hash())
+        {
+            for line in &stamp.cargo_lines {
+                println!("{}", line);
             }
-            return;
+            if let Some(OnComplete(callback)) = &self.on_complete {
+                callback(&stamp.written_paths);
+            }
+            return Ok(stamp.written_paths);
         }
 
         let mut _project_godot_content: String = String::new();
         let mut project: Option<ProjectGodot> = None;
-        let mut modules: Vec<String> = vec![];
+        let mut modules: Vec<GeneratedModule> = vec![];
+        let mut written_paths: Vec<PathBuf> = vec![];
+        let mut autoload_scene_paths: Vec<String> = vec![];
+        let mut rerun_paths: Vec<&str> = vec![];
+
+        if let Some(path) = self.create_missing_gdextension()? {
+            written_paths.push(path);
+        }
 
         if self.project_godot_path_valid {
-            let file_read = fs::read_to_string(&self.project_godot_path);
+            let file_read = self.read_project_godot();
 
             match file_read {
                 Ok(content) => {
+                    if content.trim().is_empty() {
+                        println!(
+                            "cargo::warning=Project settings file {} is empty, so features reading it will find nothing to generate",
+                            self.project_godot_path
+                        );
+                    }
+
                     // by assigning to a higher scoped variable, we ensure the string lives long enough for the ProjectGodot struct to reference it
                     _project_godot_content = content;
                     project = Some(ProjectGodot::parse_from_str(
@@ -93,59 +296,625 @@ impl Generator {
                     ));
                 }
                 Err(e) => {
-                    println!("cargo::error=Failed to read project.godot: {}", e);
-                    return;
+                    return Err(GeneratorError::Io(io::Error::new(
+                        e.kind(),
+                        format!("Failed to read {}: {}", self.project_godot_path, e),
+                    )));
+                }
+            }
+
+            if let Some(path) = self.existing_override_cfg_path() {
+                rerun_paths.push(path);
+            }
+        }
+
+        if let Some(target_version) = &self.target_godot_version
+            && self.gdextension_path_valid
+        {
+            match fs::read_to_string(&self.gdextension_path) {
+                Ok(content) => {
+                    if let Some(warning) = Gdextension::parse_from_str(&content)
+                        .configuration
+                        .and_then(|c| c.compatibility_warning(target_version))
+                    {
+                        println!("cargo::warning={}", warning);
+                    }
+                }
+                Err(e) => println!(
+                    "cargo::warning=Failed to read {} to check compatibility_minimum: {}",
+                    self.gdextension_path, e
+                ),
+            }
+        }
+
+        if self.library_consts_valid() {
+            match fs::read_to_string(&self.gdextension_path) {
+                Ok(content) => modules.extend(libraries::generate_library_consts(
+                    &self.output_dir,
+                    &Gdextension::parse_from_str(&content),
+                    self.strict_lints,
+                )?),
+                Err(e) => {
+                    return Err(GeneratorError::Io(io::Error::new(
+                        e.kind(),
+                        format!("Failed to read {}: {}", self.gdextension_path, e),
+                    )));
                 }
             }
+            rerun_paths.push(&self.gdextension_path);
         }
 
         if self.action_either_valid() {
+            // the override.cfg is checked too, as it may add actions of its own
+            let settings_paths = [
+                Some(self.project_godot_path.as_str()),
+                self.existing_override_cfg_path(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<&str>>();
+
+            if let Some(proj) = &project {
+                modules.extend(input_actions::generate_actions(
+                    &self.output_dir,
+                    &input_actions::ActionOptions {
+                        output_consts: self.action_consts,
+                        consts_cached: self.action_consts_cached,
+                        consts_tests_settings_paths: self
+                            .action_consts_tests
+                            .then_some(settings_paths.as_slice()),
+                        output_invocations: self.action_invocations,
+                        output_registration: self.action_registration,
+                        output_default_bindings: self.default_bindings,
+                        const_prefix: &self.const_prefix,
+                        strict_lints: self.strict_lints,
+                        verbose: self.verbose,
+                    },
+                    proj,
+                )?);
+            }
+            rerun_paths.push(&self.project_godot_path);
+        }
+
+        if self.action_enum_valid() {
             if let Some(proj) = &project {
-                input_actions::generate_actions(
+                modules.extend(input_actions::generate_action_enum(
                     &self.output_dir,
-                    self.action_consts,
-                    self.action_invocations,
                     proj,
+                    self.strict_lints,
+                )?);
+            }
+            rerun_paths.push(&self.project_godot_path);
+        }
+
+        if let Some(path) = &self.keybindings_markdown_path
+            && self.project_godot_path_valid
+        {
+            if let Some(proj) = &project {
+                make_path_if_not_exists(path)?;
+                fs::write(path, input_actions::format_keybindings_markdown(proj))?;
+                written_paths.push(PathBuf::from(path));
+            }
+            rerun_paths.push(&self.project_godot_path);
+        }
+
+        if let Some(path) = &self.gdscript_consts_path {
+            let scenes_and_paths = if self.resource_path_valid {
+                scenes::find_scenes(
+                    &self.resource_path,
+                    &self.scene_allow_globs,
+                    &self.scene_deny_globs,
+                    self.scene_skip_dirs(),
                 )
-                .iter()
-                .for_each(|m| modules.push(m.to_string()));
+            } else {
+                vec![]
+            };
+
+            make_path_if_not_exists(path)?;
+            fs::write(
+                path,
+                gdscript::generate_gdscript_consts(
+                    &scenes_and_paths,
+                    project.as_ref(),
+                    &self.const_prefix,
+                ),
+            )?;
+            written_paths.push(PathBuf::from(path));
+            if self.project_godot_path_valid {
+                rerun_paths.push(&self.project_godot_path);
             }
-            println!("cargo:rerun-if-changed={}", self.project_godot_path);
         }
 
         if self.icon_comments_valid() {
-            apply_icons_from_comments(
-                &self.source_path,
+            if let Some(path) = apply_icons_from_comments(
+                &self.source_paths(),
+                &self.ignore_source_globs,
                 &self.resource_path,
                 &self.gdextension_path,
                 &self.icon_sources,
-            );
-            println!("cargo:rerun-if-changed={}", self.gdextension_path);
+                &self.default_class_icons,
+            )? {
+                written_paths.push(path);
+            }
+            rerun_paths.push(&self.gdextension_path);
         }
 
         if self.layer_consts_valid() {
             if let Some(proj) = &project {
-                layers::generate_layers_consts(&self.output_dir, proj)
-                    .iter()
-                    .for_each(|m| modules.push(m.to_string()));
+                modules.extend(layers::generate_layers_consts(
+                    &self.output_dir,
+                    proj,
+                    self.layers_fill_unnamed,
+                    self.layers_as_masks,
+                    &self.const_prefix,
+                    self.strict_lints,
+                )?);
+            }
+            rerun_paths.push(&self.project_godot_path);
+        }
+
+        if self.group_consts_valid() {
+            if let Some(proj) = &project {
+                modules.extend(groups::generate_group_consts(
+                    &self.output_dir,
+                    proj,
+                    self.group_consts_string_names,
+                    self.strict_lints,
+                )?);
+            }
+            rerun_paths.push(&self.project_godot_path);
+        }
+
+        if self.typed_autoloads_valid() {
+            if let Some(proj) = &project {
+                modules.extend(autoloads::generate_typed_autoloads(
+                    &self.output_dir,
+                    &self.resource_path,
+                    proj,
+                    &self.custom_class_paths,
+                    self.strict_lints,
+                )?);
+                // the type of each autoload is read from its scene, so a changed root type regenerates
+                autoload_scene_paths = autoloads::autoload_scene_paths(&self.resource_path, proj);
+            }
+            rerun_paths.push(&self.project_godot_path);
+            rerun_paths.extend(autoload_scene_paths.iter().map(String::as_str));
+        }
+
+        if self.translation_keys_valid() {
+            if let Some(proj) = &project {
+                modules.extend(translations::generate_translation_keys(
+                    &self.output_dir,
+                    &self.resource_path,
+                    proj,
+                    self.strict_lints,
+                )?);
             }
-            println!("cargo:rerun-if-changed={}", self.project_godot_path);
+            rerun_paths.push(&self.project_godot_path);
         }
 
         if self.scene_either_valid() {
-            scenes::generate_scenes(
+            modules.extend(scenes::generate_scenes(
                 &self.output_dir,
                 &self.resource_path,
-                self.scene_consts,
-                self.scene_actions,
+                &scenes::SceneOptions {
+                    allow_globs: &self.scene_allow_globs,
+                    deny_globs: &self.scene_deny_globs,
+                    skip_dirs: self.scene_skip_dirs(),
+                    scene_consts: self.scene_consts,
+                    scene_const_kinds: &self.scene_const_kinds,
+                    scene_consts_grouped: self.scene_consts_grouped,
+                    scene_consts_use_uid: self.scene_consts_use_uid,
+                    scene_actions: self.scene_actions,
+                    scene_actions_impl_for: &self.scene_actions_impl_for,
+                    custom_class_paths: &self.custom_class_paths,
+                    current_scene_predicates: self.scene_actions_predicates,
+                    scene_nodes: self.scene_nodes,
+                    scene_dependencies: self.scene_dependencies,
+                    scene_node_accessors: self.scene_node_accessors,
+                    scene_spawners: self.scene_spawners,
+                    const_prefix: &self.const_prefix,
+                    strict_lints: self.strict_lints,
+                },
+            )?);
+        }
+
+        if self.class_registry_valid() {
+            modules.extend(godot_class::generate_class_registry(
+                &self.output_dir,
+                &self.source_paths(),
+                &self.ignore_source_globs,
+                self.strict_lints,
+            )?);
+        }
+
+        if self.prelude && self.output_dir_valid {
+            let names = modules.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+            modules.push(write_prelude(&self.output_dir, &names)?);
+        }
+
+        if let Some(single_file_path) = &self.single_file_output {
+            if !modules.is_empty() {
+                written_paths.push(write_single_file(
+                    single_file_path,
+                    &modules,
+                    self.reexport_generated,
+                )?);
+            }
+
+            if self.clean_orphans && self.output_dir_valid {
+                remove_orphans(&self.output_dir, &written_paths);
+            }
+        } else {
+            written_paths.extend(modules.iter().map(|m| m.path.clone()));
+
+            let removed = if self.clean_orphans && self.output_dir_valid {
+                remove_orphans(&self.output_dir, &written_paths)
+            } else {
+                vec![]
+            };
+
+            if self.use_out_dir {
+                if !modules.is_empty() {
+                    written_paths.push(write_include_mod_file(
+                        &self.output_dir,
+                        &modules,
+                        self.reexport_generated,
+                    )?);
+                }
+            } else if !modules.is_empty() || !removed.is_empty() {
+                written_paths.push(write_mod_file(
+                    &self.output_dir,
+                    modules.into_iter().map(|m| m.name).collect(),
+                    &removed,
+                    self.reexport_generated,
+                )?);
+            }
+        }
+
+        if self.indent != DEFAULT_INDENT || self.line_ending != LineEnding::Lf {
+            for path in written_paths
+                .iter()
+                .filter(|p| p.extension().is_some_and(|e| e == "rs"))
+            {
+                let content = fs::read_to_string(path)?;
+                fs::write(
+                    path,
+                    apply_code_style(&content, &self.indent, self.line_ending),
+                )?;
+            }
+        }
+
+        let mut cargo_lines = vec![];
+        if self.export_env_vars
+            && let Some(proj) = &project
+        {
+            cargo_lines.extend(env_var_lines(proj));
+        }
+        cargo_lines.extend(rerun_if_changed_lines(rerun_paths));
+
+        for line in &cargo_lines {
+            println!("{}", line);
+        }
+
+        if self.skip_unchanged {
+            // hashed after generating, as the output may be among the inputs, e.g. generated into `src` or icons in the `.gdextension`
+            stamp::write_stamp(
+                &self.output_dir,
+                &Stamp {
+                    hash: self.inputs_hash(),
+                    written_paths: written_paths.clone(),
+                    cargo_lines,
+                },
+            )?;
+        }
+
+        if let Some(OnComplete(callback)) = &self.on_complete {
+            callback(&written_paths);
+        }
+
+        Ok(written_paths)
+    }
+
+    /// Returns a copy of the generator outputting to `generated` in `OUT_DIR`, creating the directory, for `use_out_dir`.
+    fn with_out_dir(&self) -> io::Result<Generator> {
+        let out_dir = std::env::var("OUT_DIR").map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "OUT_DIR is not set, `use_out_dir` must be called from a build script",
+            )
+        })?;
+
+        let output_dir = Path::new(&out_dir).join(OUT_DIR_GENERATED);
+        fs::create_dir_all(&output_dir).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to create output directory {}: {}",
+                    output_dir.display(),
+                    e
+                ),
             )
+        })?;
+
+        Ok(Generator {
+            output_dir: output_dir.to_string_lossy().to_string(),
+            output_dir_valid: true,
+            ..self.clone()
+        })
+    }
+
+    /// Writes the `.gdextension` given to `create_gdextension_if_missing` if there isn't one at the gdextension path, returning its path.
+    fn create_missing_gdextension(&self) -> io::Result<Option<PathBuf>> {
+        let Some((entry_symbol, libraries)) = &self.new_gdextension else {
+            return Ok(None);
+        };
+        if !self.gdextension_path_valid || Path::new(&self.gdextension_path).exists() {
+            return Ok(None);
+        }
+
+        let libraries = libraries
             .iter()
-            .for_each(|m| modules.push(m.to_string()));
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        let content = Gdextension::new_minimal(entry_symbol, &libraries).to_string();
+
+        make_path_if_not_exists(&self.gdextension_path)
+            .and_then(|_| fs::write(&self.gdextension_path, content))
+            .map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!(
+                        "Failed to create gdextension file {}: {}",
+                        self.gdextension_path, e
+                    ),
+                )
+            })?;
+        println!(
+            "cargo::warning=Created gdextension file {}",
+            self.gdextension_path
+        );
+
+        Ok(Some(PathBuf::from(&self.gdextension_path)))
+    }
+
+    /// Hashes the configuration and every file the generator may read, for `skip_unchanged`.
+    fn inputs_hash(&self) -> u64 {
+        let mut input_files = [&self.project_godot_path, &self.gdextension_path]
+            .into_iter()
+            .chain(&self.override_cfg_path)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .collect::<Vec<PathBuf>>();
+
+        for source_path in self.source_paths() {
+            input_files.extend(find_source_files(
+                Path::new(source_path),
+                &self.ignore_source_globs,
+            ));
+        }
+        for extension in ["tscn", "csv", "po"] {
+            input_files.extend(find_files_with_extension(
+                Path::new(&self.resource_path),
+                extension,
+                self.scene_skip_dirs(),
+            ));
+        }
+
+        // every map in the configuration is a `BTreeMap`, so its `Debug` output is the same on every run
+        stamp::hash_inputs(&format!("{:?}", self), &input_files)
+    }
+
+    /// Check the configuration without generating anything, e.g. to assert a `build.rs` configuration in a test.
+    ///
+    /// Returns every problem found by the builder methods, along with any enabled feature missing the paths it requires.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = self.validation_errors.clone();
+
+        let any_output = self.layer_consts
+            || self.library_consts
+            || self.group_consts
+            || self.typed_autoloads
+            || self.translation_keys
+            || self.action_consts
+            || self.action_invocations
+            || self.action_registration
+            || self.default_bindings
+            || self.action_enum
+            || self.scene_consts
+            || self.scene_actions
+            || self.scene_nodes
+            || self.scene_dependencies
+            || self.scene_node_accessors
+            || self.scene_spawners
+            || self.class_registry
+            || self.prelude;
+        if any_output && !self.use_out_dir && self.output_dir.is_empty() {
+            errors.push("Output directory must be set with `set_output_dir`".into());
+        }
+        if self.use_out_dir && std::env::var_os("OUT_DIR").is_none() {
+            errors.push(
+                "OUT_DIR is not set, `use_out_dir` must be called from a build script".into(),
+            );
+        }
+
+        let uses_project_godot = self.layer_consts
+            || self.group_consts
+            || self.typed_autoloads
+            || self.translation_keys
+            || self.action_consts
+            || self.action_invocations
+            || self.action_registration
+            || self.default_bindings
+            || self.action_enum
+            || self.export_env_vars
+            || self.keybindings_markdown_path.is_some();
+        if uses_project_godot && self.project_godot_path.is_empty() {
+            errors.push(
+                "project.godot path must be set with `set_project_godot_path` to generate layers, groups, translation keys and actions"
+                    .into(),
+            );
+        }
+
+        if self.icon_comments && self.gdextension_path.is_empty() {
+            errors.push(
+                "gdextension path must be set with `set_gdextension_path` to apply icon comments"
+                    .into(),
+            );
+        }
+
+        if self.skip_unchanged && !self.use_out_dir && self.output_dir.is_empty() {
+            errors.push(
+                "Output directory must be set with `set_output_dir` to keep the stamp for `skip_unchanged`"
+                    .into(),
+            );
+        }
+
+        if !self.gdextension_path.is_empty()
+            && self.new_gdextension.is_none()
+            && !Path::new(&self.gdextension_path).exists()
+        {
+            errors.push(format!(
+                "gdextension path does not exist: {}",
+                self.gdextension_path
+            ));
+        }
+
+        if self.scene_actions
+            && !matches!(self.scene_actions_impl_for.as_str(), "Node" | "SceneTree")
+            && !is_engine_class(&self.scene_actions_impl_for)
+            && !self.scene_actions_impl_for.contains("::")
+            && !self
+                .custom_class_paths
+                .contains_key(&self.scene_actions_impl_for)
+        {
+            errors.push(format!(
+                "Scene actions impl type {} is not an engine class, give its full path, e.g. `crate::{}`, or add it to `custom_class_paths`",
+                self.scene_actions_impl_for, self.scene_actions_impl_for
+            ));
+        }
+
+        if self.library_consts && self.gdextension_path.is_empty() {
+            errors.push(
+                "gdextension path must be set with `set_gdextension_path` to generate library consts"
+                    .into(),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Read and parse the configured `project.godot`, passing the parsed project to `f` and returning its result.
+    ///
+    /// `ProjectGodot` borrows from the content of the file, so it can only live as long as that content.
+    /// Rather than returning it, the content is kept alive for the duration of `f`; return anything you need from it as owned data.
+    ///
+    /// Fails if `set_project_godot_path` was not called with a valid path, or the file cannot be read.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use zgrcg::Generator;
+    ///
+    /// let generator = Generator::builder().set_project_godot_path("./project.godot");
+    ///
+    /// let rendering_method = generator
+    ///     .with_parsed_project(|project| {
+    ///         project
+    ///             .rendering
+    ///             .as_ref()
+    ///             .and_then(|r| r.rendering_method)
+    ///             .map(str::to_string)
+    ///     })
+    ///     .unwrap();
+    ///
+    /// if rendering_method.as_deref() == Some("gl_compatibility") {
+    ///     println!("cargo::rustc-cfg=gl_compatibility");
+    /// }
+    /// ```
+    pub fn with_parsed_project<R>(&self, f: impl FnOnce(&ProjectGodot) -> R) -> io::Result<R> {
+        if !self.project_godot_path_valid {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "project.godot path must be set with `set_project_godot_path`",
+            ));
+        }
+
+        let content = self.read_project_godot()?;
+        Ok(f(&ProjectGodot::parse_from_str(&content)))
+    }
+
+    /// Read and parse the configured `project.godot` into an `OwnedProjectGodot`, which can be kept or returned without the file content.
+    ///
+    /// Fails if `set_project_godot_path` was not called with a valid path, or the file cannot be read.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use zgrcg::Generator;
+    ///
+    /// let project = Generator::builder()
+    ///     .set_project_godot_path("./project.godot")
+    ///     .parse_project()
+    ///     .unwrap();
+    ///
+    /// for (name, path) in &project.autoloads {
+    ///     println!("cargo::warning=Autoload {} is {}", name, path);
+    /// }
+    /// ```
+    pub fn parse_project(&self) -> io::Result<OwnedProjectGodot> {
+        if !self.project_godot_path_valid {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "project.godot path must be set with `set_project_godot_path`",
+            ));
         }
 
-        if !modules.is_empty() {
-            write_mod_file(&self.output_dir, modules);
+        let content = self.read_project_godot()?;
+        Ok(ProjectGodot::parse_from_str(&content).into_owned())
+    }
+
+    /// Reads the configured `project.godot`, followed by the `override.cfg` if there is one.
+    ///
+    /// Repeated sections are merged when parsed, with later keys winning, so the keys of the `override.cfg` replace those of the
+    /// `project.godot` as they do in Godot.
+    fn read_project_godot(&self) -> io::Result<String> {
+        let mut content = fs::read_to_string(&self.project_godot_path)?;
+
+        if let Some(path) = self.existing_override_cfg_path() {
+            content.push('\n');
+            content.push_str(&fs::read_to_string(path)?);
         }
+
+        Ok(content)
+    }
+
+    fn existing_override_cfg_path(&self) -> Option<&str> {
+        self.override_cfg_path
+            .as_deref()
+            .filter(|path| Path::new(path).exists())
+    }
+
+    /// Write the `[input]` actions of the configured `project.godot` to a JSON file at `path`, for tooling outside of Rust
+    /// such as a GDScript settings menu. Each action has its name, deadzone (`null` if unset) and resolved keystrokes.
+    ///
+    /// Fails if `set_project_godot_path` was not called with a valid path, or either file cannot be read or written.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use zgrcg::Generator;
+    ///
+    /// Generator::builder()
+    ///     .set_project_godot_path("./project.godot")
+    ///     .export_action_map_json("../godot/actions.json")
+    ///     .unwrap();
+    /// ```
+    pub fn export_action_map_json(&self, path: &str) -> io::Result<()> {
+        let json = self.with_parsed_project(input_actions::format_action_map_json)?;
+        make_path_if_not_exists(path)?;
+        fs::write(path, json)
     }
 
     /// Supply the output directory for the generated files.
@@ -153,6 +922,7 @@ impl Generator {
     pub fn set_output_dir(mut self, path: &str) -> Self {
         self.output_dir = path.to_string();
         self.output_dir_valid = true;
+        self.use_out_dir = false;
 
         if self.output_dir.is_empty() {
             self.validation_errors
@@ -164,13 +934,25 @@ impl Generator {
             if let Err(_e) = fs::create_dir_all(&self.output_dir) {
                 self.validation_errors
                     .push("Failed to create output directory".into());
+                self.output_dir_valid = false;
             }
-            self.output_dir_valid = false;
         }
 
         self
     }
 
+    /// Generate into `generated` in Cargo's `OUT_DIR` rather than the source tree, e.g. to keep generated code out of `cargo fmt` and `clippy`.
+    ///
+    /// The `mod.rs` declares each module with an `include!` of its file, rather than `pub mod <name>;`, so include it in your crate with
+    /// `pub mod generated { include!(concat!(env!("OUT_DIR"), "/generated/mod.rs")); }`.
+    ///
+    /// Must be run from `build.rs`, where Cargo sets `OUT_DIR`, which is read and created when generating. Of this and
+    /// `set_output_dir`, the last called wins.
+    pub fn use_out_dir(mut self) -> Self {
+        self.use_out_dir = true;
+        self
+    }
+
     /// Supply the path to the `.gdextension` file to enable generation of action and layer constants.
     pub fn set_gdextension_path(mut self, path: &str) -> Self {
         self.gdextension_path = path.to_string();
@@ -182,14 +964,67 @@ impl Generator {
             self.gdextension_path_valid = false;
         }
 
-        if !Path::new(&self.gdextension_path).exists() {
+        // whether the file exists is checked by `validate`, as `create_gdextension_if_missing` may be called after this
+        self
+    }
+
+    /// Create a minimal `.gdextension` file at the path given to `set_gdextension_path` when generating, if it does not exist,
+    /// so that icons can be applied to a fresh project.
+    ///
+    /// e.g. `.create_gdextension_if_missing("gdext_rust_init", &[("linux.debug.x86_64", "res://../rust/target/debug/librust.so")])`
+    pub fn create_gdextension_if_missing(
+        mut self,
+        entry_symbol: &str,
+        libraries: &[(&str, &str)],
+    ) -> Self {
+        if entry_symbol.is_empty()
+            || !entry_symbol
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
             self.validation_errors.push(format!(
-                "gdextension path does not exist: {}",
-                self.gdextension_path
+                "gdextension entry symbol must be a non-empty identifier: {:?}",
+                entry_symbol
+            ));
+        }
+
+        if libraries.is_empty() {
+            self.validation_errors
+                .push("gdextension must declare at least one library".into());
+        }
+
+        for (target, path) in libraries {
+            if target.is_empty() || path.is_empty() {
+                self.validation_errors.push(format!(
+                    "gdextension library targets and paths must be non-empty: {} = {:?}",
+                    target, path
+                ));
+            }
+        }
+
+        self.new_gdextension = Some((
+            entry_symbol.to_string(),
+            libraries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ));
+        self
+    }
+
+    /// Set the Godot version the generated code targets, e.g. `"4.3"`.
+    ///
+    /// If the version is below the `compatibility_minimum` declared by the `.gdextension`, a warning is emitted, as the
+    /// gdext API used by generated code may differ between Godot versions.
+    pub fn set_target_godot_version(mut self, version: &str) -> Self {
+        if parse_godot_version(version).is_none() {
+            self.validation_errors.push(format!(
+                "target Godot version must be like `4.3` or `4.3.1`: {:?}",
+                version
             ));
-            self.gdextension_path_valid = false;
         }
 
+        self.target_godot_version = Some(version.to_string());
         self
     }
 
@@ -215,6 +1050,21 @@ impl Generator {
         self
     }
 
+    /// Supply the path to an `override.cfg` to layer on top of the `project.godot`, as Godot does, e.g. to generate with the
+    /// actions or layers of a test fixture.
+    ///
+    /// Its keys replace those of the same name in the `project.godot`. If the file doesn't exist, it is ignored, as it is by Godot.
+    pub fn set_override_cfg_path(mut self, path: &str) -> Self {
+        if path.is_empty() {
+            self.validation_errors
+                .push("override.cfg path must not be empty".into());
+            return self;
+        }
+
+        self.override_cfg_path = Some(path.to_string());
+        self
+    }
+
     /// Supply the path to the Rust source files. Defaults to `./src`.
     pub fn set_source_path(mut self, path: &str) -> Self {
         self.source_path = path.to_string();
@@ -242,9 +1092,52 @@ impl Generator {
         self
     }
 
+    /// Add another path to Rust source files, e.g. for workspaces split across several crates.
+    ///
+    /// Added paths are scanned along with the path from `set_source_path`.
+    pub fn add_source_path(mut self, path: &str) -> Self {
+        if path.is_empty() {
+            self.validation_errors
+                .push("Additional source paths must be non-empty strings".into());
+            return self;
+        }
+
+        if !Path::new(path).exists() {
+            self.validation_errors
+                .push(format!("Source path does not exist: {}", path));
+            return self;
+        }
+
+        self.additional_source_paths.push(path.to_string());
+        self
+    }
+
+    /// Skip source files matching any of the `globs` when scanning the source paths, relative to each source path.
+    ///
+    /// Only `.rs` files are scanned, so e.g. `.rs.bk` backups are always skipped. `*` and `?` match within a path segment,
+    /// and `**` matches across segments, e.g. `generated/**` or `**/*.gen.rs`.
+    pub fn ignore_source_globs(mut self, globs: &[&str]) -> Self {
+        self.ignore_source_globs
+            .extend(globs.iter().map(|glob| glob.to_string()));
+        self
+    }
+
+    // the primary source path, followed by any additional ones
+    fn source_paths(&self) -> Vec<&str> {
+        let mut paths = vec![self.source_path.as_str()];
+        for path in &self.additional_source_paths {
+            if !paths.contains(&path.as_str()) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
     /// Supply the location of the godot `res://` root.
+    ///
+    /// The path is stored without trailing separators, and canonicalized to an absolute path if it exists.
     pub fn set_resource_path(mut self, path: &str) -> Self {
-        self.resource_path = path.to_string();
+        self.resource_path = normalize_resource_path(path);
 
         if self.resource_path.is_empty() {
             self.validation_errors
@@ -290,6 +1183,26 @@ impl Generator {
         self
     }
 
+    /// Supply default icons for classes without an icon comment, mapping a base class to an icon.
+    ///
+    /// `base_class` is the `base=` of the `#[class(...)]` attribute, e.g. `Control`.
+    ///
+    /// `icon_path` should be the path as used in Godot, e.g. `res://icons/gd/Control.svg`, and is fetched from the matching icon source.
+    /// Classes with an explicit `zgrcg:icon` comment keep their comment's icon.
+    pub fn default_class_icons(mut self, icons: &[(&str, &str)]) -> Self {
+        for (base_class, icon_path) in icons {
+            if base_class.is_empty() || icon_path.is_empty() {
+                self.validation_errors
+                    .push("Default class icon entries must be non-empty strings".into());
+            } else {
+                self.default_class_icons
+                    .insert(base_class.to_string(), icon_path.to_string());
+            }
+        }
+
+        self
+    }
+
     /*
         because we can't guarantee the order of builder calls, we have to allow enabling features even if the paths aren't set yet,
         and then check requirements in generate()
@@ -304,17 +1217,111 @@ impl Generator {
         self.layer_consts && self.project_godot_path_valid
     }
 
-    /// Enable generation of action constants from `project.godot`.
+    /// Generate a variant for every layer in `1..=32`, using a `LAYER_<n>` placeholder for layers without a name in `project.godot`.
     ///
-    /// e.g. for the action `MoveLeft` in Godot, a function `MOVE_LEFT()` will be generated, returning `StringName("MoveLeft")`.
-    pub fn output_action_consts(mut self) -> Self {
+    /// This makes each layer enum exhaustive over the bitfield, so any mask bit can be converted with `from_bit`. If another
+    /// layer is already named `layer_<n>`, the placeholder is `UNNAMED_LAYER_<n>`.
+    pub fn layers_fill_unnamed(mut self) -> Self {
+        self.layers_fill_unnamed = true;
+        self
+    }
+
+    /// Generate a `<Group>Mask` newtype over a `u32` mask for each layer group, instead of an enum.
+    ///
+    /// Each layer is an associated const, e.g. `Physics2dMask::WALLS`, and masks combine with `|` and `&`, `insert`, `remove`
+    /// and `contains`, converting into `u32` and `i64` with `From`.
+    pub fn layers_as_masks(mut self) -> Self {
+        self.layers_as_masks = true;
+        self
+    }
+
+    /// Enable generation of group name constants from the `[global_group]` section of `project.godot`.
+    ///
+    /// e.g. for a group `enemies`, generates `pub const ENEMIES_GROUP: &str = "enemies";`
+    pub fn output_group_consts(mut self) -> Self {
+        self.group_consts = true;
+        self
+    }
+    /// Enable generation of a `LIBRARY` const from the `[libraries]` of the `.gdextension`, for the target being compiled for.
+    ///
+    /// e.g. `windows.release.x86_64` is checked with `cfg!(all(target_os = "windows", not(debug_assertions), target_arch = "x86_64"))`.
+    /// Entries with a tag that has no Rust `cfg`, e.g. `editor`, are skipped with a warning.
+    pub fn output_library_consts(mut self) -> Self {
+        self.library_consts = true;
+        self
+    }
+    fn library_consts_valid(&self) -> bool {
+        self.library_consts && self.gdextension_path_valid
+    }
+
+    fn group_consts_valid(&self) -> bool {
+        self.group_consts && self.project_godot_path_valid
+    }
+
+    /// Also generate a `StringName` accessor for each group, e.g. `ENEMIES_GROUP_NAME()`.
+    pub fn group_consts_string_names(mut self) -> Self {
+        self.group_consts_string_names = true;
+        self
+    }
+
+    /// Enable generation of an accessor for each autoload in the `[autoload]` section of `project.godot`, typed as the root
+    /// node of its scene in the resource directory.
+    ///
+    /// e.g. for `gamestate="*res://game/gamestate.tscn"` with a root `[node name="GameState" type="Node2D"]`, generates
+    /// `pub fn gamestate() -> Option<Gd<Node2D>>`. Autoload scripts, scenes whose root has no type, and roots of a class
+    /// that is neither an engine class nor in `custom_class_paths` are returned as `Node`.
+    pub fn output_typed_autoloads(mut self) -> Self {
+        self.typed_autoloads = true;
+        self
+    }
+    fn typed_autoloads_valid(&self) -> bool {
+        self.typed_autoloads && self.project_godot_path_valid
+    }
+
+    /// Enable generation of a `tr_keys` module with a const for each translation key, for use with `tr()`.
+    ///
+    /// Keys are read from the `.csv` and `.po` sources of the translations listed in the `[internationalization]` section of `project.godot`,
+    /// relative to the resource path. e.g. for a key `MENU_START`, generates `pub const MENU_START: &str = "MENU_START";`
+    pub fn output_translation_keys(mut self) -> Self {
+        self.translation_keys = true;
+        self
+    }
+    fn translation_keys_valid(&self) -> bool {
+        self.translation_keys && self.project_godot_path_valid && self.resource_path_valid
+    }
+
+    /// Enable generation of action constants from `project.godot`.
+    ///
+    /// e.g. for the action `MoveLeft` in Godot, a function `MOVE_LEFT()` will be generated, returning `StringName("MoveLeft")`.
+    pub fn output_action_consts(mut self) -> Self {
         self.action_consts = true;
         self
     }
 
-    // applies to both action_consts and action_invocations
+    /// Cache the `StringName` of each action const in a `thread_local!`, so its accessor returns a clone rather than allocating on every call.
+    ///
+    /// e.g. `MOVE_LEFT()` returns a clone of `MOVE_LEFT_STRING_NAME`, created on its first use in each thread.
+    pub fn action_consts_cached(mut self) -> Self {
+        self.action_consts_cached = true;
+        self
+    }
+
+    /// Add a `#[cfg(test)]` module to the action consts, checking each action is still in `project.godot` or the `override.cfg`.
+    ///
+    /// The consts aren't regenerated until the build script reruns, so a `&str` const like `FIRE_STR` can outlive its action
+    /// after it's renamed in Godot. Run `cargo test` to check for this, e.g. in CI, which fails naming each missing action.
+    pub fn output_action_consts_tests(mut self) -> Self {
+        self.action_consts_tests = true;
+        self
+    }
+
+    // applies to action_consts, action_invocations, action_registration and default_bindings
     fn action_either_valid(&self) -> bool {
-        self.action_invocations && self.project_godot_path_valid
+        (self.action_consts
+            || self.action_invocations
+            || self.action_registration
+            || self.default_bindings)
+            && self.project_godot_path_valid
     }
 
     /// Enable generation of action invocation traits from `project.godot`.
@@ -325,6 +1332,52 @@ impl Generator {
         self
     }
 
+    /// Enable generation of a `register_all_actions()` function from `project.godot`.
+    ///
+    /// It adds each action missing from the `InputMap` at runtime, along with its keyboard and mouse button events, so the bindings are available without the project's input map.
+    pub fn output_action_registration(mut self) -> Self {
+        self.action_registration = true;
+        self
+    }
+
+    /// Enable generation of a `restore_default_bindings()` function from `project.godot`, e.g. for a rebinding menu.
+    ///
+    /// It erases the events of each action in the `InputMap` at runtime and adds back its keyboard and mouse button events from `project.godot`.
+    pub fn output_default_bindings(mut self) -> Self {
+        self.default_bindings = true;
+        self
+    }
+
+    /// Enable generation of an `InputAction` enum from `project.godot`, with a variant for each action.
+    ///
+    /// e.g. for the action `move_left` in Godot, the variant `InputAction::MoveLeft` converts into `StringName("move_left")` with `From`,
+    /// and `InputAction::try_from("move_left")` returns it, or an `UnknownInputAction` error for a name which is not an action.
+    pub fn output_action_enum(mut self) -> Self {
+        self.action_enum = true;
+        self
+    }
+    fn action_enum_valid(&self) -> bool {
+        self.action_enum && self.project_godot_path_valid
+    }
+
+    /// Enable writing a Markdown table of each action and the keystrokes it maps to from `project.godot`, e.g. for a game manual.
+    ///
+    /// Unlike the other outputs, it is written to `path` rather than the output directory, and is not added to the `mod.rs`.
+    pub fn output_keybindings_markdown(mut self, path: &str) -> Self {
+        self.keybindings_markdown_path = Some(path.to_string());
+        self
+    }
+
+    /// Enable writing a `.gd` file of consts mirroring the Rust scene consts and action names, so GDScript can reference the same
+    /// scenes and actions, e.g. `const MAIN := "res://scenes/Main.tscn"` and `const JUMP := &"jump"`.
+    ///
+    /// Scenes are found in the resource path and actions are read from `project.godot`, whichever are set. Like
+    /// `output_keybindings_markdown`, it is written to `path` rather than the output directory.
+    pub fn output_gdscript_consts(mut self, path: &str) -> Self {
+        self.gdscript_consts_path = Some(path.to_string());
+        self
+    }
+
     /// Enable parsing of icon comments from source files and applying them to the .gdextension file.
     ///
     /// e.g. a comment like `// zgrcg:icon="res://icons/gd/Control.svg"` above a struct definition will set the icon for that class in the .gdextension file to the specified icon.
@@ -346,8 +1399,69 @@ impl Generator {
         self.scene_consts = true;
         self
     }
+
+    /// Set the kinds of constant generated for each scene by `output_scene_consts`.
+    ///
+    /// Default: `[SceneConstKind::Str]`, e.g. `pub const MAIN: &'static str = "res://scenes/Main.tscn";`.
+    /// Adding `SceneConstKind::StringName` or `SceneConstKind::NodePath` also generates `MAIN_NAME()` or `MAIN_NODE_PATH()` accessors.
+    pub fn scene_const_kinds(mut self, kinds: &[SceneConstKind]) -> Self {
+        if kinds.is_empty() {
+            self.validation_errors
+                .push("scene_const_kinds requires at least one kind".to_string());
+        }
+
+        self.scene_const_kinds = kinds.to_vec();
+        self
+    }
+
+    /// Group the scene constants into a submodule per top-level folder of the resource directory, rather than prefixing colliding names.
+    ///
+    /// e.g. for a scene at `res://ui/Menu.tscn`, the constant will be generated as `scene_consts::ui::MENU`.
+    /// Scenes at the root of the resource directory are generated at the top level of `scene_consts`.
+    pub fn scene_consts_grouped(mut self) -> Self {
+        self.scene_consts_grouped = true;
+        self
+    }
+
+    /// Use the `uid://` of each scene from its `.tscn` header as the value of its scene constants, rather than its `res://` path,
+    /// so the constants keep working if the scene is moved. Scenes without a uid fall back to their path.
+    pub fn scene_consts_use_uid(mut self) -> Self {
+        self.scene_consts_use_uid = true;
+        self
+    }
+
+    /// Only generate for scenes matching any of the `allow` globs, if there are any, and none of the `deny` globs, e.g. to skip demo and test scenes.
+    ///
+    /// Globs are matched against the scene's file name, with or without `.tscn`, and its path relative to the resource directory,
+    /// e.g. `Test_*` or `demo/**`. Applies to every scene output.
+    pub fn scene_name_filter(mut self, allow: &[&str], deny: &[&str]) -> Self {
+        self.scene_allow_globs
+            .extend(allow.iter().map(|glob| glob.to_string()));
+        self.scene_deny_globs
+            .extend(deny.iter().map(|glob| glob.to_string()));
+        self
+    }
+
+    /// Also scan Godot's `.godot` and `.import` cache directories in the resource directory for scenes, which are skipped by default
+    /// as they may hold stray cached scenes and are often large.
+    pub fn scan_godot_cache_dirs(mut self) -> Self {
+        self.scan_godot_cache_dirs = true;
+        self
+    }
+    fn scene_skip_dirs(&self) -> &'static [&'static str] {
+        if self.scan_godot_cache_dirs {
+            &[]
+        } else {
+            GODOT_CACHE_DIRS
+        }
+    }
     fn scene_either_valid(&self) -> bool {
-        self.scene_consts && self.resource_path_valid
+        (self.scene_consts
+            || self.scene_nodes
+            || self.scene_dependencies
+            || self.scene_node_accessors
+            || self.scene_spawners)
+            && self.resource_path_valid
     }
 
     /// Enable generation of scene actions from resource directory.
@@ -357,4 +1471,1000 @@ impl Generator {
         self.scene_actions = true;
         self
     }
+
+    /// Implement the generated `SceneActions` trait for `type_name` rather than `Node`.
+    ///
+    /// The type is either an engine class with a `get_tree()` method, e.g. `"Control"`, which is imported, or a user class with a
+    /// `Node` base, e.g. `"crate::SceneManager"`, which reaches the tree through `self.base()`. A user class is given by its
+    /// path, or by its name in `custom_class_paths`. For `"SceneTree"`, the trait is implemented for `Gd<SceneTree>` and changes
+    /// scene directly.
+    pub fn scene_actions_impl_for(mut self, type_name: &str) -> Self {
+        if type_name.trim().is_empty() {
+            self.validation_errors
+                .push("Scene actions impl type must not be empty".to_string());
+            return self;
+        }
+
+        self.scene_actions_impl_for = type_name.trim().to_string();
+        self
+    }
+
+    /// Supply the Rust paths of user classes found in scenes, mapping a class name to its path, e.g.
+    /// `("GameState", "crate::game::GameState")` for a `#[derive(GodotClass)]` struct.
+    ///
+    /// Typed autoloads, scene node accessors and scene spawners use the path for nodes of the class, rather than `Node`, and
+    /// `scene_actions_impl_for` accepts the bare class name.
+    pub fn custom_class_paths(mut self, paths: &[(&str, &str)]) -> Self {
+        for (class, path) in paths {
+            if class.is_empty() || path.is_empty() {
+                self.validation_errors
+                    .push("Custom class path entries must be non-empty strings".into());
+            } else {
+                self.custom_class_paths
+                    .insert(class.to_string(), path.to_string());
+            }
+        }
+
+        self
+    }
+
+    /// Also generate an `is_current_scene_<name>()` predicate on `SceneActions` for each scene, checking whether the
+    /// tree's current scene was loaded from it, e.g. for a state machine to check which scene is active.
+    ///
+    /// Requires `output_scene_actions`.
+    pub fn scene_actions_current_scene_predicates(mut self) -> Self {
+        self.scene_actions_predicates = true;
+        self
+    }
+
+    /// Enable generation of node path constants for each scene, parsed from the `.tscn` files in the resource directory.
+    ///
+    /// e.g. for a scene at `res://scenes/Player.tscn` with a child node `HealthBar`, a module `player` will be generated
+    /// containing `pub const HEALTH_BAR: &'static str = "HealthBar";`, the path of the node relative to the scene root.
+    pub fn output_scene_nodes(mut self) -> Self {
+        self.scene_nodes = true;
+        self
+    }
+
+    /// Enable generation of dependency constants for each scene, parsed from the `[ext_resource]` entries of the `.tscn` files.
+    ///
+    /// e.g. for a scene at `res://scenes/Player.tscn` using `res://scenes/Enemy.tscn`, a module `player` will be generated
+    /// containing `pub const DEPENDENCIES: &[&'static str] = &["res://scenes/Enemy.tscn"];`, sorted and deduplicated by path.
+    pub fn output_scene_dependencies(mut self) -> Self {
+        self.scene_dependencies = true;
+        self
+    }
+
+    /// Enable generation of node accessor traits for each scene whose root node has a type, parsed from the `.tscn` files.
+    ///
+    /// e.g. for a scene at `res://scenes/Player.tscn` with a `CharacterBody2D` root and a child `HealthBar` of type `ProgressBar`,
+    /// a module `player` will be generated with a trait `PlayerNodes` implemented for `Gd<CharacterBody2D>`, containing
+    /// `fn get_health_bar(&self) -> Option<Gd<ProgressBar>>`, which returns `None` if the node is missing.
+    pub fn output_scene_node_accessors(mut self) -> Self {
+        self.scene_node_accessors = true;
+        self
+    }
+
+    /// Enable generation of a function instantiating each scene, parsed from the `.tscn` files in the resource directory.
+    ///
+    /// e.g. for a scene at `res://scenes/Player.tscn` with a `CharacterBody2D` root, `scene_spawners` will contain
+    /// `pub fn instantiate_player() -> Option<Gd<CharacterBody2D>>`, which logs a Godot error and returns `None` if the scene can't be loaded.
+    pub fn output_scene_spawners(mut self) -> Self {
+        self.scene_spawners = true;
+        self
+    }
+
+    /// Enable generation of a registry of every struct deriving `GodotClass` in the source files.
+    ///
+    /// e.g. for `#[derive(GodotClass)] #[class(init, base=Control)] pub struct Menu`, the `CLASSES` const in `class_registry`
+    /// will contain `RegisteredClass { name: "Menu", base: Some("Control"), init: true }`.
+    pub fn output_class_registry(mut self) -> Self {
+        self.class_registry = true;
+        self
+    }
+    fn class_registry_valid(&self) -> bool {
+        self.class_registry && self.source_path_valid
+    }
+
+    /// Re-export the contents of every generated module from the generated `mod.rs`,
+    /// so that everything can be imported with e.g. `use crate::generated::*;`.
+    ///
+    /// Note: the re-exports are glob imports, so if two generated modules define an item with the same name
+    /// (e.g. a scene and an action both called `MAIN`), that name is ambiguous and must be used through its module instead.
+    pub fn reexport_generated(mut self) -> Self {
+        self.reexport_generated = true;
+        self
+    }
+
+    /// Write a `prelude.rs` to the output directory re-exporting the generated traits and layer enums,
+    /// so they can all be imported with `use crate::generated::prelude::*;`.
+    ///
+    /// e.g. with action invocations and scene actions enabled, the prelude contains `pub use super::actions_invocations::InputActionInvocations;`
+    /// and `pub use super::scene_actions::SceneActions;`
+    pub fn output_prelude(mut self) -> Self {
+        self.prelude = true;
+        self
+    }
+
+    /// Write every generated module to a single file at `path`, inlined as `pub mod <name> { ... }` blocks, rather than a file per module and a `mod.rs`.
+    ///
+    /// The modules are still generated into the output directory first, and their files are removed once inlined.
+    /// Inner attributes like `#![allow(dead_code)]` become `#[allow(dead_code)]` on each module, so the file can be used with `mod` or `include!`.
+    ///
+    /// With `clean_orphans`, generated files left in the output directory by a previous run are removed too, but an existing
+    /// `mod.rs` is not rewritten.
+    pub fn single_file_output(mut self, path: &str) -> Self {
+        self.single_file_output = Some(path.to_string());
+        self
+    }
+
+    /// Remove files left in the output directory by a previous run which were not generated this run,
+    /// e.g. `scene_actions.rs` after disabling `output_scene_actions`, along with their declarations in `mod.rs`.
+    ///
+    /// Only files starting with the `// @generated by zgrcg` marker are removed, so files added by hand are kept.
+    pub fn clean_orphans(mut self) -> Self {
+        self.clean_orphans = true;
+        self
+    }
+
+    /// Print a `cargo::warning` line for each event of every action as it is parsed, e.g. to debug keystrokes missing from the
+    /// generated docs. By default only genuine issues, such as an action without events, are reported.
+    pub fn verbose(mut self) -> Self {
+        self.verbose = true;
+        self
+    }
+
+    /// Skip generating entirely when neither the configuration nor any input has changed since the last run, e.g. on a no-op rebuild.
+    ///
+    /// A hash of the configuration and the content of `project.godot`, the `.gdextension`, the source files and the scenes and
+    /// translations in the resource directory is kept in a `.zgrcg_stamp` file in the output directory. A skipped run writes
+    /// nothing, but still returns the paths written and prints the `cargo:` lines of the last run.
+    pub fn skip_unchanged(mut self) -> Self {
+        self.skip_unchanged = true;
+        self
+    }
+
+    /// Omit the blanket `#![allow(dead_code)]` and `#![allow(non_snake_case)]` from generated files, so your crate's lints apply to them.
+    ///
+    /// Items whose naming requires it are still allowed individually, e.g. `#[allow(non_snake_case)]` on `pub fn FIRE() -> StringName`.
+    pub fn strict_lints(mut self) -> Self {
+        self.strict_lints = true;
+        self
+    }
+
+    /// Prefix the names of generated scene consts, action consts and layer variants with `prefix`, to avoid collisions when
+    /// glob importing several generated modules.
+    ///
+    /// The prefix is converted to `SCREAMING_SNAKE_CASE` and joined with an underscore, e.g. `const_prefix("scene")` generates
+    /// `SCENE_MAIN` rather than `MAIN`, and `SCENE_MAIN_NAME()` rather than `MAIN_NAME()`.
+    pub fn const_prefix(mut self, prefix: &str) -> Self {
+        self.const_prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}_", to_const_name(prefix))
+        };
+        self
+    }
+
+    /// Set build-time environment variables from `project.godot`, which can be read in your crate with `env!`.
+    ///
+    /// `ZGRCG_PROJECT_NAME` is set from `config/name`, and `ZGRCG_GODOT_VERSION` from the version in `config/features`, e.g. `4.5`.
+    /// Variables missing from `project.godot` are not set, so use `option_env!` if they may be missing.
+    pub fn export_env_vars(mut self) -> Self {
+        self.export_env_vars = true;
+        self
+    }
+
+    /// Indent generated code with `indent` for each level, e.g. `"\t"`, rather than four spaces.
+    pub fn set_indent(mut self, indent: &str) -> Self {
+        if indent.is_empty() || !indent.chars().all(|c| c == ' ' || c == '\t') {
+            self.validation_errors.push(format!(
+                "Indent must be one or more spaces or tabs, got {:?}",
+                indent
+            ));
+            return self;
+        }
+
+        self.indent = indent.to_string();
+        self
+    }
+
+    /// End the lines of generated code with `line_ending`, rather than `LineEnding::Lf`.
+    pub fn set_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Run `callback` once generation succeeds, with the path of every file written, e.g. to copy files or run your own codegen.
+    ///
+    /// The callback is not run if generation fails. It must be `'static`, so move any state it uses into it, and `Send + Sync`,
+    /// so the `Generator` holding it can be shared across threads, e.g. wrap mutable state in a `Mutex`. Setting it again
+    /// replaces the previous callback.
+    pub fn on_complete(mut self, callback: Box<OnCompleteCallback>) -> Self {
+        self.on_complete = Some(OnComplete(Arc::from(callback)));
+        self
+    }
+}
+
+/// Formats a `cargo:rerun-if-changed` line for each path, once per path in order of first use.
+fn rerun_if_changed_lines(paths: Vec<&str>) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for path in paths {
+        let line = format!("cargo:rerun-if-changed={}", path);
+        if !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+    lines
+}
+/// Formats a `cargo:rustc-env` line for each environment variable exported from the project.
+fn env_var_lines(project: &ProjectGodot) -> Vec<String> {
+    let application = project.application.as_ref();
+    let name = application.and_then(|a| a.name);
+    let version = application
+        .and_then(|a| a.features.as_ref())
+        .and_then(|features| {
+            features
+                .iter()
+                .find(|feature| parse_godot_version(feature).is_some())
+        });
+
+    [
+        ("ZGRCG_PROJECT_NAME", name),
+        ("ZGRCG_GODOT_VERSION", version.copied()),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| value.map(|value| format!("cargo:rustc-env={}={}", key, value)))
+    .collect()
+}
+#[test]
+fn test_env_var_lines() {
+    let project = ProjectGodot::parse_from_str(
+        r#"config_version=5
+
+[application]
+
+config/name="Example"
+config/features=PackedStringArray("4.5", "GL Compatibility")
+"#,
+    );
+    assert_eq!(
+        env_var_lines(&project),
+        vec![
+            "cargo:rustc-env=ZGRCG_PROJECT_NAME=Example",
+            "cargo:rustc-env=ZGRCG_GODOT_VERSION=4.5"
+        ]
+    );
+
+    let project = ProjectGodot::parse_from_str(
+        "config_version=5\n\n[application]\n\nconfig/features=PackedStringArray(\"Forward Plus\")\n",
+    );
+    assert_eq!(env_var_lines(&project), Vec::<String>::new());
+}
+
+#[test]
+fn test_rerun_if_changed_lines() {
+    assert_eq!(
+        rerun_if_changed_lines(vec![
+            "./project.godot",
+            "./rust.gdextension",
+            "./project.godot"
+        ]),
+        vec![
+            "cargo:rerun-if-changed=./project.godot",
+            "cargo:rerun-if-changed=./rust.gdextension"
+        ]
+    );
+}
+
+#[test]
+fn test_set_resource_path_trailing_slash() {
+    let dir = utils::TestDir::new("set_resource_path");
+    fs::create_dir_all(dir.join("scenes")).unwrap();
+
+    let generator = Generator::builder().set_resource_path(&format!("{}/", dir.display()));
+    assert!(generator.resource_path_valid);
+    assert!(!generator.resource_path.ends_with('/'));
+    assert_eq!(
+        utils::to_resource_path(
+            &format!("{}/scenes/Main.tscn", generator.resource_path),
+            &generator.resource_path
+        ),
+        "res://scenes/Main.tscn"
+    );
+}
+
+#[test]
+fn test_with_parsed_project() {
+    let generator = Generator::builder().set_project_godot_path("./example/project.godot");
+    assert_eq!(
+        generator
+            .with_parsed_project(|project| project.config_version)
+            .unwrap(),
+        Some(5)
+    );
+
+    assert!(
+        Generator::builder()
+            .with_parsed_project(|project| project.config_version)
+            .is_err()
+    );
+}
+#[test]
+fn test_parse_project() {
+    let project = Generator::builder()
+        .set_project_godot_path("./example/project.godot")
+        .parse_project()
+        .unwrap();
+    assert_eq!(project.config_version, Some(5));
+    assert_eq!(project.name.as_deref(), Some("ExampleProject"));
+
+    assert!(Generator::builder().parse_project().is_err());
+}
+
+#[test]
+fn test_add_source_path() {
+    let generator = Generator::builder()
+        .set_source_path("./src")
+        .add_source_path("./example/src")
+        .add_source_path("./src");
+    assert!(generator.validation_errors.is_empty());
+    assert_eq!(generator.source_paths(), vec!["./src", "./example/src"]);
+
+    let generator = Generator::builder()
+        .add_source_path("")
+        .add_source_path("./does/not/exist");
+    assert_eq!(generator.validation_errors.len(), 2);
+    assert_eq!(generator.source_paths(), vec!["./src"]);
+}
+#[test]
+fn test_create_gdextension_if_missing() {
+    let dir = utils::TestDir::new("create_gdextension_if_missing");
+    let gdextension_path = dir.join("rust.gdextension");
+
+    // the file is only written when generating, so the calls can come in either order
+    let generator = Generator::builder()
+        .set_gdextension_path(gdextension_path.to_str().unwrap())
+        .create_gdextension_if_missing(
+            "gdext_rust_init",
+            &[(
+                "linux.debug.x86_64",
+                "res://../rust/target/debug/librust.so",
+            )],
+        );
+    assert_eq!(generator.validate(), Ok(()));
+    assert!(!gdextension_path.exists());
+    assert_eq!(
+        generator.try_generate().unwrap(),
+        vec![gdextension_path.clone()]
+    );
+
+    let content = fs::read_to_string(&gdextension_path).unwrap();
+    assert!(content.contains("[configuration]\nentry_symbol=\"gdext_rust_init\""));
+    assert!(
+        content
+            .contains("[libraries]\nlinux.debug.x86_64=\"res://../rust/target/debug/librust.so\"")
+    );
+
+    // an existing file is left untouched
+    fs::write(
+        &gdextension_path,
+        "[configuration]\nentry_symbol=\"custom\"\n",
+    )
+    .unwrap();
+    let generator = Generator::builder()
+        .create_gdextension_if_missing("gdext_rust_init", &[("linux", "res://librust.so")])
+        .set_gdextension_path(gdextension_path.to_str().unwrap());
+    assert_eq!(generator.try_generate().unwrap(), Vec::<PathBuf>::new());
+    assert_eq!(
+        fs::read_to_string(&gdextension_path).unwrap(),
+        "[configuration]\nentry_symbol=\"custom\"\n"
+    );
+
+    let generator = Generator::builder()
+        .set_gdextension_path(gdextension_path.to_str().unwrap())
+        .create_gdextension_if_missing("not a symbol", &[]);
+    assert_eq!(generator.validation_errors.len(), 2);
+
+    // without it, a missing file is a validation error
+    assert_eq!(
+        Generator::builder()
+            .set_gdextension_path(dir.join("missing.gdextension").to_str().unwrap())
+            .validate(),
+        Err(vec![format!(
+            "gdextension path does not exist: {}",
+            dir.join("missing.gdextension").display()
+        )])
+    );
+}
+#[test]
+fn test_library_consts_read_error() {
+    let dir = utils::TestDir::new("library_consts_read_error");
+    fs::create_dir_all(dir.join("rust.gdextension")).unwrap();
+
+    // the path exists, but is a directory, so can't be read
+    let generator = Generator::builder()
+        .set_output_dir(dir.join("generated").to_str().unwrap())
+        .set_gdextension_path(dir.join("rust.gdextension").to_str().unwrap())
+        .output_library_consts();
+    assert!(matches!(
+        generator.try_generate(),
+        Err(GeneratorError::Io(_))
+    ));
+}
+#[test]
+fn test_set_target_godot_version() {
+    let generator = Generator::builder().set_target_godot_version("4.5");
+    assert!(generator.validation_errors.is_empty());
+    assert_eq!(generator.target_godot_version.as_deref(), Some("4.5"));
+
+    let generator = Generator::builder().set_target_godot_version("four");
+    assert_eq!(generator.validation_errors.len(), 1);
+}
+#[test]
+fn test_try_generate_returns_written_paths() {
+    let dir = utils::TestDir::new("try_generate_returns_written_paths");
+
+    let mut written = Generator::builder()
+        .set_output_dir(dir.to_str().unwrap())
+        .set_project_godot_path("./example/project.godot")
+        .set_resource_path("./example/gd")
+        .output_layer_consts()
+        .output_scene_consts()
+        .output_scene_dependencies()
+        .try_generate()
+        .unwrap();
+    written.sort();
+    assert_eq!(
+        written,
+        vec![
+            dir.join("layer_consts.rs"),
+            dir.join("mod.rs"),
+            dir.join("scene_consts.rs"),
+            dir.join("scene_dependencies.rs"),
+        ]
+    );
+
+    let invalid = Generator::builder()
+        .set_project_godot_path("./does/not/exist/project.godot")
+        .try_generate();
+    assert!(matches!(invalid, Err(GeneratorError::Validation(_))));
+}
+#[test]
+fn test_on_complete() {
+    let dir = utils::TestDir::new("on_complete");
+
+    let completed: Arc<std::sync::Mutex<Vec<PathBuf>>> = Arc::default();
+    let captured = completed.clone();
+    let generator = Generator::builder()
+        .set_output_dir(dir.to_str().unwrap())
+        .set_project_godot_path("./example/project.godot")
+        .output_layer_consts()
+        .on_complete(Box::new(move |paths| {
+            captured.lock().unwrap().extend_from_slice(paths)
+        }));
+
+    // the generator stays shareable across threads with a callback set
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    assert_send_sync(&generator);
+
+    generator.generate();
+    let mut paths = completed.lock().unwrap().clone();
+    paths.sort();
+    assert_eq!(paths, vec![dir.join("layer_consts.rs"), dir.join("mod.rs")]);
+
+    // not run when generation fails
+    completed.lock().unwrap().clear();
+    generator
+        .clone()
+        .set_project_godot_path("./does/not/exist/project.godot")
+        .generate();
+    assert!(completed.lock().unwrap().is_empty());
+
+    // a skipped run is given the paths written by the last run
+    let generator = generator.skip_unchanged();
+    generator.generate();
+    completed.lock().unwrap().clear();
+    generator.generate();
+    let mut paths = completed.lock().unwrap().clone();
+    paths.sort();
+    assert_eq!(paths, vec![dir.join("layer_consts.rs"), dir.join("mod.rs")]);
+}
+#[test]
+fn test_clean_orphans() {
+    let dir = utils::TestDir::new("clean_orphans");
+
+    Generator::builder()
+        .set_output_dir(dir.to_str().unwrap())
+        .set_resource_path("./example/gd")
+        .output_scene_consts()
+        .output_scene_actions()
+        .try_generate()
+        .unwrap();
+    assert!(dir.join("scene_actions.rs").exists());
+
+    // disabling scene actions removes the stale file and its module declaration
+    Generator::builder()
+        .set_output_dir(dir.to_str().unwrap())
+        .set_resource_path("./example/gd")
+        .output_scene_consts()
+        .clean_orphans()
+        .try_generate()
+        .unwrap();
+    assert!(dir.join("scene_consts.rs").exists());
+    assert!(!dir.join("scene_actions.rs").exists());
+    assert_eq!(
+        fs::read_to_string(dir.join("mod.rs")).unwrap(),
+        "pub mod scene_consts;\n"
+    );
+
+    // switching to a single file removes the generated files of the previous run, keeping the mod.rs
+    let single_file_dir = utils::TestDir::new("clean_orphans_single");
+    let single_file = single_file_dir.join("generated.rs");
+    Generator::builder()
+        .set_output_dir(dir.to_str().unwrap())
+        .set_project_godot_path("./example/project.godot")
+        .output_layer_consts()
+        .single_file_output(single_file.to_str().unwrap())
+        .clean_orphans()
+        .try_generate()
+        .unwrap();
+    assert!(single_file.exists());
+    assert!(!dir.join("scene_consts.rs").exists());
+    assert!(dir.join("mod.rs").exists());
+}
+#[test]
+fn test_validate() {
+    let dir = utils::TestDir::new("validate");
+
+    assert_eq!(
+        Generator::builder()
+            .set_output_dir(dir.to_str().unwrap())
+            .set_project_godot_path("./example/project.godot")
+            .set_resource_path("./example/gd")
+            .output_layer_consts()
+            .output_scene_consts()
+            .validate(),
+        Ok(())
+    );
+
+    // the validation doesn't write anything
+    assert!(fs::read_dir(&dir).unwrap().next().is_none());
+
+    let missing_paths = Generator::builder()
+        .set_project_godot_path("./does/not/exist/project.godot")
+        .set_resource_path("./does/not/exist")
+        .validate()
+        .unwrap_err();
+    assert_eq!(missing_paths.len(), 2);
+
+    let empty_strings = Generator::builder()
+        .set_output_dir("")
+        .set_gdextension_path("")
+        .add_source_path("")
+        .validate()
+        .unwrap_err();
+    assert_eq!(
+        empty_strings,
+        vec![
+            "Output directory must be set, and cannot be empty",
+            "gdextension path must be set with `set_gdextension_path`",
+            "Additional source paths must be non-empty strings",
+        ]
+    );
+
+    let missing_requirements = Generator::builder()
+        .output_layer_consts()
+        .output_icon_comments()
+        .validate()
+        .unwrap_err();
+    assert_eq!(missing_requirements.len(), 3);
+
+    // a bare user class has no path to implement scene actions for
+    let scene_actions = |impl_for: &str| {
+        Generator::builder()
+            .set_output_dir(dir.to_str().unwrap())
+            .set_resource_path("./example/gd")
+            .output_scene_actions()
+            .scene_actions_impl_for(impl_for)
+            .custom_class_paths(&[("SceneManager", "crate::SceneManager")])
+            .validate()
+    };
+    assert_eq!(
+        scene_actions("GameState"),
+        Err(vec![
+            "Scene actions impl type GameState is not an engine class, give its full path, e.g. `crate::GameState`, or add it to `custom_class_paths`".to_string()
+        ])
+    );
+    assert_eq!(scene_actions("Control"), Ok(()));
+    assert_eq!(scene_actions("crate::GameState"), Ok(()));
+    assert_eq!(scene_actions("SceneManager"), Ok(()));
+}
+#[test]
+fn test_generator_clone() {
+    let base = Generator::builder()
+        .set_resource_path("./example/gd")
+        .output_scene_consts();
+    let variant = base.clone().output_scene_nodes();
+
+    assert!(!base.scene_nodes);
+    assert!(variant.scene_nodes);
+    assert_eq!(base.resource_path, variant.resource_path);
+    assert!(format!("{:?}", variant).contains("scene_nodes: true"));
+}
+#[test]
+fn test_use_out_dir() {
+    let dir = utils::TestDir::new("use_out_dir");
+    let dir = dir.to_str().unwrap();
+
+    // OUT_DIR is only read when generating
+    let generator = Generator::builder().use_out_dir();
+    assert!(generator.use_out_dir);
+    assert!(generator.output_dir.is_empty());
+
+    // the last of `use_out_dir` and `set_output_dir` wins
+    let generator = generator.set_output_dir(dir);
+    assert!(!generator.use_out_dir);
+    assert_eq!(generator.output_dir, dir);
+    assert!(generator.use_out_dir().use_out_dir);
+}
+#[test]
+fn test_generate_with_empty_project_godot() {
+    let dir = utils::TestDir::new("generate_with_empty_project_godot");
+    fs::create_dir_all(dir.join("generated")).unwrap();
+    fs::create_dir_all(dir.join("godot")).unwrap();
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("project.godot"), "\n").unwrap();
+    fs::write(dir.join("rust.gdextension"), "").unwrap();
+
+    let generator = Generator::builder()
+        .set_output_dir(dir.join("generated").to_str().unwrap())
+        .set_project_godot_path(dir.join("project.godot").to_str().unwrap())
+        .set_gdextension_path(dir.join("rust.gdextension").to_str().unwrap())
+        .set_resource_path(dir.join("godot").to_str().unwrap())
+        .set_source_path(dir.join("src").to_str().unwrap())
+        .output_layer_consts()
+        .output_action_consts()
+        .output_action_invocations()
+        .output_action_registration()
+        .output_default_bindings()
+        .output_icon_comments()
+        .output_scene_consts()
+        .output_scene_actions()
+        .output_scene_nodes()
+        .output_scene_dependencies()
+        .output_class_registry();
+
+    let written = generator.try_generate().unwrap();
+    assert!(!written.contains(&dir.join("generated").join("layer_consts.rs")));
+    assert!(!dir.join("generated").join("layer_consts.rs").exists());
+    generator.generate();
+}
+#[test]
+fn test_export_action_map_json() {
+    let dir = utils::TestDir::new("export_action_map_json");
+    let json_path = dir.join("json").join("actions.json");
+
+    assert!(
+        Generator::builder()
+            .export_action_map_json(json_path.to_str().unwrap())
+            .is_err()
+    );
+
+    Generator::builder()
+        .set_project_godot_path("./example/project.godot")
+        .export_action_map_json(json_path.to_str().unwrap())
+        .unwrap();
+
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(json.starts_with("{\n  \"actions\": [\n    {\n      \"name\": "));
+    assert!(json.ends_with("  ]\n}\n"));
+}
+#[test]
+fn test_output_keybindings_markdown() {
+    let dir = utils::TestDir::new("output_keybindings_markdown");
+    let markdown_path = dir.join("docs").join("keybindings.md");
+
+    let written = Generator::builder()
+        .set_project_godot_path("./example/project.godot")
+        .output_keybindings_markdown(markdown_path.to_str().unwrap())
+        .try_generate()
+        .unwrap();
+
+    assert_eq!(written, vec![markdown_path.clone()]);
+    assert!(
+        fs::read_to_string(&markdown_path)
+            .unwrap()
+            .contains("|Fire|`left_click` or `J`|\n|jump|`SPACE`|\n")
+    );
+}
+#[test]
+fn test_output_gdscript_consts() {
+    let dir = utils::TestDir::new("output_gdscript_consts");
+    let gdscript_path = dir.join("godot").join("consts.gd");
+
+    let written = Generator::builder()
+        .set_project_godot_path("./example/project.godot")
+        .set_resource_path("./example/gd")
+        .output_gdscript_consts(gdscript_path.to_str().unwrap())
+        .try_generate()
+        .unwrap();
+
+    assert_eq!(written, vec![gdscript_path.clone()]);
+    let content = fs::read_to_string(&gdscript_path).unwrap();
+    assert!(content.contains("const MAIN := \"res://scenes/Main.tscn\"\n"));
+    assert!(content.contains("const FIRE := &\"Fire\"\n"));
+}
+#[test]
+fn test_set_override_cfg_path() {
+    let dir = utils::TestDir::new("set_override_cfg_path");
+    fs::write(
+        dir.join("project.godot"),
+        "config_version=5\n\n[application]\n\nconfig/name=\"Example\"\n\n[layer_names]\n\n2d_physics/layer_1=\"walls\"\n2d_physics/layer_2=\"floors\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("override.cfg"),
+        "[layer_names]\n\n2d_physics/layer_2=\"ground\"\n2d_physics/layer_3=\"enemies\"\n",
+    )
+    .unwrap();
+
+    let generator = Generator::builder()
+        .set_project_godot_path(dir.join("project.godot").to_str().unwrap())
+        .set_override_cfg_path(dir.join("override.cfg").to_str().unwrap());
+    let project = generator.parse_project().unwrap();
+
+    assert_eq!(project.name.as_deref(), Some("Example"));
+    assert_eq!(
+        project
+            .layer_names
+            .iter()
+            .map(|(key, name)| (key.as_str(), name.as_str()))
+            .collect::<Vec<(&str, &str)>>(),
+        vec![
+            ("2d_physics/layer_1", "walls"),
+            ("2d_physics/layer_2", "ground"),
+            ("2d_physics/layer_3", "enemies"),
+        ]
+    );
+
+    // a missing override.cfg is ignored
+    let project = Generator::builder()
+        .set_project_godot_path(dir.join("project.godot").to_str().unwrap())
+        .set_override_cfg_path(dir.join("missing.cfg").to_str().unwrap())
+        .parse_project()
+        .unwrap();
+    assert_eq!(project.layer_names.len(), 2);
+}
+#[test]
+fn test_skip_unchanged() {
+    let dir = utils::TestDir::new("skip_unchanged");
+    let output_dir = dir.to_str().unwrap();
+
+    let generator = Generator::builder()
+        .set_output_dir(output_dir)
+        .set_project_godot_path("./example/project.godot")
+        .output_layer_consts()
+        .skip_unchanged();
+
+    let written = generator.try_generate().unwrap();
+    assert!(written.contains(&dir.join("layer_consts.rs")));
+    let modified = fs::metadata(dir.join("layer_consts.rs"))
+        .unwrap()
+        .modified()
+        .unwrap();
+
+    assert_eq!(generator.try_generate().unwrap(), written);
+    assert_eq!(
+        fs::metadata(dir.join("layer_consts.rs"))
+            .unwrap()
+            .modified()
+            .unwrap(),
+        modified
+    );
+
+    // the hash of the configuration doesn't depend on the order icon sources are added in
+    let sources = [
+        ("res://icons/a/", "./icons/a"),
+        ("res://icons/b/", "./icons/b"),
+        ("res://icons/c/", "./icons/c"),
+    ];
+    let with_sources = |sources: &[(&str, &str)]| {
+        sources
+            .iter()
+            .fold(generator.clone(), |generator, (prefix, source)| {
+                generator.add_icon_source(prefix, source)
+            })
+            .inputs_hash()
+    };
+    let reversed = sources.iter().rev().copied().collect::<Vec<(&str, &str)>>();
+    assert_eq!(with_sources(&sources), with_sources(&reversed));
+
+    // a change to the configuration regenerates
+    let generator = generator.layers_fill_unnamed();
+    assert!(
+        generator
+            .try_generate()
+            .unwrap()
+            .contains(&dir.join("layer_consts.rs"))
+    );
+
+    // as does a deleted output
+    fs::remove_file(dir.join("layer_consts.rs")).unwrap();
+    assert!(
+        generator
+            .try_generate()
+            .unwrap()
+            .contains(&dir.join("layer_consts.rs"))
+    );
+}
+#[test]
+fn test_output_prelude() {
+    let dir = utils::TestDir::new("output_prelude");
+    let output_dir = dir.to_str().unwrap();
+
+    let written = Generator::builder()
+        .set_output_dir(output_dir)
+        .set_project_godot_path("./example/project.godot")
+        .set_resource_path("./example/gd")
+        .output_layer_consts()
+        .output_action_consts()
+        .output_action_invocations()
+        .output_scene_consts()
+        .output_scene_actions()
+        .output_prelude()
+        .try_generate()
+        .unwrap();
+
+    assert!(written.contains(&dir.join("prelude.rs")));
+    assert_eq!(
+        fs::read_to_string(dir.join("prelude.rs")).unwrap(),
+        utils::with_generated_header(
+            "#![allow(unused_imports)]\n\npub use super::actions_invocations::InputActionInvocations;\npub use super::layer_consts::*;\npub use super::scene_actions::SceneActions;\n"
+        )
+    );
+    assert!(
+        fs::read_to_string(dir.join("mod.rs"))
+            .unwrap()
+            .contains("pub mod prelude;\n")
+    );
+}
+#[test]
+fn test_output_dir_created() {
+    let dir = utils::TestDir::new("output_dir_created");
+    let output_dir = dir.join("generated");
+
+    // the first build on a fresh checkout creates the output directory
+    let generator = Generator::builder()
+        .set_output_dir(output_dir.to_str().unwrap())
+        .set_project_godot_path("./example/project.godot")
+        .output_layer_consts()
+        .output_prelude()
+        .clean_orphans();
+    assert!(output_dir.exists());
+    // a stale generated file, which is removed even on the run that created the directory
+    fs::write(
+        output_dir.join("stale.rs"),
+        utils::with_generated_header("pub const STALE: i32 = 0;\n"),
+    )
+    .unwrap();
+
+    let written = generator.try_generate().unwrap();
+    assert!(written.contains(&output_dir.join("prelude.rs")));
+    assert!(!output_dir.join("stale.rs").exists());
+}
+#[test]
+fn test_single_file_output() {
+    let dir = utils::TestDir::new("single_file_output");
+    fs::create_dir_all(dir.join("generated")).unwrap();
+    let single_file = dir.join("generated.rs");
+
+    let written = Generator::builder()
+        .set_output_dir(dir.join("generated").to_str().unwrap())
+        .set_project_godot_path("./example/project.godot")
+        .output_layer_consts()
+        .output_action_consts()
+        .single_file_output(single_file.to_str().unwrap())
+        .try_generate()
+        .unwrap();
+
+    assert_eq!(written, vec![single_file.clone()]);
+    let content = fs::read_to_string(&single_file).unwrap();
+    assert!(content.starts_with(
+        "// @generated by zgrcg, do not edit by hand\n#[allow(dead_code)]\n#[allow(non_snake_case)]\npub mod actions_consts {\n    use godot::builtin::StringName;\n"
+    ));
+    assert!(content.contains("}\n\n#[allow(dead_code)]\npub mod layer_consts {\n"));
+    assert!(!content.contains("#!["));
+    assert_eq!(fs::read_dir(dir.join("generated")).unwrap().count(), 0);
+}
+#[test]
+fn test_const_prefix() {
+    assert_eq!(
+        Generator::builder().const_prefix("scene").const_prefix,
+        "SCENE_"
+    );
+    assert_eq!(
+        Generator::builder().const_prefix("SCENE_").const_prefix,
+        "SCENE_"
+    );
+    assert_eq!(
+        Generator::builder().const_prefix("myGame").const_prefix,
+        "MY_GAME_"
+    );
+    assert_eq!(Generator::builder().const_prefix("").const_prefix, "");
+}
+#[test]
+fn test_set_indent_and_line_ending() {
+    let dir = utils::TestDir::new("set_indent_and_line_ending");
+
+    let written = Generator::builder()
+        .set_output_dir(dir.to_str().unwrap())
+        .set_project_godot_path("./example/project.godot")
+        .output_layer_consts()
+        .set_indent("\t")
+        .set_line_ending(LineEnding::CrLf)
+        .try_generate()
+        .unwrap();
+
+    assert_eq!(written.len(), 2);
+    for path in &written {
+        let content = fs::read_to_string(path).unwrap();
+        assert_eq!(
+            content.matches('\n').count(),
+            content.matches("\r\n").count()
+        );
+        assert!(!content.contains("\n "), "{}", path.display());
+    }
+    let layers = fs::read_to_string(dir.join("layer_consts.rs")).unwrap();
+    assert!(layers.contains("{\r\n\t"));
+    assert!(layers.contains("\r\n\t\tmatch self {\r\n"));
+
+    assert_eq!(
+        Generator::builder().set_indent("--").validate(),
+        Err(vec![
+            "Indent must be one or more spaces or tabs, got \"--\"".into()
+        ])
+    );
+}
+#[test]
+fn test_strict_lints() {
+    let dir = utils::TestDir::new("strict_lints");
+
+    let generate = |strict_lints: bool| {
+        let generator = Generator::builder()
+            .set_output_dir(dir.to_str().unwrap())
+            .set_project_godot_path("./example/project.godot")
+            .output_layer_consts()
+            .output_action_consts()
+            .output_action_invocations();
+        if strict_lints {
+            generator.strict_lints().try_generate().unwrap()
+        } else {
+            generator.try_generate().unwrap()
+        }
+    };
+
+    let read_consts = |written: &[PathBuf]| {
+        let path = written
+            .iter()
+            .find(|p| p.to_string_lossy().ends_with("actions_consts.rs"))
+            .unwrap();
+        fs::read_to_string(path).unwrap()
+    };
+
+    let written = generate(true);
+    for path in written.iter().filter(|p| !p.ends_with("mod.rs")) {
+        let content = fs::read_to_string(path).unwrap();
+        assert!(!content.contains("#![allow("), "{}", path.display());
+    }
+    assert!(read_consts(&written).contains("#[allow(non_snake_case)]\npub fn "));
+
+    let written = generate(false);
+    let consts = read_consts(&written);
+    assert!(consts.contains("#![allow(dead_code)]\n#![allow(non_snake_case)]\n"));
+    assert!(!consts.contains("#[allow(non_snake_case)]"));
+
+    for path in written {
+        let _ = fs::remove_file(path);
+    }
 }
@@ -0,0 +1,185 @@
+use std::{io, path::Path};
+
+use crate::{
+    gdextension::Gdextension,
+    mod_file::GeneratedModule,
+    utils::{format_lint_allows, write_generated_file},
+};
+
+const MOD_LIBRARIES: &str = "library_consts";
+
+/// Generates a `LIBRARY` const for the `[libraries]` entry of the `.gdextension` matching the target being compiled for.
+///
+/// Entries with a feature tag which doesn't map to a Rust `cfg`, e.g. `editor` or `double`, are skipped with a warning.
+pub fn generate_library_consts(
+    output_dir: &str,
+    gdextension: &Gdextension,
+    strict_lints: bool,
+) -> io::Result<Vec<GeneratedModule>> {
+    let Some(libraries) = gdextension
+        .libraries
+        .as_ref()
+        .filter(|libraries| !libraries.libraries.is_empty())
+    else {
+        println!(
+            "cargo::warning=No libraries found in the .gdextension, skipping library_consts.rs generation"
+        );
+        return Ok(vec![]);
+    };
+
+    let mut entries: Vec<(&str, Vec<&str>, &str)> = vec![];
+    for (key, path) in &libraries.libraries {
+        match library_cfg_predicates(key) {
+            Ok(predicates) => entries.push((key, predicates, path)),
+            Err(tag) => println!(
+                "cargo::warning=Library {} has the tag {} which has no Rust cfg, skipping",
+                key, tag
+            ),
+        }
+    }
+    // the first matching entry is used, so more specific entries come first, e.g. `macos.debug.arm64` before `macos.debug`
+    entries.sort_by_key(|(_, predicates, _)| std::cmp::Reverse(predicates.len()));
+
+    let libraries_path = Path::new(output_dir).join(format!("{}.rs", MOD_LIBRARIES));
+
+    write_generated_file(
+        &libraries_path,
+        &format_libraries_to_consts(&entries, strict_lints),
+    )?;
+
+    Ok(vec![GeneratedModule {
+        name: MOD_LIBRARIES.to_string(),
+        path: libraries_path,
+    }])
+}
+
+/// Maps the tags of a `[libraries]` key to Rust `cfg` predicates, or returns the first tag without one.
+///
+/// e.g. `windows.release.x86_64` -> `["target_os = \"windows\"", "not(debug_assertions)", "target_arch = \"x86_64\""]`
+fn library_cfg_predicates(key: &str) -> Result<Vec<&'static str>, &str> {
+    key.split('.')
+        .filter_map(|tag| match tag {
+            "windows" => Some(Ok("target_os = \"windows\"")),
+            "linux" => Some(Ok("target_os = \"linux\"")),
+            "macos" => Some(Ok("target_os = \"macos\"")),
+            "android" => Some(Ok("target_os = \"android\"")),
+            "ios" => Some(Ok("target_os = \"ios\"")),
+            "web" => Some(Ok("target_family = \"wasm\"")),
+            "debug" | "template_debug" => Some(Ok("debug_assertions")),
+            "release" | "template_release" => Some(Ok("not(debug_assertions)")),
+            "x86_64" => Some(Ok("target_arch = \"x86_64\"")),
+            "x86_32" => Some(Ok("target_arch = \"x86\"")),
+            "arm64" => Some(Ok("target_arch = \"aarch64\"")),
+            "arm32" => Some(Ok("target_arch = \"arm\"")),
+            "rv64" => Some(Ok("target_arch = \"riscv64\"")),
+            "wasm32" => Some(Ok("target_arch = \"wasm32\"")),
+            // a universal macOS library matches every architecture
+            "universal" => None,
+            _ => Some(Err(tag)),
+        })
+        .collect()
+}
+#[test]
+fn test_library_cfg_predicates() {
+    assert_eq!(
+        library_cfg_predicates("windows.release.x86_64"),
+        Ok(vec![
+            "target_os = \"windows\"",
+            "not(debug_assertions)",
+            "target_arch = \"x86_64\""
+        ])
+    );
+    assert_eq!(
+        library_cfg_predicates("linux.release.x86_64"),
+        Ok(vec![
+            "target_os = \"linux\"",
+            "not(debug_assertions)",
+            "target_arch = \"x86_64\""
+        ])
+    );
+    assert_eq!(
+        library_cfg_predicates("macos.template_debug.universal"),
+        Ok(vec!["target_os = \"macos\"", "debug_assertions"])
+    );
+    assert_eq!(library_cfg_predicates("linux.editor.x86_64"), Err("editor"));
+    assert_eq!(
+        library_cfg_predicates("windows.debug.double"),
+        Err("double")
+    );
+}
+
+/// Formats `(key, cfg predicates, path)` entries, in the order they're checked, into the content of the library consts file.
+fn format_libraries_to_consts(entries: &[(&str, Vec<&str>, &str)], strict_lints: bool) -> String {
+    format!(
+        r#"{}
+/// The library in the `.gdextension` for the target being compiled for, or `None` if no library matches it.
+pub const LIBRARY: Option<&str> = library();
+
+/// Checks each `[libraries]` entry in turn, most specific first.
+const fn library() -> Option<&'static str> {{
+{}    None
+}}"#,
+        format_lint_allows(strict_lints, false),
+        entries
+            .iter()
+            .map(|(key, predicates, path)| {
+                format!(
+                    "    // {}\n    if cfg!(all({})) {{\n        return Some(\"{}\");\n    }}\n",
+                    key,
+                    predicates.join(", "),
+                    path
+                )
+            })
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_libraries_to_consts() {
+    let expected = r#"#![allow(dead_code)]
+
+/// The library in the `.gdextension` for the target being compiled for, or `None` if no library matches it.
+pub const LIBRARY: Option<&str> = library();
+
+/// Checks each `[libraries]` entry in turn, most specific first.
+const fn library() -> Option<&'static str> {
+    // linux.release.x86_64
+    if cfg!(all(target_os = "linux", not(debug_assertions), target_arch = "x86_64")) {
+        return Some("res://../rust/target/release/librust.so");
+    }
+    // windows.release.x86_64
+    if cfg!(all(target_os = "windows", not(debug_assertions), target_arch = "x86_64")) {
+        return Some("res://../rust/target/release/rust.dll");
+    }
+    None
+}"#;
+
+    let entries = ["linux.release.x86_64", "windows.release.x86_64"]
+        .iter()
+        .zip([
+            "res://../rust/target/release/librust.so",
+            "res://../rust/target/release/rust.dll",
+        ])
+        .map(|(key, path)| (*key, library_cfg_predicates(key).unwrap(), path))
+        .collect::<Vec<(&str, Vec<&str>, &str)>>();
+
+    assert_eq!(format_libraries_to_consts(&entries, false), expected);
+}
+#[test]
+fn test_generate_library_consts() {
+    let dir = crate::utils::TestDir::new("generate_library_consts");
+
+    let content = std::fs::read_to_string("./example/rust.gdextension").unwrap();
+    let modules = generate_library_consts(
+        dir.to_str().unwrap(),
+        &Gdextension::parse_from_str(&content),
+        false,
+    )
+    .unwrap();
+    assert_eq!(modules.len(), 1);
+
+    let generated = std::fs::read_to_string(dir.join("library_consts.rs")).unwrap();
+    // the arm64 entries are more specific than those for any macOS architecture, so are checked first
+    let arm64 = generated.find("// macos.debug.arm64").unwrap();
+    let universal = generated.find("// macos.debug\n").unwrap();
+    assert!(arm64 < universal);
+}
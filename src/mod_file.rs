@@ -1,18 +1,698 @@
-use std::{fs::File, io::Write, path::Path};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::utils::{
+    GENERATED_MARKER, LiteralState, make_path_if_not_exists, with_generated_header,
+    write_generated_file,
+};
 
 const MOD_FILE: &str = "mod.rs";
+const MOD_PRELUDE: &str = "prelude";
+
+/// The item re-exported by the prelude for each generated module which defines traits or types, by module name.
+const PRELUDE_EXPORTS: &[(&str, &str)] = &[
+    ("actions_enum", "InputAction"),
+    ("actions_invocations", "InputActionInvocations"),
+    ("actions_registration", "register_all_actions"),
+    ("layer_consts", "*"),
+    ("scene_actions", "SceneActions"),
+];
+
+/// A module written to the output directory by one of the generators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedModule {
+    /// The name of the module, as declared in `mod.rs`.
+    pub name: String,
+    /// The path of the file the module was written to.
+    pub path: PathBuf,
+}
 
-pub fn write_mod_file(output_dir: &str, mods: Vec<String>) {
+/// Writes the `mod.rs` for the output directory, merging the generated modules into any existing declarations.
+///
+/// Modules and other lines added by hand to an existing `mod.rs` are preserved. Module declarations are written sorted and deduplicated.
+///
+/// When `reexport` is set, each generated module is also glob re-exported with `pub use <module>::*;`.
+///
+/// Declarations of the `removed` modules, e.g. orphans deleted by `remove_orphans`, are dropped.
+///
+/// Returns the path of the written `mod.rs`.
+pub fn write_mod_file(
+    output_dir: &str,
+    mods: Vec<String>,
+    removed: &[String],
+    reexport: bool,
+) -> io::Result<PathBuf> {
     let mod_file_path = Path::new(output_dir).join(MOD_FILE);
-    let mut mod_file = File::create(mod_file_path).unwrap();
+    let existing = fs::read_to_string(&mod_file_path).unwrap_or_default();
+
+    fs::write(
+        &mod_file_path,
+        merge_mod_file(&existing, mods, removed, reexport),
+    )?;
+    Ok(mod_file_path)
+}
+#[test]
+fn test_write_mod_file_preserves_manual_modules() {
+    let dir = crate::utils::TestDir::new("write_mod_file");
+    let output_dir = dir.to_str().unwrap();
+
+    write_mod_file(output_dir, vec!["scene_consts".into()], &[], false).unwrap();
+    let manual = fs::read_to_string(dir.join(MOD_FILE)).unwrap()
+        + "/// Hand written helpers.\n#[cfg(test)]\npub mod helpers;\n";
+    fs::write(dir.join(MOD_FILE), manual).unwrap();
+
+    write_mod_file(output_dir, vec!["scene_consts".into()], &[], false).unwrap();
+    let content = fs::read_to_string(dir.join(MOD_FILE)).unwrap();
+    assert!(content.contains("/// Hand written helpers.\n#[cfg(test)]\npub mod helpers;\n"));
+    assert_eq!(content.matches("pub mod scene_consts;").count(), 1);
+}
+
+fn merge_mod_file(existing: &str, mods: Vec<String>, removed: &[String], reexport: bool) -> String {
+    let mut generated = mods
+        .into_iter()
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<String>>();
+
+    // sort and dedupe so the output is stable regardless of feature order
+    generated.sort();
+    generated.dedup();
+
+    // each declared module with the attribute and doc comment lines leading its declaration
+    let mut declared: BTreeMap<String, Vec<&str>> =
+        generated.iter().map(|m| (m.clone(), vec![])).collect();
+    let mut leading_lines: Vec<&str> = vec![];
+    let mut other_lines: Vec<&str> = vec![];
+
+    for line in existing.lines() {
+        if parse_mod_declaration(line)
+            .or_else(|| parse_glob_reexport(line))
+            .is_some_and(|m| removed.iter().any(|r| r == m))
+        {
+            leading_lines.clear();
+        } else if let Some(m) = parse_mod_declaration(line) {
+            declared
+                .entry(m.to_string())
+                .or_default()
+                .append(&mut leading_lines);
+        } else if is_leading_line(line) {
+            leading_lines.push(line);
+        } else if parse_glob_reexport(line).is_some_and(|m| generated.iter().any(|g| g == m)) {
+            // re-exports of generated modules are rewritten below, depending on `reexport`
+            leading_lines.clear();
+        } else if !line.trim().is_empty() {
+            other_lines.append(&mut leading_lines);
+            other_lines.push(line);
+        }
+    }
+    other_lines.append(&mut leading_lines);
+
+    let mut content = String::new();
+    for (m, leading_lines) in &declared {
+        for line in leading_lines {
+            content.push_str(line);
+            content.push('\n');
+        }
+        content.push_str(&format!("pub mod {};\n", m));
+    }
+
+    if reexport && !generated.is_empty() {
+        content.push('\n');
+        for m in &generated {
+            content.push_str(&format!("pub use {}::*;\n", m));
+        }
+    }
+
+    if !other_lines.is_empty() {
+        content.push('\n');
+        for line in other_lines {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+
+    content
+}
+#[test]
+fn test_merge_mod_file() {
+    assert_eq!(
+        merge_mod_file(
+            "",
+            vec!["actions_consts".into(), "layer_consts".into()],
+            &[],
+            false
+        ),
+        "pub mod actions_consts;\npub mod layer_consts;\n"
+    );
+    assert_eq!(
+        merge_mod_file(
+            "pub mod actions_consts;\npub mod helpers;\n\npub use helpers::*;\n",
+            vec!["actions_consts".into(), "scene_consts".into()],
+            &[],
+            false
+        ),
+        "pub mod actions_consts;\npub mod helpers;\npub mod scene_consts;\n\npub use helpers::*;\n"
+    );
+}
+
+#[test]
+fn test_merge_mod_file_keeps_leading_lines() {
+    let existing = "pub mod actions_consts;\n/// Hand written helpers.\n#[cfg(test)]\npub mod helpers;\n\n#[allow(unused)]\npub use helpers::*;\n// trailing comment\n";
+    assert_eq!(
+        merge_mod_file(
+            existing,
+            vec!["scene_consts".into(), "actions_consts".into()],
+            &[],
+            false
+        ),
+        "pub mod actions_consts;\n/// Hand written helpers.\n#[cfg(test)]\npub mod helpers;\npub mod scene_consts;\n\n#[allow(unused)]\npub use helpers::*;\n// trailing comment\n"
+    );
+
+    // the leading lines of a removed module are dropped with it
+    assert_eq!(
+        merge_mod_file(
+            "#[cfg(test)]\npub mod scene_actions;\npub mod scene_consts;\n",
+            vec!["scene_consts".into()],
+            &["scene_actions".into()],
+            false
+        ),
+        "pub mod scene_consts;\n"
+    );
+}
+
+#[test]
+fn test_merge_mod_file_sorts_and_dedupes() {
+    assert_eq!(
+        merge_mod_file(
+            "pub mod scene_consts;\n",
+            vec![
+                "scene_consts".into(),
+                "actions_invocations".into(),
+                "".into(),
+                "scene_actions".into(),
+                "actions_invocations".into(),
+            ],
+            &[],
+            false
+        ),
+        "pub mod actions_invocations;\npub mod scene_actions;\npub mod scene_consts;\n"
+    );
+}
+
+#[test]
+fn test_merge_mod_file_reexports() {
+    let reexported = merge_mod_file(
+        "pub mod helpers;\n",
+        vec!["scene_consts".into(), "actions_consts".into()],
+        &[],
+        true,
+    );
+    assert_eq!(
+        reexported,
+        "pub mod actions_consts;\npub mod helpers;\npub mod scene_consts;\n\npub use actions_consts::*;\npub use scene_consts::*;\n"
+    );
+
+    // regenerating keeps a single set of re-exports
+    assert_eq!(
+        merge_mod_file(
+            &reexported,
+            vec!["scene_consts".into(), "actions_consts".into()],
+            &[],
+            true
+        ),
+        reexported
+    );
+
+    // disabling the option removes the generated re-exports, but keeps manual ones
+    assert_eq!(
+        merge_mod_file(
+            &(reexported + "pub use helpers::*;\n"),
+            vec!["scene_consts".into(), "actions_consts".into()],
+            &[],
+            false
+        ),
+        "pub mod actions_consts;\npub mod helpers;\npub mod scene_consts;\n\npub use helpers::*;\n"
+    );
+}
+
+#[test]
+fn test_merge_mod_file_removed() {
+    assert_eq!(
+        merge_mod_file(
+            "pub mod helpers;\npub mod scene_actions;\npub mod scene_consts;\n\npub use scene_actions::*;\npub use scene_consts::*;\n",
+            vec!["scene_consts".into()],
+            &["scene_actions".into()],
+            true
+        ),
+        "pub mod helpers;\npub mod scene_consts;\n\npub use scene_consts::*;\n"
+    );
+}
+
+/// Writes the generated `mods` into a single file at `path`, each inlined as a `pub mod <name> { ... }` block, then removes their separate files.
+///
+/// Used instead of `write_mod_file`, so no `mod.rs` is written. When `reexport` is set, each module is also glob re-exported.
+///
+/// Returns the path of the written file.
+pub fn write_single_file(
+    path: &str,
+    mods: &[GeneratedModule],
+    reexport: bool,
+) -> io::Result<PathBuf> {
+    let mut contents = mods
+        .iter()
+        .map(|m| fs::read_to_string(&m.path).map(|content| (m.name.clone(), content)))
+        .collect::<io::Result<Vec<(String, String)>>>()?;
+    contents.sort();
+    contents.dedup_by(|a, b| a.0 == b.0);
+
+    make_path_if_not_exists(path)?;
+    fs::write(
+        path,
+        with_generated_header(&format_single_file(&contents, reexport)),
+    )?;
+
+    for m in mods {
+        if m.path != Path::new(path) {
+            fs::remove_file(&m.path)?;
+        }
+    }
+
+    Ok(PathBuf::from(path))
+}
+
+/// Formats `(module name, module content)` pairs into the content of a single file with each module inlined.
+///
+/// The generated header of each module is dropped, and its leading `#![allow(...)]` inner attributes become `#[allow(...)]`
+/// attributes on the module, so the file can be used with either `mod` or `include!`.
+///
+/// Lines continuing a string literal are not indented, so the content of multi-line strings is unchanged.
+fn format_single_file(mods: &[(String, String)], reexport: bool) -> String {
+    let mut content = mods
+        .iter()
+        .map(|(name, module_content)| {
+            let (attributes, body) = split_inner_attributes(module_content);
+
+            let mut state = LiteralState::Code;
+            let body = body
+                .lines()
+                .map(|line| {
+                    let indented = if state != LiteralState::Code {
+                        line.to_string()
+                    } else if line.trim().is_empty() {
+                        String::new()
+                    } else {
+                        format!("    {}", line)
+                    };
+                    state = state.after_line(line);
+                    indented
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            format!(
+                "{}pub mod {} {{\n{}\n}}\n",
+                attributes,
+                name,
+                body.trim_matches('\n')
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if reexport && !mods.is_empty() {
+        content.push('\n');
+        for (name, _) in mods {
+            content.push_str(&format!("pub use {}::*;\n", name));
+        }
+    }
+
+    content
+}
+#[test]
+fn test_format_single_file() {
+    let mods = [
+        (
+            "actions_consts".to_string(),
+            with_generated_header(
+                "#![allow(dead_code)]\n#![allow(non_snake_case)]\nuse godot::builtin::StringName;\n\n/// Maps to: `J`\npub fn FIRE() -> StringName { StringName::from(\"Fire\") }",
+            ),
+        ),
+        (
+            "layer_consts".to_string(),
+            "#![allow(dead_code)]\n\npub enum Physics2d {\n    WALLS = 1,\n}\n".to_string(),
+        ),
+    ];
+
+    assert_eq!(
+        format_single_file(&mods, false),
+        r#"#[allow(dead_code)]
+#[allow(non_snake_case)]
+pub mod actions_consts {
+    use godot::builtin::StringName;
+
+    /// Maps to: `J`
+    pub fn FIRE() -> StringName { StringName::from("Fire") }
+}
+
+#[allow(dead_code)]
+pub mod layer_consts {
+    pub enum Physics2d {
+        WALLS = 1,
+    }
+}
+"#
+    );
+    assert!(
+        format_single_file(&mods, true)
+            .ends_with("}\n\npub use actions_consts::*;\npub use layer_consts::*;\n")
+    );
+}
+#[test]
+fn test_format_single_file_string_literals() {
+    let mods = [(
+        "docs".to_string(),
+        "pub const HELP: &str = r#\"\nfirst\n  second\n\"#;\npub const X: i32 = 1;\n".to_string(),
+    )];
+
+    assert_eq!(
+        format_single_file(&mods, false),
+        "pub mod docs {\n    pub const HELP: &str = r#\"\nfirst\n  second\n\"#;\n    pub const X: i32 = 1;\n}\n"
+    );
+}
 
+/// Splits the generated header and leading `#![allow(...)]` inner attributes from a generated file's content, returning the
+/// attributes as outer `#[allow(...)]` attributes for the module declaration, and the rest of the content.
+fn split_inner_attributes(content: &str) -> (String, String) {
+    let mut lines = content
+        .lines()
+        .skip_while(|line| line.starts_with(GENERATED_MARKER))
+        .peekable();
+
+    let mut attributes = String::new();
+    while let Some(allow) = lines
+        .peek()
+        .and_then(|line| line.trim().strip_prefix("#!["))
+    {
+        attributes.push_str(&format!("#[{}\n", allow));
+        lines.next();
+    }
+
+    (attributes, lines.collect::<Vec<&str>>().join("\n"))
+}
+#[test]
+fn test_split_inner_attributes() {
+    assert_eq!(
+        split_inner_attributes(&with_generated_header(
+            "#![allow(dead_code)]\n#![allow(non_snake_case)]\nuse godot::builtin::StringName;\n"
+        )),
+        (
+            "#[allow(dead_code)]\n#[allow(non_snake_case)]\n".to_string(),
+            "use godot::builtin::StringName;".to_string()
+        )
+    );
+    assert_eq!(
+        split_inner_attributes("pub const A: i32 = 1;\n#![allow(dead_code)]"),
+        (
+            String::new(),
+            "pub const A: i32 = 1;\n#![allow(dead_code)]".to_string()
+        )
+    );
+}
+
+/// Writes the `mod.rs` for an output directory in Cargo's `OUT_DIR`, declaring each generated module as a `pub mod <name>`
+/// block with an `include!` of its file, as plain `mod` declarations can't find files outside of the source tree.
+///
+/// Inner attributes aren't allowed in an `include!`d file, so each module's leading `#![allow(...)]` attributes are moved from
+/// its file onto its declaration. When `reexport` is set, each module is also glob re-exported.
+///
+/// Returns the path of the written `mod.rs`.
+pub fn write_include_mod_file(
+    output_dir: &str,
+    mods: &[GeneratedModule],
+    reexport: bool,
+) -> io::Result<PathBuf> {
+    let mut declarations = vec![];
     for m in mods {
-        if m.is_empty() {
+        let (attributes, body) = split_inner_attributes(&fs::read_to_string(&m.path)?);
+        fs::write(&m.path, with_generated_header(&format!("{}\n", body)))?;
+
+        let file_name = m
+            .path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("{}.rs", m.name));
+        declarations.push((m.name.clone(), attributes, file_name));
+    }
+    declarations.sort();
+    declarations.dedup_by(|a, b| a.0 == b.0);
+
+    let mod_file_path = Path::new(output_dir).join(MOD_FILE);
+    fs::write(
+        &mod_file_path,
+        format_include_mod_file(&declarations, reexport),
+    )?;
+    Ok(mod_file_path)
+}
+#[test]
+fn test_write_include_mod_file() {
+    let dir = crate::utils::TestDir::new("write_include_mod_file");
+
+    fs::write(
+        dir.join("layer_consts.rs"),
+        with_generated_header("#![allow(dead_code)]\n\npub enum Physics2d {\n    WALLS = 1,\n}\n"),
+    )
+    .unwrap();
+    let mods = [GeneratedModule {
+        name: "layer_consts".to_string(),
+        path: dir.join("layer_consts.rs"),
+    }];
+
+    let mod_file_path = write_include_mod_file(dir.to_str().unwrap(), &mods, false).unwrap();
+    assert_eq!(mod_file_path, dir.join(MOD_FILE));
+    assert_eq!(
+        fs::read_to_string(dir.join(MOD_FILE)).unwrap(),
+        "#[allow(dead_code)]\npub mod layer_consts {\n    include!(\"layer_consts.rs\");\n}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("layer_consts.rs")).unwrap(),
+        with_generated_header("\npub enum Physics2d {\n    WALLS = 1,\n}\n")
+    );
+}
+
+/// Formats `(module name, attributes, file name)` triples into the content of an `include!` style `mod.rs`.
+fn format_include_mod_file(mods: &[(String, String, String)], reexport: bool) -> String {
+    let mut content = mods
+        .iter()
+        .map(|(name, attributes, file_name)| {
+            format!(
+                "{}pub mod {} {{\n    include!({:?});\n}}\n",
+                attributes, name, file_name
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if reexport && !mods.is_empty() {
+        content.push('\n');
+        for (name, _, _) in mods {
+            content.push_str(&format!("pub use {}::*;\n", name));
+        }
+    }
+
+    content
+}
+#[test]
+fn test_format_include_mod_file() {
+    let mods = [
+        (
+            "actions_consts".to_string(),
+            "#[allow(dead_code)]\n#[allow(non_snake_case)]\n".to_string(),
+            "actions_consts.rs".to_string(),
+        ),
+        (
+            "prelude".to_string(),
+            "#[allow(unused_imports)]\n".to_string(),
+            "prelude.rs".to_string(),
+        ),
+    ];
+
+    assert_eq!(
+        format_include_mod_file(&mods, false),
+        r#"#[allow(dead_code)]
+#[allow(non_snake_case)]
+pub mod actions_consts {
+    include!("actions_consts.rs");
+}
+
+#[allow(unused_imports)]
+pub mod prelude {
+    include!("prelude.rs");
+}
+"#
+    );
+    assert!(
+        format_include_mod_file(&mods, true)
+            .ends_with("}\n\npub use actions_consts::*;\npub use prelude::*;\n")
+    );
+}
+
+/// Writes a `prelude.rs` to the output directory re-exporting the traits and types of the generated `mods`,
+/// so they can be brought into scope with `use crate::generated::prelude::*;`.
+///
+/// Returns the prelude module, to be declared in the `mod.rs` along with the other modules.
+pub fn write_prelude(output_dir: &str, mods: &[String]) -> io::Result<GeneratedModule> {
+    let prelude_path = Path::new(output_dir).join(format!("{}.rs", MOD_PRELUDE));
+
+    write_generated_file(&prelude_path, &format_prelude(mods))?;
+
+    Ok(GeneratedModule {
+        name: MOD_PRELUDE.to_string(),
+        path: prelude_path,
+    })
+}
+
+fn format_prelude(mods: &[String]) -> String {
+    format!(
+        "#![allow(unused_imports)]\n\n{}",
+        PRELUDE_EXPORTS
+            .iter()
+            .filter(|(m, _)| mods.iter().any(|generated| generated == m))
+            .map(|(m, item)| format!("pub use super::{}::{};\n", m, item))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_prelude() {
+    assert_eq!(
+        format_prelude(&[
+            "scene_consts".into(),
+            "scene_actions".into(),
+            "layer_consts".into(),
+            "actions_invocations".into(),
+            "actions_consts".into(),
+        ]),
+        "#![allow(unused_imports)]\n\npub use super::actions_invocations::InputActionInvocations;\npub use super::layer_consts::*;\npub use super::scene_actions::SceneActions;\n"
+    );
+    assert_eq!(
+        format_prelude(&["actions_registration".into()]),
+        "#![allow(unused_imports)]\n\npub use super::actions_registration::register_all_actions;\n"
+    );
+    assert_eq!(
+        format_prelude(&["scene_consts".into()]),
+        "#![allow(unused_imports)]\n\n"
+    );
+}
+
+/// Removes files in the output directory carrying the `@generated` marker which were not written this run,
+/// e.g. the output of a feature that has since been disabled.
+///
+/// Files without the marker, and files in subdirectories, are never removed. Returns the module names of the removed files.
+pub fn remove_orphans(output_dir: &str, written: &[PathBuf]) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(output_dir) else {
+        return vec![];
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "rs"))
+        .filter(|path| !written.iter().any(|w| w.file_name() == path.file_name()))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+
+    let mut removed = vec![];
+    for path in paths {
+        let is_generated =
+            fs::read_to_string(&path).is_ok_and(|content| content.starts_with(GENERATED_MARKER));
+        if !is_generated {
             continue;
         }
 
-        mod_file
-            .write(format!("pub mod {};\n", m).as_bytes())
-            .unwrap();
+        match fs::remove_file(&path) {
+            Ok(_) => {
+                println!(
+                    "cargo::warning=Removed orphaned generated file {}",
+                    path.display()
+                );
+                if let Some(stem) = path.file_stem() {
+                    removed.push(stem.to_string_lossy().to_string());
+                }
+            }
+            Err(e) => println!(
+                "cargo::warning=Failed to remove orphaned generated file {}: {}",
+                path.display(),
+                e
+            ),
+        }
     }
+
+    removed
+}
+#[test]
+fn test_remove_orphans() {
+    let dir = crate::utils::TestDir::new("remove_orphans");
+    let output_dir = dir.to_str().unwrap();
+
+    let generated = crate::utils::with_generated_header("#![allow(dead_code)]\n");
+    fs::write(dir.join("scene_consts.rs"), &generated).unwrap();
+    fs::write(dir.join("scene_actions.rs"), &generated).unwrap();
+    fs::write(dir.join("helpers.rs"), "pub fn helper() {}\n").unwrap();
+    fs::write(dir.join(MOD_FILE), "pub mod helpers;\n").unwrap();
+
+    let removed = remove_orphans(output_dir, &[dir.join("scene_consts.rs")]);
+    assert_eq!(removed, vec!["scene_actions".to_string()]);
+    assert!(dir.join("scene_consts.rs").exists());
+    assert!(!dir.join("scene_actions.rs").exists());
+    assert!(dir.join("helpers.rs").exists());
+    assert!(dir.join(MOD_FILE).exists());
+}
+
+/// Whether the line is an outer attribute or doc comment, which belongs to the item following it.
+fn is_leading_line(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("#[") || (line.starts_with("///") && !line.starts_with("////"))
+}
+#[test]
+fn test_is_leading_line() {
+    assert!(is_leading_line("#[cfg(test)]"));
+    assert!(is_leading_line("  /// Helpers."));
+    assert!(!is_leading_line("#![allow(unused)]"));
+    assert!(!is_leading_line("//// not a doc comment"));
+    assert!(!is_leading_line("// a comment"));
+}
+
+/// Returns the module name declared by a `pub mod <name>;` line.
+fn parse_mod_declaration(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix("pub mod ")?
+        .strip_suffix(';')
+        .map(|m| m.trim())
+}
+#[test]
+fn test_parse_mod_declaration() {
+    assert_eq!(parse_mod_declaration("pub mod helpers;"), Some("helpers"));
+    assert_eq!(
+        parse_mod_declaration("  pub mod helpers ;"),
+        Some("helpers")
+    );
+    assert_eq!(parse_mod_declaration("mod helpers;"), None);
+    assert_eq!(parse_mod_declaration("pub use helpers::*;"), None);
+}
+
+/// Returns the module name re-exported by a `pub use <name>::*;` line.
+fn parse_glob_reexport(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix("pub use ")?
+        .strip_suffix("::*;")
+        .map(|m| m.trim())
+}
+#[test]
+fn test_parse_glob_reexport() {
+    assert_eq!(
+        parse_glob_reexport("pub use scene_consts::*;"),
+        Some("scene_consts")
+    );
+    assert_eq!(parse_glob_reexport("pub use scene_consts::MAIN;"), None);
+    assert_eq!(parse_glob_reexport("pub mod scene_consts;"), None);
 }
@@ -4,15 +4,23 @@
 use godot::{global::Key, obj::EngineEnum};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use crate::utils::GdValue;
 
 /// Parsed representation of a `project.godot` file
 pub struct ProjectGodot<'a> {
     pub config_version: Option<u32>,
     pub application: Option<ApplicationSection<'a>>,
+    pub audio: Option<AudioSection<'a>>,
     pub autoload: Option<AutoloadSection<'a>>,
     pub dotnet: Option<DotnetSection<'a>>,
+    pub global_group: Option<GlobalGroupSection<'a>>,
     pub input: Option<InputSection>,
+    pub internationalization: Option<InternationalizationSection<'a>>,
     pub layer_names: Option<LayerNamesSection<'a>>,
     pub rendering: Option<RenderingSection<'a>>,
 }
@@ -27,7 +35,9 @@ impl ProjectGodot<'_> {
                 for line in global_section.lines() {
                     if let Some((key, value)) = line.split_once('=') {
                         if key.trim() == "config_version" {
-                            godot_project.config_version = value.trim().parse::<u32>().ok();
+                            godot_project.config_version = GdValue::parse(value)
+                                .as_int()
+                                .and_then(|version| u32::try_from(version).ok());
                         }
                     }
                 }
@@ -37,17 +47,59 @@ impl ProjectGodot<'_> {
         for section in &sections {
             let trimmed_section = section.trim();
             if trimmed_section.starts_with("[application]") {
-                godot_project.application = ApplicationSection::parse(section);
+                merge_section(
+                    &mut godot_project.application,
+                    ApplicationSection::parse(section),
+                    ApplicationSection::merge,
+                );
+            } else if trimmed_section.starts_with("[audio]") {
+                merge_section(
+                    &mut godot_project.audio,
+                    AudioSection::parse(section),
+                    AudioSection::merge,
+                );
             } else if trimmed_section.starts_with("[autoload]") {
-                godot_project.autoload = AutoloadSection::parse(section);
+                merge_section(
+                    &mut godot_project.autoload,
+                    AutoloadSection::parse(section),
+                    AutoloadSection::merge,
+                );
             } else if trimmed_section.starts_with("[dotnet]") {
-                godot_project.dotnet = DotnetSection::parse(section);
+                merge_section(
+                    &mut godot_project.dotnet,
+                    DotnetSection::parse(section),
+                    DotnetSection::merge,
+                );
+            } else if trimmed_section.starts_with("[global_group]") {
+                merge_section(
+                    &mut godot_project.global_group,
+                    GlobalGroupSection::parse(section),
+                    GlobalGroupSection::merge,
+                );
             } else if trimmed_section.starts_with("[input]") {
-                godot_project.input = InputSection::parse(section);
+                merge_section(
+                    &mut godot_project.input,
+                    InputSection::parse(section),
+                    InputSection::merge,
+                );
+            } else if trimmed_section.starts_with("[internationalization]") {
+                merge_section(
+                    &mut godot_project.internationalization,
+                    InternationalizationSection::parse(section),
+                    InternationalizationSection::merge,
+                );
             } else if trimmed_section.starts_with("[layer_names]") {
-                godot_project.layer_names = LayerNamesSection::parse(section);
+                merge_section(
+                    &mut godot_project.layer_names,
+                    LayerNamesSection::parse(section),
+                    LayerNamesSection::merge,
+                );
             } else if trimmed_section.starts_with("[rendering]") {
-                godot_project.rendering = RenderingSection::parse(section);
+                merge_section(
+                    &mut godot_project.rendering,
+                    RenderingSection::parse(section),
+                    RenderingSection::merge,
+                );
             }
         }
 
@@ -58,9 +110,12 @@ impl ProjectGodot<'_> {
         Self {
             config_version: None,
             application: None,
+            audio: None,
             autoload: None,
             dotnet: None,
+            global_group: None,
             input: None,
+            internationalization: None,
             layer_names: None,
             rendering: None,
         }
@@ -68,7 +123,8 @@ impl ProjectGodot<'_> {
 
     fn split_sections<'a>(file_content: &'a str) -> Vec<&'a str> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"\[\w+\]").unwrap();
+            // anchored to whole lines, so brackets inside values (e.g. `config/name="Game [beta]"`) are not headers
+            static ref RE: Regex = Regex::new(r"(?m)^\[\w+\][ \t]*\r?$").unwrap();
         }
 
         let mut result = Vec::new();
@@ -90,6 +146,214 @@ impl ProjectGodot<'_> {
         result
     }
 }
+#[test]
+fn test_split_sections_ignores_brackets_in_values() {
+    let content = r#"config_version=5
+
+[application]
+
+config/name="Game [beta]"
+config/description="press [input] to start"
+
+[layer_names]
+
+2d_physics/layer_1="walls"
+"#;
+
+    let sections = ProjectGodot::split_sections(content);
+    assert_eq!(sections.len(), 3);
+    assert!(sections[1].trim().starts_with("[application]"));
+    assert!(sections[1].contains("press [input] to start"));
+    assert!(sections[2].trim().starts_with("[layer_names]"));
+
+    let project = ProjectGodot::parse_from_str(content);
+    assert_eq!(project.config_version, Some(5));
+    assert!(project.input.is_none());
+    assert!(project.application.is_some());
+    assert_eq!(
+        project
+            .layer_names
+            .unwrap()
+            .layers
+            .get("2d_physics/layer_1"),
+        Some(&"walls")
+    );
+}
+
+/// Merges a repeated section (e.g. two `[input]` blocks left by a merge conflict) into the previously parsed one.
+///
+/// Keys from both are kept, with later keys winning.
+fn merge_section<T>(existing: &mut Option<T>, parsed: Option<T>, merge: impl FnOnce(&mut T, T)) {
+    if let Some(parsed) = parsed {
+        match existing {
+            Some(existing) => merge(existing, parsed),
+            None => *existing = Some(parsed),
+        }
+    }
+}
+#[test]
+fn test_parse_from_str_empty() {
+    for content in ["", "  \n\t\n"] {
+        let project = ProjectGodot::parse_from_str(content);
+        assert_eq!(project.config_version, None);
+        assert!(project.application.is_none());
+        assert!(project.audio.is_none());
+        assert!(project.input.is_none());
+        assert!(project.layer_names.is_none());
+        assert!(project.rendering.is_none());
+    }
+}
+#[test]
+fn test_parse_from_str_merges_duplicate_sections() {
+    let content = r#"config_version=5
+
+[layer_names]
+
+2d_physics/layer_1="walls"
+2d_physics/layer_2="floors"
+
+[application]
+
+config/name="Example"
+
+[layer_names]
+
+2d_physics/layer_2="ground"
+2d_physics/layer_3="enemies"
+"#;
+
+    let project = ProjectGodot::parse_from_str(content);
+    let layers = project.layer_names.unwrap().layers;
+    assert_eq!(layers.len(), 3);
+    assert_eq!(layers.get("2d_physics/layer_1"), Some(&"walls"));
+    assert_eq!(layers.get("2d_physics/layer_2"), Some(&"ground"));
+    assert_eq!(layers.get("2d_physics/layer_3"), Some(&"enemies"));
+}
+
+/// An owned copy of a parsed `project.godot`, which doesn't borrow from the file content, so can be returned from a function
+/// or kept after the content is dropped. Created with [`ProjectGodot::into_owned`].
+///
+/// The values of each section are flattened into fields, with missing values and sections left as `None` or empty.
+#[derive(Clone, Default)]
+pub struct OwnedProjectGodot {
+    pub config_version: Option<u32>,
+    /// `[application]` `config/name`
+    pub name: Option<String>,
+    /// `[application]` `run/main_scene`
+    pub main_scene: Option<String>,
+    /// `[application]` `config/features`
+    pub features: Vec<String>,
+    /// `[application]` `config/icon`
+    pub icon: Option<String>,
+    /// `[audio]` `buses/default_bus_layout`
+    pub default_bus_layout: Option<String>,
+    /// `[autoload]` name to path, e.g. `"GameState"` to `"*res://game_state.gd"`
+    pub autoloads: BTreeMap<String, String>,
+    /// `[dotnet]` `project/assembly_name`
+    pub assembly_name: Option<String>,
+    /// `[global_group]` name to description
+    pub groups: BTreeMap<String, String>,
+    /// `[input]` action name to action
+    pub inputs: HashMap<String, Input>,
+    /// `[internationalization]` `locale/translations`
+    pub translations: Vec<String>,
+    /// `[layer_names]` key to name, e.g. `"2d_physics/layer_1"` to `"walls"`
+    pub layer_names: BTreeMap<String, String>,
+    /// `[rendering]` `renderer/rendering_method`
+    pub rendering_method: Option<String>,
+    /// `[rendering]` `renderer/rendering_method.mobile`
+    pub rendering_method_mobile: Option<String>,
+}
+
+impl ProjectGodot<'_> {
+    /// Copies the parsed project into an [`OwnedProjectGodot`], so it no longer borrows from the file content.
+    pub fn into_owned(self) -> OwnedProjectGodot {
+        fn owned_map(map: BTreeMap<&str, &str>) -> BTreeMap<String, String> {
+            map.into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        }
+        let owned = |value: Option<&str>| value.map(str::to_string);
+
+        let (name, main_scene, features, icon) = match self.application {
+            Some(application) => (
+                owned(application.name),
+                owned(application.main_scene),
+                application
+                    .features
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+                owned(application.icon),
+            ),
+            None => (None, None, vec![], None),
+        };
+        let (rendering_method, rendering_method_mobile) = match self.rendering {
+            Some(rendering) => (
+                owned(rendering.rendering_method),
+                owned(rendering.rendering_method_mobile),
+            ),
+            None => (None, None),
+        };
+
+        OwnedProjectGodot {
+            config_version: self.config_version,
+            name,
+            main_scene,
+            features,
+            icon,
+            default_bus_layout: self.audio.and_then(|audio| owned(audio.default_bus_layout)),
+            autoloads: self
+                .autoload
+                .map_or_else(BTreeMap::new, |autoload| owned_map(autoload.autoloads)),
+            assembly_name: self.dotnet.and_then(|dotnet| owned(dotnet.assembly_name)),
+            groups: self
+                .global_group
+                .map_or_else(BTreeMap::new, |global_group| owned_map(global_group.groups)),
+            inputs: self.input.map(|input| input.inputs).unwrap_or_default(),
+            translations: self
+                .internationalization
+                .map(|i18n| i18n.translations.into_iter().map(str::to_string).collect())
+                .unwrap_or_default(),
+            layer_names: self
+                .layer_names
+                .map_or_else(BTreeMap::new, |layer_names| owned_map(layer_names.layers)),
+            rendering_method,
+            rendering_method_mobile,
+        }
+    }
+}
+#[test]
+fn test_project_godot_into_owned() {
+    let content = include_str!("../example/project.godot");
+    let project = ProjectGodot::parse_from_str(content);
+    let name = project.application.as_ref().and_then(|a| a.name);
+    let layer_count = project.layer_names.as_ref().map_or(0, |l| l.layers.len());
+    let input_count = project.input.as_ref().map_or(0, |i| i.inputs.len());
+
+    let owned = project.into_owned();
+    assert_eq!(owned.config_version, Some(5));
+    assert_eq!(owned.name.as_deref(), name);
+    assert_eq!(owned.layer_names.len(), layer_count);
+    assert_eq!(owned.inputs.len(), input_count);
+    assert!(owned.inputs.contains_key("Fire"));
+
+    // the owned project outlives the content it was parsed from
+    let owned = {
+        let content = String::from(
+            "config_version=5\n\n[application]\n\nconfig/name=\"Owned\"\n\n[autoload]\n\nGameState=\"*res://game_state.gd\"\n",
+        );
+        ProjectGodot::parse_from_str(&content).into_owned()
+    };
+    assert_eq!(owned.name.as_deref(), Some("Owned"));
+    assert_eq!(
+        owned.autoloads.get("GameState").map(String::as_str),
+        Some("*res://game_state.gd")
+    );
+    assert!(owned.features.is_empty());
+    assert!(owned.rendering_method.is_none());
+}
 
 /// Application section of the `project.godot` file
 ///
@@ -214,6 +478,14 @@ impl ApplicationSection<'_> {
         }
         Some(config)
     }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.name = other.name.or(self.name);
+        self.main_scene = other.main_scene.or(self.main_scene);
+        self.features = other.features.or(self.features.take());
+        self.icon = other.icon.or(self.icon);
+    }
 }
 
 /// Autoload section of the `project.godot` file
@@ -224,7 +496,7 @@ impl ApplicationSection<'_> {
 /// gamestate="*res://src/game/gamestate.tscn"
 /// ```
 pub struct AutoloadSection<'a> {
-    pub autoloads: HashMap<&'a str, &'a str>,
+    pub autoloads: BTreeMap<&'a str, &'a str>,
 }
 
 impl AutoloadSection<'_> {
@@ -232,16 +504,16 @@ impl AutoloadSection<'_> {
     ///
     /// # Example
     /// ```
-    /// # use std::collections::HashMap;
+    /// # use std::collections::BTreeMap;
     /// # pub struct AutoloadSection<'a> {
-    /// #     pub autoloads: HashMap<&'a str, &'a str>,
+    /// #     pub autoloads: BTreeMap<&'a str, &'a str>,
     /// # }
     /// # impl AutoloadSection<'_> {
     /// #     pub fn parse<'a>(content: &'a str) -> Option<AutoloadSection<'a>> {
     /// #         if !content.trim().starts_with("[autoload]") {
     /// #             return None;
     /// #         }
-    /// #         let mut autoloads = HashMap::new();
+    /// #         let mut autoloads = BTreeMap::new();
     /// #         for line in content.lines() {
     /// #             let line = line.trim();
     /// #             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
@@ -268,7 +540,7 @@ impl AutoloadSection<'_> {
         if !content.trim().starts_with("[autoload]") {
             return None;
         }
-        let mut autoloads = HashMap::new();
+        let mut autoloads = BTreeMap::new();
         for line in content.lines() {
             let line = line.trim();
             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
@@ -282,6 +554,88 @@ impl AutoloadSection<'_> {
         }
         Some(AutoloadSection { autoloads })
     }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.autoloads.extend(other.autoloads);
+    }
+}
+
+/// Audio section of the `project.godot` file
+///
+/// It has the following format:
+/// ```text
+/// [audio]
+/// buses/default_bus_layout="res://audio/bus_layout.tres"
+/// ```
+///
+/// The key is absent when the project uses Godot's default of `res://default_bus_layout.tres`.
+pub struct AudioSection<'a> {
+    pub default_bus_layout: Option<&'a str>,
+}
+
+impl AudioSection<'_> {
+    /// Parse an audio section from `project.godot` file content
+    ///
+    /// # Example
+    /// ```
+    /// # pub struct AudioSection<'a> {
+    /// #     pub default_bus_layout: Option<&'a str>,
+    /// # }
+    /// # impl AudioSection<'_> {
+    /// #     pub fn parse<'a>(content: &'a str) -> Option<AudioSection<'a>> {
+    /// #         if !content.trim().starts_with("[audio]") {
+    /// #             return None;
+    /// #         }
+    /// #         let mut default_bus_layout = None;
+    /// #         for line in content.lines() {
+    /// #             let line = line.trim();
+    /// #             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+    /// #                 continue;
+    /// #             }
+    /// #             if let Some((key, value)) = line.split_once('=') {
+    /// #                 if key.trim() == "buses/default_bus_layout" {
+    /// #                     default_bus_layout = Some(value.trim().trim_matches('"'));
+    /// #                 }
+    /// #             }
+    /// #         }
+    /// #         Some(AudioSection { default_bus_layout })
+    /// #     }
+    /// # }
+    ///
+    /// let content = r#"[audio]
+    /// buses/default_bus_layout="res://audio/bus_layout.tres"
+    /// "#;
+    ///
+    /// let audio_section = AudioSection::parse(content).unwrap();
+    /// assert_eq!(audio_section.default_bus_layout, Some("res://audio/bus_layout.tres"));
+    ///
+    /// let audio_section = AudioSection::parse("[audio]\n\ndriver/enable_input=true\n").unwrap();
+    /// assert_eq!(audio_section.default_bus_layout, None);
+    /// ```
+    pub fn parse<'a>(content: &'a str) -> Option<AudioSection<'a>> {
+        if !content.trim().starts_with("[audio]") {
+            return None;
+        }
+        let mut default_bus_layout = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=')
+                && key.trim() == "buses/default_bus_layout"
+            {
+                default_bus_layout = Some(value.trim().trim_matches('"'));
+            }
+        }
+        Some(AudioSection { default_bus_layout })
+    }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.default_bus_layout = other.default_bus_layout.or(self.default_bus_layout);
+    }
 }
 
 /// Dotnet section of the `project.godot` file
@@ -349,6 +703,178 @@ impl DotnetSection<'_> {
         }
         Some(DotnetSection { assembly_name })
     }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.assembly_name = other.assembly_name.or(self.assembly_name);
+    }
+}
+
+/// Global group section of the `project.godot` file, added in Godot 4.2
+///
+/// It has the following format, mapping each group name to its description:
+/// ```text
+/// [global_group]
+/// enemies="Hostile units the player can damage"
+/// pickups=""
+/// ```
+pub struct GlobalGroupSection<'a> {
+    pub groups: BTreeMap<&'a str, &'a str>,
+}
+
+impl GlobalGroupSection<'_> {
+    /// Parse a global_group section from `project.godot` file content
+    ///
+    /// # Example
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// # pub struct GlobalGroupSection<'a> {
+    /// #     pub groups: BTreeMap<&'a str, &'a str>,
+    /// # }
+    /// # impl GlobalGroupSection<'_> {
+    /// #     pub fn parse<'a>(content: &'a str) -> Option<GlobalGroupSection<'a>> {
+    /// #         if !content.trim().starts_with("[global_group]") {
+    /// #             return None;
+    /// #         }
+    /// #         let mut groups = BTreeMap::new();
+    /// #         for line in content.lines() {
+    /// #             let line = line.trim();
+    /// #             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+    /// #                 continue;
+    /// #             }
+    /// #             if let Some((key, value)) = line.split_once('=') {
+    /// #                 groups.insert(key.trim().trim_matches('"'), value.trim().trim_matches('"'));
+    /// #             }
+    /// #         }
+    /// #         Some(GlobalGroupSection { groups })
+    /// #     }
+    /// # }
+    ///
+    /// let content = r#"[global_group]
+    /// enemies="Hostile units the player can damage"
+    /// pickups=""
+    /// "#;
+    ///
+    /// let global_group_section = GlobalGroupSection::parse(content).unwrap();
+    /// assert_eq!(global_group_section.groups.get("enemies"), Some(&"Hostile units the player can damage"));
+    /// assert_eq!(global_group_section.groups.get("pickups"), Some(&""));
+    /// ```
+    pub fn parse<'a>(content: &'a str) -> Option<GlobalGroupSection<'a>> {
+        if !content.trim().starts_with("[global_group]") {
+            return None;
+        }
+        let mut groups = BTreeMap::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                // group names with spaces or symbols are quoted
+                groups.insert(key.trim().trim_matches('"'), value.trim().trim_matches('"'));
+            }
+        }
+        Some(GlobalGroupSection { groups })
+    }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.groups.extend(other.groups);
+    }
+}
+#[test]
+fn test_global_group_section_parse() {
+    let project = ProjectGodot::parse_from_str(
+        r#"config_version=5
+
+[global_group]
+
+enemies="Hostile units"
+"player allies"=""
+"#,
+    );
+
+    let groups = project.global_group.unwrap().groups;
+    assert_eq!(
+        groups.into_iter().collect::<Vec<(&str, &str)>>(),
+        vec![("enemies", "Hostile units"), ("player allies", "")]
+    );
+    assert!(
+        GlobalGroupSection::parse(
+            "[input]
+"
+        )
+        .is_none()
+    );
+}
+
+/// Internationalization section of the `project.godot` file
+///
+/// It has the following format, listing the translations loaded by the project:
+/// ```text
+/// [internationalization]
+/// locale/translations=PackedStringArray("res://locale/menu.en.translation", "res://locale/fr.po")
+/// ```
+pub struct InternationalizationSection<'a> {
+    pub translations: Vec<&'a str>,
+}
+
+impl InternationalizationSection<'_> {
+    /// Parse an internationalization section from `project.godot` file content
+    pub fn parse<'a>(content: &'a str) -> Option<InternationalizationSection<'a>> {
+        if !content.trim().starts_with("[internationalization]") {
+            return None;
+        }
+        let mut translations = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=')
+                && key.trim() == "locale/translations"
+            {
+                translations = value
+                    .trim()
+                    .trim_start_matches("PackedStringArray(")
+                    .trim_end_matches(')')
+                    .split(',')
+                    .map(|s| s.trim().trim_matches('"'))
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
+        }
+        Some(InternationalizationSection { translations })
+    }
+
+    /// Merge a repeated section into this one, with `other` winning if it lists any translations.
+    pub fn merge(&mut self, other: Self) {
+        if !other.translations.is_empty() {
+            self.translations = other.translations;
+        }
+    }
+}
+#[test]
+fn test_internationalization_section_parse() {
+    let project = ProjectGodot::parse_from_str(
+        r#"config_version=5
+
+[internationalization]
+
+locale/translations=PackedStringArray("res://locale/menu.en.translation", "res://locale/fr.po")
+locale/translations_pot_files=PackedStringArray("res://main.tscn")
+"#,
+    );
+
+    assert_eq!(
+        project.internationalization.unwrap().translations,
+        vec!["res://locale/menu.en.translation", "res://locale/fr.po"]
+    );
+
+    let empty = InternationalizationSection::parse(
+        "[internationalization]\nlocale/translations=PackedStringArray()\n",
+    );
+    assert_eq!(empty.unwrap().translations, Vec::<&str>::new());
 }
 
 /// Rendering section of the `project.godot` file
@@ -358,10 +884,19 @@ impl DotnetSection<'_> {
 /// [rendering]
 /// renderer/rendering_method="gl_compatibility"
 /// renderer/rendering_method.mobile="gl_compatibility"
+/// anti_aliasing/quality/msaa_2d=1
+/// scaling_3d/mode=1
+/// scaling_3d/scale=0.75
 /// ```
 pub struct RenderingSection<'a> {
     pub rendering_method: Option<&'a str>,
     pub rendering_method_mobile: Option<&'a str>,
+    /// The 2D MSAA level, `0` (disabled) to `3` (8x).
+    pub msaa_2d: Option<i32>,
+    /// The 3D scaling mode, e.g. `0` for bilinear or `1` for FSR 1.0.
+    pub scaling_3d_mode: Option<i32>,
+    /// The 3D resolution scale, e.g. `0.75`.
+    pub scaling_3d_scale: Option<f32>,
 }
 
 impl RenderingSection<'_> {
@@ -372,6 +907,9 @@ impl RenderingSection<'_> {
     /// # pub struct RenderingSection<'a> {
     /// #     pub rendering_method: Option<&'a str>,
     /// #     pub rendering_method_mobile: Option<&'a str>,
+    /// #     pub msaa_2d: Option<i32>,
+    /// #     pub scaling_3d_mode: Option<i32>,
+    /// #     pub scaling_3d_scale: Option<f32>,
     /// # }
     /// # impl RenderingSection<'_> {
     /// #     pub fn parse<'a>(content: &'a str) -> Option<RenderingSection<'a>> {
@@ -380,6 +918,9 @@ impl RenderingSection<'_> {
     /// #         }
     /// #         let mut rendering_method = None;
     /// #         let mut rendering_method_mobile = None;
+    /// #         let mut msaa_2d = None;
+    /// #         let mut scaling_3d_mode = None;
+    /// #         let mut scaling_3d_scale = None;
     /// #         for line in content.lines() {
     /// #             let line = line.trim();
     /// #             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
@@ -391,22 +932,37 @@ impl RenderingSection<'_> {
     /// #                 match key {
     /// #                     "renderer/rendering_method" => rendering_method = Some(value),
     /// #                     "renderer/rendering_method.mobile" => rendering_method_mobile = Some(value),
+    /// #                     "anti_aliasing/quality/msaa_2d" => msaa_2d = value.parse().ok(),
+    /// #                     "scaling_3d/mode" => scaling_3d_mode = value.parse().ok(),
+    /// #                     "scaling_3d/scale" => scaling_3d_scale = value.parse().ok(),
     /// #                     _ => {}
     /// #                 }
     /// #             }
     /// #         }
-    /// #         Some(RenderingSection { rendering_method, rendering_method_mobile })
+    /// #         Some(RenderingSection {
+    /// #             rendering_method,
+    /// #             rendering_method_mobile,
+    /// #             msaa_2d,
+    /// #             scaling_3d_mode,
+    /// #             scaling_3d_scale,
+    /// #         })
     /// #     }
     /// # }
     ///
     /// let content = r#"[rendering]
     /// renderer/rendering_method="gl_compatibility"
     /// renderer/rendering_method.mobile="gl_compatibility"
+    /// anti_aliasing/quality/msaa_2d=1
+    /// scaling_3d/mode=1
+    /// scaling_3d/scale=0.75
     /// "#;
     ///
     /// let rendering_section = RenderingSection::parse(content).unwrap();
     /// assert_eq!(rendering_section.rendering_method, Some("gl_compatibility"));
     /// assert_eq!(rendering_section.rendering_method_mobile, Some("gl_compatibility"));
+    /// assert_eq!(rendering_section.msaa_2d, Some(1));
+    /// assert_eq!(rendering_section.scaling_3d_mode, Some(1));
+    /// assert_eq!(rendering_section.scaling_3d_scale, Some(0.75));
     /// ```
     pub fn parse<'a>(content: &'a str) -> Option<RenderingSection<'a>> {
         if !content.trim().starts_with("[rendering]") {
@@ -414,6 +970,9 @@ impl RenderingSection<'_> {
         }
         let mut rendering_method = None;
         let mut rendering_method_mobile = None;
+        let mut msaa_2d = None;
+        let mut scaling_3d_mode = None;
+        let mut scaling_3d_scale = None;
         for line in content.lines() {
             let line = line.trim();
             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
@@ -425,6 +984,9 @@ impl RenderingSection<'_> {
                 match key {
                     "renderer/rendering_method" => rendering_method = Some(value),
                     "renderer/rendering_method.mobile" => rendering_method_mobile = Some(value),
+                    "anti_aliasing/quality/msaa_2d" => msaa_2d = value.parse().ok(),
+                    "scaling_3d/mode" => scaling_3d_mode = value.parse().ok(),
+                    "scaling_3d/scale" => scaling_3d_scale = value.parse().ok(),
                     _ => {}
                 }
             }
@@ -432,8 +994,22 @@ impl RenderingSection<'_> {
         Some(RenderingSection {
             rendering_method,
             rendering_method_mobile,
+            msaa_2d,
+            scaling_3d_mode,
+            scaling_3d_scale,
         })
     }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.rendering_method = other.rendering_method.or(self.rendering_method);
+        self.rendering_method_mobile = other
+            .rendering_method_mobile
+            .or(self.rendering_method_mobile);
+        self.msaa_2d = other.msaa_2d.or(self.msaa_2d);
+        self.scaling_3d_mode = other.scaling_3d_mode.or(self.scaling_3d_mode);
+        self.scaling_3d_scale = other.scaling_3d_scale.or(self.scaling_3d_scale);
+    }
 }
 
 /// Layer names section of the `project.godot` file
@@ -445,7 +1021,7 @@ impl RenderingSection<'_> {
 /// 2d_physics/layer_2="noncolliding"
 /// ```
 pub struct LayerNamesSection<'a> {
-    pub layers: HashMap<&'a str, &'a str>,
+    pub layers: BTreeMap<&'a str, &'a str>,
 }
 
 impl LayerNamesSection<'_> {
@@ -453,16 +1029,16 @@ impl LayerNamesSection<'_> {
     ///
     /// # Example
     /// ```
-    /// # use std::collections::HashMap;
+    /// # use std::collections::BTreeMap;
     /// # pub struct LayerNamesSection<'a> {
-    /// #     pub layers: HashMap<&'a str, &'a str>,
+    /// #     pub layers: BTreeMap<&'a str, &'a str>,
     /// # }
     /// # impl LayerNamesSection<'_> {
     /// #     pub fn parse<'a>(content: &'a str) -> Option<LayerNamesSection<'a>> {
     /// #         if !content.trim().starts_with("[layer_names]") {
     /// #             return None;
     /// #         }
-    /// #         let mut layers = HashMap::new();
+    /// #         let mut layers = BTreeMap::new();
     /// #         for line in content.lines() {
     /// #             let line = line.trim();
     /// #             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
@@ -491,7 +1067,7 @@ impl LayerNamesSection<'_> {
         if !content.trim().starts_with("[layer_names]") {
             return None;
         }
-        let mut layers = HashMap::new();
+        let mut layers = BTreeMap::new();
         for line in content.lines() {
             let line = line.trim();
             if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
@@ -505,6 +1081,11 @@ impl LayerNamesSection<'_> {
         }
         Some(LayerNamesSection { layers })
     }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.layers.extend(other.layers);
+    }
 }
 
 /// Input section of the `project.godot` file
@@ -553,6 +1134,11 @@ impl InputSection {
         }
         return Some(InputSection { inputs });
     }
+
+    /// Merge a repeated section into this one, with `other` winning per key.
+    pub fn merge(&mut self, other: Self) {
+        self.inputs.extend(other.inputs);
+    }
 }
 
 #[test]
@@ -588,18 +1174,18 @@ fn parse_input_from_input_block(block_lines: Vec<String>) -> Option<Input> {
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
-        if line.contains("={") {
-            name = line.split("={").next().unwrap().trim().to_string();
+        if let Some(index) = find_unquoted(line, "={") {
+            name = unquote_key(line[..index].trim());
         } else if line == "}" {
             break;
-        } else if line.contains(": ") {
-            let (key, value) = line.split_once(':').unwrap();
+        } else if let Some(index) = find_unquoted(line, ":") {
+            let (key, value) = (&line[..index], &line[index + 1..]);
             let key = key.trim().trim_matches('"');
             let value = value.trim().trim_matches(',');
 
             match key {
                 "deadzone" => {
-                    deadzone = value.parse::<f32>().ok();
+                    deadzone = GdValue::parse(value).as_float().map(|d| d as f32);
                 }
                 "events" => {
                     let events_str = value.trim_start_matches('[').trim_end_matches(']').trim();
@@ -641,6 +1227,211 @@ fn test_parse_input_from_input_block() {
     assert_eq!(parsed_input.deadzone, Some(0.5));
     assert_eq!(parsed_input.events.len(), 1);
 }
+#[test]
+fn test_parse_input_from_input_block_colon_in_value() {
+    let input = r#""Fire: Alt"={
+"deadzone": 0.2,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"a: b={","device":-1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":65,"physical_keycode":0,"key_label":0,"unicode":0,"location":0,"echo":false,"script":null)
+]
+}"#;
+
+    let block_lines: Vec<String> = input.lines().map(|s| s.to_string()).collect();
+    let parsed_input = parse_input_from_input_block(block_lines).unwrap();
+
+    assert_eq!(parsed_input.name, "Fire: Alt");
+    assert_eq!(parsed_input.deadzone, Some(0.2));
+    assert_eq!(parsed_input.events.len(), 1);
+    assert_eq!(
+        parsed_input.events[0].str_properties.get("resource_name"),
+        Some(&"a: b={".to_string())
+    );
+    assert_eq!(
+        parsed_input.events[0].get_key_string(),
+        Some("A".to_string())
+    );
+}
+
+/// Unquotes a key Godot wrote quoted because of special characters, e.g. `"Fire: Alt"`, unescaping its content.
+fn unquote_key(key: &str) -> String {
+    let Some(quoted) = key.strip_prefix('"').and_then(|key| key.strip_suffix('"')) else {
+        return key.to_string();
+    };
+
+    let mut unquoted = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unquoted.push('\n'),
+            Some('t') => unquoted.push('\t'),
+            Some(escaped) => unquoted.push(escaped),
+            None => unquoted.push('\\'),
+        }
+    }
+    unquoted
+}
+#[test]
+fn test_unquote_key() {
+    assert_eq!(unquote_key("Fire"), "Fire");
+    assert_eq!(unquote_key("\"Fire: Alt\""), "Fire: Alt");
+    assert_eq!(unquote_key(r#""say \"hi\" \\o/""#), r#"say "hi" \o/"#);
+    assert_eq!(unquote_key("\""), "\"");
+}
+
+/// Finds the first `pattern` outside of a quoted string, skipping escaped quotes within strings.
+fn find_unquoted(s: &str, pattern: &str) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            _ if !in_quotes && s[i..].starts_with(pattern) => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+#[test]
+fn test_find_unquoted() {
+    assert_eq!(find_unquoted(r#""deadzone": 0.5,"#, ":"), Some(10));
+    assert_eq!(find_unquoted(r#""a: b": 1"#, ":"), Some(6));
+    assert_eq!(find_unquoted(r#""a\": b": 1"#, ":"), Some(8));
+    assert_eq!(find_unquoted(r#"Fire={"#, "={"), Some(4));
+    assert_eq!(find_unquoted(r#""x={""#, "={"), None);
+}
+
+/// A modifier key held alongside the key or button of a [`KeyCombo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Modifier {
+    Ctrl,
+    Alt,
+    Shift,
+    Meta,
+}
+
+impl Modifier {
+    /// The order modifiers are rendered in by default, e.g. `ctrl+alt+shift+meta+A`.
+    pub const CANONICAL_ORDER: [Modifier; 4] = [
+        Modifier::Ctrl,
+        Modifier::Alt,
+        Modifier::Shift,
+        Modifier::Meta,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Modifier::Ctrl => "ctrl",
+            Modifier::Alt => "alt",
+            Modifier::Shift => "shift",
+            Modifier::Meta => "meta",
+        }
+    }
+}
+
+/// A key or mouse button bound to an action, with the modifiers which must be held with it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyCombo {
+    pub modifiers: Vec<Modifier>,
+    pub key: String,
+    /// The device the event is bound to, or `None` for all devices (`-1`).
+    pub device: Option<i32>,
+}
+
+impl KeyCombo {
+    pub fn new(modifiers: &[Modifier], key: &str) -> Self {
+        KeyCombo {
+            modifiers: modifiers.to_vec(),
+            key: key.to_string(),
+            device: None,
+        }
+    }
+
+    /// Whether any modifier must be held alongside the key, e.g. `ctrl+A`.
+    pub fn has_modifiers(&self) -> bool {
+        !self.modifiers.is_empty()
+    }
+
+    /// Renders the combo with its modifiers in the given order, e.g. `shift+ctrl+A`.
+    ///
+    /// Modifiers missing from `order` are not rendered.
+    pub fn to_string_in_order(&self, order: &[Modifier]) -> String {
+        order
+            .iter()
+            .filter(|modifier| self.modifiers.contains(modifier))
+            .map(|modifier| format!("{}+", modifier.as_str()))
+            .chain(std::iter::once(self.key.clone()))
+            .collect()
+    }
+
+    /// Renders the combo followed by its device if it has one, e.g. `ctrl+A (device 1)`, for docs rather than tools
+    /// parsing the keystroke.
+    pub fn to_string_with_device(&self) -> String {
+        match self.device {
+            Some(device) => format!("{} (device {})", self, device),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_in_order(&Modifier::CANONICAL_ORDER))
+    }
+}
+#[test]
+fn test_key_combo_to_string_in_order() {
+    let combo = KeyCombo {
+        modifiers: vec![Modifier::Shift, Modifier::Meta, Modifier::Ctrl],
+        key: "X".to_string(),
+        device: None,
+    };
+
+    assert_eq!(combo.to_string(), "ctrl+shift+meta+X");
+    assert_eq!(
+        combo.to_string_in_order(&[
+            Modifier::Meta,
+            Modifier::Shift,
+            Modifier::Alt,
+            Modifier::Ctrl
+        ]),
+        "meta+shift+ctrl+X"
+    );
+    assert_eq!(
+        KeyCombo {
+            modifiers: vec![],
+            key: "left_click".to_string(),
+            device: None,
+        }
+        .to_string(),
+        "left_click"
+    );
+    assert_eq!(
+        KeyCombo {
+            modifiers: vec![Modifier::Ctrl],
+            key: "A".to_string(),
+            device: Some(1),
+        }
+        .to_string_with_device(),
+        "ctrl+A (device 1)"
+    );
+    assert_eq!(
+        KeyCombo {
+            modifiers: vec![Modifier::Ctrl],
+            key: "A".to_string(),
+            device: Some(1),
+        }
+        .to_string(),
+        "ctrl+A"
+    );
+    assert!(!combo.to_string_in_order(&[]).contains('+'));
+}
 
 #[derive(Clone)]
 pub struct InputEvent {
@@ -655,47 +1446,71 @@ pub struct InputEvent {
 impl InputEvent {
     // TODO - this may need to be extended to cover more cases, but for now it covers the basics
     // eg: controller buttons
-    pub fn get_key_string(&self) -> Option<String> {
-        let ctrl = self
-            .int_properties
-            .get("ctrl_pressed")
-            .copied()
-            .unwrap_or(0)
-            == 1;
-        let shift = self
-            .int_properties
-            .get("shift_pressed")
-            .copied()
-            .unwrap_or(0)
-            == 1;
-        let alt = self.int_properties.get("alt_pressed").copied().unwrap_or(0) == 1;
+    pub fn get_key_combo(&self) -> Option<KeyCombo> {
+        let modifiers = [
+            (Modifier::Ctrl, "ctrl_pressed"),
+            (Modifier::Alt, "alt_pressed"),
+            (Modifier::Shift, "shift_pressed"),
+            (Modifier::Meta, "meta_pressed"),
+        ]
+        .into_iter()
+        .filter(|(_, property)| self.is_pressed(property))
+        .map(|(modifier, _)| modifier)
+        .collect();
 
         let key_str = match self.event_type.as_str() {
             "InputEventKey" => key_str_from_codes(
-                self.int_properties.get("keycode").copied(),
-                self.int_properties.get("physical_keycode").copied(),
+                self.code_property("keycode", "scancode"),
+                self.code_property("physical_keycode", "physical_scancode"),
                 self.int_properties.get("unicode").copied(),
             ),
-            "InputEventMouseButton" => mouse_button_str_from_code(
+            "InputEventMouseButton" => {
                 self.int_properties
                     .get("button_index")
                     .copied()
-                    .unwrap_or(8),
-                self.bool_properties
-                    .get("double_click")
-                    .copied()
-                    .unwrap_or(false),
-            ),
-            _ => None,
+                    .and_then(|button_index| {
+                        mouse_button_str_from_code(
+                            button_index,
+                            self.bool_properties
+                                .get("double_click")
+                                .copied()
+                                .unwrap_or(false),
+                        )
+                    })
+            }
+            // e.g. joypad events, which have no keystroke
+            _ => return None,
         };
 
-        return Some(format!(
-            "{}{}{}{}",
-            if ctrl { "ctrl+" } else { "" },
-            if shift { "shift+" } else { "" },
-            if alt { "alt+" } else { "" },
-            key_str.unwrap_or_else(|| "".to_string())
-        ));
+        Some(KeyCombo {
+            modifiers,
+            key: key_str.unwrap_or_default(),
+            // -1 binds the event to all devices
+            device: self
+                .int_properties
+                .get("device")
+                .copied()
+                .filter(|device| *device >= 0),
+        })
+    }
+
+    pub fn get_key_string(&self) -> Option<String> {
+        self.get_key_combo()
+            .map(|combo| combo.to_string_with_device())
+    }
+
+    /// The non-zero value of a code property, falling back to its name in older Godot 4 files, e.g. `scancode` for `keycode`.
+    fn code_property(&self, property: &str, fallback: &str) -> Option<i32> {
+        [property, fallback]
+            .into_iter()
+            .filter_map(|name| self.int_properties.get(name).copied())
+            .find(|code| *code != 0)
+    }
+
+    /// Whether a modifier property is set, as either `true` or `1`.
+    fn is_pressed(&self, property: &str) -> bool {
+        self.bool_properties.get(property).copied().unwrap_or(false)
+            || self.int_properties.get(property).copied().unwrap_or(0) == 1
     }
 }
 
@@ -723,7 +1538,58 @@ fn test_input_event_get_key_string_keys() {
     assert_eq!(event.get_key_string(), Some("ctrl+shift+A".to_string()));
 
     event.int_properties.insert("alt_pressed".to_string(), 1);
-    assert_eq!(event.get_key_string(), Some("ctrl+shift+alt+A".to_string()));
+    assert_eq!(event.get_key_string(), Some("ctrl+alt+shift+A".to_string()));
+
+    event
+        .bool_properties
+        .insert("meta_pressed".to_string(), true);
+    assert_eq!(
+        event.get_key_string(),
+        Some("ctrl+alt+shift+meta+A".to_string())
+    );
+}
+#[test]
+fn test_input_event_get_key_combo_modifiers() {
+    let event = extract_input_event_from_string(
+        r#"Object(InputEventKey,"device":-1,"window_id":0,"alt_pressed":true,"shift_pressed":true,"ctrl_pressed":true,"meta_pressed":false,"pressed":false,"keycode":83,"physical_keycode":0,"key_label":0,"unicode":0,"echo":false,"script":null)"#,
+    )
+    .unwrap();
+
+    let combo = event.get_key_combo().unwrap();
+    assert_eq!(
+        combo,
+        KeyCombo {
+            modifiers: vec![Modifier::Ctrl, Modifier::Alt, Modifier::Shift],
+            key: "S".to_string(),
+            device: None,
+        }
+    );
+    assert!(combo.has_modifiers());
+    assert_eq!(combo.to_string(), "ctrl+alt+shift+S");
+}
+#[test]
+fn test_input_event_get_key_combo_device() {
+    let event = extract_input_event_from_string(
+        r#"Object(InputEventKey,"device":1,"window_id":0,"alt_pressed":false,"shift_pressed":false,"ctrl_pressed":false,"meta_pressed":false,"pressed":false,"keycode":65,"physical_keycode":0,"key_label":0,"unicode":0,"echo":false,"script":null)"#,
+    )
+    .unwrap();
+
+    assert_eq!(event.get_key_combo().unwrap().device, Some(1));
+    assert_eq!(event.get_key_string(), Some("A (device 1)".to_string()));
+}
+#[test]
+fn test_input_event_get_key_string_scancode() {
+    let event = extract_input_event_from_string(
+        r#"Object(InputEventKey,"resource_local_to_scene":false,"resource_name":"","device":0,"alt_pressed":false,"shift_pressed":false,"control":false,"meta":false,"command":false,"pressed":false,"scancode":87,"physical_scancode":0,"unicode":0,"echo":false,"script":null)"#,
+    )
+    .unwrap();
+    assert_eq!(event.get_key_string(), Some("W (device 0)".to_string()));
+
+    let event = extract_input_event_from_string(
+        r#"Object(InputEventKey,"device":-1,"alt_pressed":false,"shift_pressed":false,"pressed":false,"scancode":0,"physical_scancode":83,"unicode":0,"echo":false,"script":null)"#,
+    )
+    .unwrap();
+    assert_eq!(event.get_key_string(), Some("S".to_string()));
 }
 
 #[test]
@@ -775,7 +1641,16 @@ fn test_input_event_get_key_string_mouse() {
         Some("ctrl+double_wheel_down".to_string())
     );
 
-    event.int_properties.insert("button_index".to_string(), 8); // Invalid button
+    event.int_properties.insert("button_index".to_string(), 8); // Back
+    assert_eq!(
+        event.get_key_string(),
+        Some("ctrl+double_xbutton1".to_string())
+    );
+
+    event.int_properties.insert("button_index".to_string(), 10); // Invalid button
+    assert_eq!(event.get_key_string(), Some("ctrl+".to_string()));
+
+    event.int_properties.remove("button_index");
     assert_eq!(event.get_key_string(), Some("ctrl+".to_string()));
 }
 
@@ -791,6 +1666,9 @@ fn mouse_button_str_from_code(button_index: i32, double_click: bool) -> Option<S
             5 => "wheel_down",
             6 => "wheel_left",
             7 => "wheel_right",
+            // `MOUSE_BUTTON_XBUTTON1` and `MOUSE_BUTTON_XBUTTON2`, usually back and forward
+            8 => "xbutton1",
+            9 => "xbutton2",
             _ => return None,
         },
         if button_index <= 3 { "_click" } else { "" }
@@ -819,8 +1697,16 @@ fn test_mouse_button_str_from_code() {
         mouse_button_str_from_code(5, true),
         Some("double_wheel_down".to_string())
     );
-    assert_eq!(mouse_button_str_from_code(8, false), None);
-    assert_eq!(mouse_button_str_from_code(8, true), None);
+    assert_eq!(
+        mouse_button_str_from_code(8, false),
+        Some("xbutton1".to_string())
+    );
+    assert_eq!(
+        mouse_button_str_from_code(9, true),
+        Some("double_xbutton2".to_string())
+    );
+    assert_eq!(mouse_button_str_from_code(10, false), None);
+    assert_eq!(mouse_button_str_from_code(10, true), None);
 }
 
 fn key_str_from_codes(
@@ -873,7 +1759,8 @@ fn key_str_from_codes(
                     // Printable ASCII range
                     std::str::from_utf8(&code_u8).unwrap_or("")
                 }
-                _ => "",
+                // e.g. keypad and media keys, which godot names in its `Key` enum
+                _ => Key::try_from_ord(code).map_or("", |key| key.as_str()),
             })
             .to_string(),
         );
@@ -914,6 +1801,19 @@ fn test_key_str_from_codes() {
     );
     assert_eq!(key_str_from_codes(None, None, None), None);
 }
+#[test]
+fn test_key_str_from_codes_keypad_and_media() {
+    // KP_0
+    assert_eq!(
+        key_str_from_codes(None, None, Some(4194438)),
+        Some("KP_0".to_string())
+    );
+    // VOLUMEUP
+    assert_eq!(
+        key_str_from_codes(None, None, Some(4194382)),
+        Some("VOLUMEUP".to_string())
+    );
+}
 
 /// Splits the events array string into individual event strings.
 ///
@@ -923,16 +1823,38 @@ fn test_key_str_from_codes() {
 /// it returns:
 ///
 /// `["Object(InputEventKey, \"test\":false)", "Object(InputEventMouseButton, \"test\":false)"]`
+///
+/// Parentheses are counted outside of quotes, so an event may nest constructors to any depth, e.g. `"script":Object(...)`.
 fn split_events_array(events: &str) -> Vec<String> {
-    lazy_static! {
-        static ref INPUT_OBJECT_REGEX: Regex =
-            Regex::new(r#"Object\(((?:[^()]|\((?:[^()]|\([^()]*\))*\))*)\)"#).unwrap();
-    };
+    let mut result = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut in_parentheses = 0;
+    let mut event_start = None;
 
-    INPUT_OBJECT_REGEX
-        .find_iter(events)
-        .map(|mat| mat.as_str().to_string())
-        .collect()
+    for (i, c) in events.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            _ if in_quotes => {}
+            'O' if in_parentheses == 0 && events[i..].starts_with("Object(") => {
+                event_start = Some(i);
+            }
+            '(' => in_parentheses += 1,
+            ')' if in_parentheses > 0 => {
+                in_parentheses -= 1;
+                if in_parentheses == 0
+                    && let Some(start) = event_start.take()
+                {
+                    result.push(events[start..=i].to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    result
 }
 
 #[test]
@@ -947,6 +1869,22 @@ fn test_split_events_array() {
         assert_eq!(event, expected[i]);
     }
 }
+#[test]
+fn test_split_events_array_nested_object() {
+    let input = r#"[Object(InputEventMouseButton,"position":Vector2(0, 0),"script":Object(Resource,"resource_name":"a (b)","data":Object(Resource,"size":Vector2i(1, 2)))), Object(InputEventKey,"keycode":65,"script":null)]"#;
+
+    assert_eq!(
+        split_events_array(input),
+        vec![
+            r#"Object(InputEventMouseButton,"position":Vector2(0, 0),"script":Object(Resource,"resource_name":"a (b)","data":Object(Resource,"size":Vector2i(1, 2))))"#,
+            r#"Object(InputEventKey,"keycode":65,"script":null)"#,
+        ]
+    );
+
+    let event = extract_input_event_from_string(&split_events_array(input)[0]).unwrap();
+    assert_eq!(event.event_type, "InputEventMouseButton");
+    assert_eq!(event.vec2_properties.get("position"), Some(&(0.0, 0.0)));
+}
 
 fn extract_input_event_from_string(event_str: &str) -> Option<InputEvent> {
     let maybe_split = event_str.trim_start_matches("Object(").split_once(',');
@@ -966,29 +1904,35 @@ fn extract_input_event_from_string(event_str: &str) -> Option<InputEvent> {
     let mut str_properties: HashMap<String, String> = HashMap::new();
 
     for (key, value) in properties {
-        if value == "true" || value == "false" {
-            bool_properties.insert(key.to_string(), value == "true");
-        } else if let Ok(int_value) = value.parse::<i32>() {
-            int_properties.insert(key.to_string(), int_value);
-        } else if let Ok(float_value) = value.parse::<f32>() {
-            float_properties.insert(key.to_string(), float_value);
-        } else if value.starts_with("Vector2(") && value.ends_with(')') {
-            let vec_str = &value["Vector2(".len()..value.len() - 1];
-            let parts: Vec<&str> = vec_str.split(',').collect();
-            if parts.len() == 2 {
-                if let (Ok(x), Ok(y)) = (
-                    parts[0].trim().parse::<f32>(),
-                    parts[1].trim().parse::<f32>(),
-                ) {
-                    vec2_properties.insert(key.to_string(), (x, y));
+        let key = key.to_string();
+        match GdValue::parse(value) {
+            GdValue::Bool(b) => {
+                bool_properties.insert(key, b);
+            }
+            GdValue::Int(i) => match i32::try_from(i) {
+                Ok(i) => {
+                    int_properties.insert(key, i);
                 }
+                Err(_) => {
+                    float_properties.insert(key, i as f32);
+                }
+            },
+            GdValue::Float(f) => {
+                float_properties.insert(key, f as f32);
+            }
+            GdValue::Vector(components) => {
+                if let [x, y] = components[..] {
+                    vec2_properties.insert(key, (x as f32, y as f32));
+                } else {
+                    str_properties.insert(key, value.to_string());
+                }
+            }
+            GdValue::Str(s) => {
+                str_properties.insert(key, s.to_string());
+            }
+            GdValue::Other(other) => {
+                str_properties.insert(key, other.to_string());
             }
-        } else if value.starts_with('"') && value.ends_with('"') {
-            str_properties.insert(key.to_string(), value.trim_matches('"').to_string());
-        } else if value == "null" {
-            str_properties.insert(key.to_string(), "null".to_string());
-        } else {
-            str_properties.insert(key.to_string(), value.to_string());
         }
     }
 
@@ -1026,11 +1970,14 @@ fn test_extract_input_event_from_string() {
 fn split_properties_string(properties: &str) -> Vec<(&str, &str)> {
     let mut result = Vec::new();
     let mut in_quotes = false;
+    let mut escaped = false;
     let mut in_parentheses = 0;
     let mut last_split = 0;
 
     for (i, c) in properties.char_indices() {
         match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
             '"' => in_quotes = !in_quotes,
             '(' => {
                 if !in_quotes {
@@ -1045,8 +1992,11 @@ fn split_properties_string(properties: &str) -> Vec<(&str, &str)> {
             ',' => {
                 if !in_quotes && in_parentheses == 0 {
                     let part = &properties[last_split..i];
-                    if let Some((key, value)) = part.split_once(':') {
-                        result.push((key.trim().trim_matches('"'), value.trim()));
+                    if let Some(index) = find_unquoted(part, ":") {
+                        result.push((
+                            part[..index].trim().trim_matches('"'),
+                            part[index + 1..].trim(),
+                        ));
                     }
                     last_split = i + 1;
                 }
@@ -1058,8 +2008,11 @@ fn split_properties_string(properties: &str) -> Vec<(&str, &str)> {
     // Add the last part
     if last_split < properties.len() {
         let part = &properties[last_split..];
-        if let Some((key, value)) = part.split_once(':') {
-            result.push((key.trim().trim_matches('"'), value.trim()));
+        if let Some(index) = find_unquoted(part, ":") {
+            result.push((
+                part[..index].trim().trim_matches('"'),
+                part[index + 1..].trim(),
+            ));
         }
     }
 
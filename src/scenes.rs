@@ -1,97 +1,586 @@
-use std::{collections::HashMap, fs, io::Write, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Component, Path},
+};
 
-use crate::utils::{make_path_if_not_exists, pascal_to_snake_case, to_resource_path};
+use crate::{
+    mod_file::GeneratedModule,
+    tscn::Tscn,
+    utils::{
+        find_files_with_extension, format_item_allow, format_lint_allows, is_engine_class,
+        matches_glob, pascal_to_snake_case, pascal_to_snake_case_acronyms, resolve_class_type,
+        to_resource_path, to_upper_camel_case, write_generated_file,
+    },
+};
 
 const ACTIONS: &str = "actions";
 const CONSTS: &str = "consts";
+const NODES: &str = "nodes";
+const DEPENDENCIES: &str = "dependencies";
+const NODE_ACCESSORS: &str = "node_accessors";
+const SPAWNERS: &str = "spawners";
+
+/// The kinds of constant generated for each scene by `output_scene_consts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneConstKind {
+    /// `pub const MAIN: &'static str = "res://scenes/Main.tscn";`
+    Str,
+    /// `pub fn MAIN_NAME() -> StringName { StringName::from("res://scenes/Main.tscn") }`
+    StringName,
+    /// `pub fn MAIN_NODE_PATH() -> NodePath { NodePath::from("res://scenes/Main.tscn") }`
+    NodePath,
+}
 
-/// Finds all `.tscn` files in the given resource path and generates scene constants and/or actions as specified.
+/// The scene settings of a `Generator`, selecting which scenes are found and what is generated for them by `generate_scenes`.
+#[derive(Debug, Clone, Copy)]
+pub struct SceneOptions<'a> {
+    pub allow_globs: &'a [String],
+    pub deny_globs: &'a [String],
+    pub skip_dirs: &'a [&'a str],
+    pub scene_consts: bool,
+    pub scene_const_kinds: &'a [SceneConstKind],
+    pub scene_consts_grouped: bool,
+    pub scene_consts_use_uid: bool,
+    pub scene_actions: bool,
+    pub scene_actions_impl_for: &'a str,
+    pub custom_class_paths: &'a BTreeMap<String, String>,
+    pub current_scene_predicates: bool,
+    pub scene_nodes: bool,
+    pub scene_dependencies: bool,
+    pub scene_node_accessors: bool,
+    pub scene_spawners: bool,
+    pub const_prefix: &'a str,
+    pub strict_lints: bool,
+}
+
+/// Finds all `.tscn` files in the given resource path and generates scene constants, actions, node paths and/or dependencies as specified.
 pub fn generate_scenes(
     output_dir: &str,
     resource_path: &str,
-    scene_consts: bool,
-    scene_actions: bool,
-) -> Vec<String> {
+    options: &SceneOptions,
+) -> io::Result<Vec<GeneratedModule>> {
+    let SceneOptions {
+        allow_globs,
+        deny_globs,
+        skip_dirs,
+        scene_consts,
+        scene_const_kinds,
+        scene_consts_grouped,
+        scene_consts_use_uid,
+        scene_actions,
+        scene_actions_impl_for,
+        custom_class_paths,
+        current_scene_predicates,
+        scene_nodes,
+        scene_dependencies,
+        scene_node_accessors,
+        scene_spawners,
+        const_prefix,
+        strict_lints,
+    } = *options;
     let mut generated_modules = Vec::new();
 
     let output_dir = Path::new(output_dir);
+
+    let scenes_and_paths = find_scenes(resource_path, allow_globs, deny_globs, skip_dirs);
+
+    if scene_consts {
+        let uids = if scene_consts_use_uid {
+            read_scene_uids(resource_path, &scenes_and_paths)
+        } else {
+            HashMap::new()
+        };
+
+        write_scene_module(
+            output_dir,
+            mod_name(CONSTS),
+            &if scene_consts_grouped {
+                format_scenes_to_grouped_consts(
+                    &scenes_and_paths,
+                    scene_const_kinds,
+                    &uids,
+                    const_prefix,
+                    strict_lints,
+                )
+            } else {
+                format_scenes_to_consts(
+                    &scenes_and_paths,
+                    scene_const_kinds,
+                    &uids,
+                    const_prefix,
+                    strict_lints,
+                )
+            },
+            &mut generated_modules,
+        )?;
+    }
+
+    if scene_actions {
+        write_scene_module(
+            output_dir,
+            mod_name(ACTIONS),
+            &format_scenes_to_actions(
+                &scenes_and_paths,
+                scene_actions_impl_for,
+                custom_class_paths,
+                current_scene_predicates,
+                strict_lints,
+            ),
+            &mut generated_modules,
+        )?;
+    }
+
+    if scene_nodes || scene_dependencies || scene_node_accessors || scene_spawners {
+        let scene_contents = read_scene_files(resource_path, &scenes_and_paths);
+        let scenes = scene_contents
+            .iter()
+            .map(|(name, path, content)| (*name, *path, Tscn::parse_from_str(content)))
+            .collect::<Vec<(&String, &String, Tscn)>>();
+
+        if scene_nodes {
+            let nodes = scenes
+                .iter()
+                .map(|(name, path, tscn)| {
+                    (
+                        *name,
+                        *path,
+                        tscn.nodes.iter().filter_map(|n| n.path()).collect(),
+                    )
+                })
+                .collect::<Vec<(&String, &String, Vec<String>)>>();
+
+            write_scene_module(
+                output_dir,
+                mod_name(NODES),
+                &format_scenes_to_nodes(&nodes, strict_lints),
+                &mut generated_modules,
+            )?;
+        }
+
+        if scene_dependencies {
+            let dependencies = scenes
+                .iter()
+                .map(|(name, path, tscn)| (*name, *path, tscn.dependencies()))
+                .collect::<Vec<(&String, &String, Vec<&str>)>>();
+
+            write_scene_module(
+                output_dir,
+                mod_name(DEPENDENCIES),
+                &format_scenes_to_dependencies(&dependencies, strict_lints),
+                &mut generated_modules,
+            )?;
+        }
+
+        if scene_node_accessors {
+            let scenes = scenes
+                .iter()
+                .map(|(name, path, tscn)| (*name, *path, tscn))
+                .collect::<Vec<(&String, &String, &Tscn)>>();
+
+            write_scene_module(
+                output_dir,
+                mod_name(NODE_ACCESSORS),
+                &format_scenes_to_node_accessors(&scenes, custom_class_paths, strict_lints),
+                &mut generated_modules,
+            )?;
+        }
+
+        if scene_spawners {
+            let roots = scenes
+                .iter()
+                .map(|(name, path, tscn)| {
+                    (
+                        *name,
+                        *path,
+                        tscn.root().and_then(|root| root.node_type).map_or_else(
+                            || "Node".to_string(),
+                            |class| resolve_class_type(class, custom_class_paths, path),
+                        ),
+                    )
+                })
+                .collect::<Vec<(&String, &String, String)>>();
+
+            write_scene_module(
+                output_dir,
+                mod_name(SPAWNERS),
+                &format_scenes_to_spawners(&roots, strict_lints),
+                &mut generated_modules,
+            )?;
+        }
+    }
+
+    Ok(generated_modules)
+}
+
+/// Finds the `.tscn` files in the resource path allowed by the globs, returning the name and `res://` path of each scene, sorted by
+/// least directories then alphabetically.
+///
+/// Scenes are named by their file name with any non-alphanumeric characters removed, see `to_scene_name`. Scenes whose names
+/// generate the same identifiers, e.g. `main.tscn` and `Main.tscn`, have their folders prepended to their name until it is unique.
+/// Directories named in `skip_dirs`, e.g. `GODOT_CACHE_DIRS`, are not scanned.
+pub fn find_scenes(
+    resource_path: &str,
+    allow_globs: &[String],
+    deny_globs: &[String],
+    skip_dirs: &[&str],
+) -> Vec<(String, String)> {
     let resource_dir = Path::new(resource_path);
 
     // recursively find all .tscn files
     let mut scenes_and_paths: HashMap<String, (String, String)> = HashMap::new();
-    for entry in walkdir::WalkDir::new(resource_dir) {
-        let entry = entry.unwrap();
-        if entry.path().is_file()
-            && entry.path().extension().is_some()
-            && entry.path().extension().unwrap() == "tscn"
-        {
-            let scene_path = entry.path().to_str().unwrap().replace("\\", "/");
-            let mut scene_name = entry
-                .path()
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string();
-
-            // while there is a name collision, prepend parent folder name
-            let mut parent = entry.path().parent();
-            while scenes_and_paths.contains_key(&scene_name) {
-                if let Some(p) = parent {
-                    if let Some(folder_name) = p.file_name() {
-                        scene_name = format!("{}{}", folder_name.to_str().unwrap(), scene_name);
-                        parent = p.parent();
-                    } else {
-                        break;
-                    }
-                } else {
-                    break;
-                }
-            }
+    for scene_file in find_files_with_extension(resource_dir, "tscn", skip_dirs) {
+        let scene_path = scene_file.to_str().unwrap().replace("\\", "/");
+        if !scene_filter_allows(
+            &to_resource_path(&scene_path, resource_path),
+            allow_globs,
+            deny_globs,
+        ) {
+            continue;
+        }
 
-            scenes_and_paths.insert(
-                scene_name.clone(),
-                (
-                    scene_name,
-                    to_resource_path(scene_path.as_str(), resource_path),
-                ),
+        // only the folders within the resource directory, so the path to the project isn't prepended
+        let folders = scene_file
+            .parent()
+            .and_then(|parent| parent.strip_prefix(resource_dir).ok())
+            .map(|parent| {
+                parent
+                    .components()
+                    .filter_map(|component| match component {
+                        Component::Normal(folder) => folder.to_str(),
+                        _ => None,
+                    })
+                    .collect::<Vec<&str>>()
+            })
+            .unwrap_or_default();
+        let folders = folders
+            .iter()
+            .map(|folder| to_scene_name(folder))
+            .collect::<Vec<String>>();
+        let folders = folders.iter().map(String::as_str).collect::<Vec<&str>>();
+        // keyed by the snake_case name, as `main.tscn` and `Main.tscn` generate the same identifiers
+        let (scene_name, collision) = unique_scene_name(
+            &to_scene_name(scene_file.file_stem().unwrap().to_str().unwrap()),
+            &folders,
+            |name| scenes_and_paths.contains_key(&pascal_to_snake_case(name)),
+        );
+        if let Some(collision) = collision {
+            println!(
+                "cargo::warning=Scenes {} and {} have the same name even with their folders prepended, naming the second {}",
+                scenes_and_paths[&pascal_to_snake_case(&collision)].1,
+                to_resource_path(&scene_path, resource_path),
+                scene_name
             );
         }
+
+        scenes_and_paths.insert(
+            pascal_to_snake_case(&scene_name),
+            (
+                scene_name,
+                to_resource_path(scene_path.as_str(), resource_path),
+            ),
+        );
     }
 
     // convert to vec and sort by least directories then alphabetical
-    let mut scenes_and_paths: Vec<(String, String)> =
-        scenes_and_paths.into_iter().map(|(_, v)| v).collect();
+    let mut scenes_and_paths: Vec<(String, String)> = scenes_and_paths.into_values().collect();
     scenes_and_paths
         .sort_by(|a, b| least_directories_then_alphabetical(&a.1.as_str(), &b.1.as_str()));
+    scenes_and_paths
+}
 
-    if scene_consts {
-        let mn = mod_name(CONSTS);
-        let consts_output = format_scenes_to_consts(&scenes_and_paths);
-        let consts_path = output_dir.join(format!("{}.rs", mn));
+/// Converts a file or folder name into a scene name, joining its alphanumeric words in camelCase, e.g. `level-one` -> `levelOne`.
+fn to_scene_name(name: &str) -> String {
+    let scene_name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(i, word)| match i {
+            0 => word.to_string(),
+            _ => to_upper_camel_case(word),
+        })
+        .collect::<String>();
 
-        make_path_if_not_exists(consts_path.to_str().unwrap());
+    if scene_name.is_empty() {
+        "scene".to_string()
+    } else {
+        scene_name
+    }
+}
+#[test]
+fn test_to_scene_name() {
+    assert_eq!(to_scene_name("Main"), "Main");
+    assert_eq!(to_scene_name("level-one"), "levelOne");
+    assert_eq!(to_scene_name("Level One"), "LevelOne");
+    assert_eq!(to_scene_name("boss_fight.v2"), "bossFightV2");
+    assert_eq!(to_scene_name("2d"), "2d");
+    assert_eq!(to_scene_name("()"), "scene");
+}
+
+/// Prefixes an identifier with `_` if it starts with a digit, e.g. the module of `2d.tscn`.
+pub(crate) fn prefix_leading_digit(ident: String) -> String {
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else {
+        ident
+    }
+}
+#[test]
+fn test_prefix_leading_digit() {
+    assert_eq!(prefix_leading_digit("main".to_string()), "main");
+    assert_eq!(prefix_leading_digit("2d_level".to_string()), "_2d_level");
+    assert_eq!(prefix_leading_digit("2D_LEVEL".to_string()), "_2D_LEVEL");
+}
 
-        let mut consts_file = fs::File::create(consts_path).unwrap();
-        consts_file.write_all(consts_output.as_bytes()).unwrap();
+/// The keywords of the 2024 edition, which can't name a module without an `r#` prefix.
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
 
-        generated_modules.push(mn)
+/// Converts a scene or folder name into a snake_case module name, or an autoload name into its accessor, prefixing it with `r#`
+/// if it is a keyword, or with `_` if it starts with a digit or is a path keyword which can't be a raw identifier.
+pub(crate) fn to_module_name(name: &str) -> String {
+    let module_name = pascal_to_snake_case(name);
+    match module_name.as_str() {
+        "crate" | "self" | "super" => format!("_{}", module_name),
+        keyword if RUST_KEYWORDS.contains(&keyword) => format!("r#{}", module_name),
+        _ => prefix_leading_digit(module_name),
     }
+}
+#[test]
+fn test_to_module_name() {
+    assert_eq!(to_module_name("LevelOne"), "level_one");
+    assert_eq!(to_module_name("2dLevels"), "_2d_levels");
+    assert_eq!(to_module_name("type"), "r#type");
+    assert_eq!(to_module_name("Match"), "r#match");
+    assert_eq!(to_module_name("self"), "_self");
+    assert_eq!(to_module_name(&to_scene_name("ui-menus")), "ui_menus");
+    assert_eq!(to_module_name(&to_scene_name("world maps")), "world_maps");
+}
 
-    if scene_actions {
-        let mn = mod_name(ACTIONS);
-        let actions_output = format_scenes_to_actions(&scenes_and_paths);
-        let actions_path = output_dir.join(format!("{}.rs", mn));
+/// Writes the output of a scene generator to `<output_dir>/<mn>.rs`, recording the module.
+fn write_scene_module(
+    output_dir: &Path,
+    mn: String,
+    output: &str,
+    generated_modules: &mut Vec<GeneratedModule>,
+) -> io::Result<()> {
+    let module_path = output_dir.join(format!("{}.rs", mn));
 
-        make_path_if_not_exists(actions_path.to_str().unwrap());
+    write_generated_file(&module_path, output)?;
 
-        let mut actions_file = fs::File::create(actions_path).unwrap();
-        actions_file.write_all(actions_output.as_bytes()).unwrap();
+    generated_modules.push(GeneratedModule {
+        name: mn,
+        path: module_path,
+    });
+    Ok(())
+}
 
-        generated_modules.push(mn)
-    }
+/// Whether a scene passes the scene filter, by its file name or its `res://` path relative to the resource directory.
+///
+/// A scene must match one of the `allow_globs`, if there are any, and none of the `deny_globs`.
+fn scene_filter_allows(scene_path: &str, allow_globs: &[String], deny_globs: &[String]) -> bool {
+    let relative = scene_path.trim_start_matches("res://");
+    let file_name = relative.rsplit('/').next().unwrap_or(relative);
+    let file_stem = file_name.strip_suffix(".tscn").unwrap_or(file_name);
+    let matches = |glob: &String| {
+        [relative, file_name, file_stem]
+            .iter()
+            .any(|candidate| matches_glob(glob, candidate))
+    };
+
+    (allow_globs.is_empty() || allow_globs.iter().any(matches)) && !deny_globs.iter().any(matches)
+}
+#[test]
+fn test_scene_filter_allows() {
+    let deny = vec!["Test_*".to_string(), "demo/**".to_string()];
+    assert!(scene_filter_allows("res://scenes/Level.tscn", &[], &deny));
+    assert!(!scene_filter_allows(
+        "res://scenes/Test_Level.tscn",
+        &[],
+        &deny
+    ));
+    assert!(!scene_filter_allows("res://demo/Showcase.tscn", &[], &deny));
+
+    let allow = vec!["scenes/**".to_string()];
+    assert!(scene_filter_allows(
+        "res://scenes/Level.tscn",
+        &allow,
+        &deny
+    ));
+    assert!(!scene_filter_allows("res://ui/Menu.tscn", &allow, &deny));
+    assert!(!scene_filter_allows(
+        "res://scenes/Test_Level.tscn",
+        &allow,
+        &deny
+    ));
+}
+#[test]
+fn test_generate_scenes_filter() {
+    let dir = crate::utils::TestDir::new("generate_scenes_filter");
+    fs::create_dir_all(dir.join("godot").join("levels")).unwrap();
+    fs::create_dir_all(dir.join("generated")).unwrap();
+    fs::write(dir.join("godot").join("levels").join("Level.tscn"), "").unwrap();
+    fs::write(dir.join("godot").join("levels").join("Test_Level.tscn"), "").unwrap();
+
+    let modules = generate_scenes(
+        dir.join("generated").to_str().unwrap(),
+        dir.join("godot").to_str().unwrap(),
+        &SceneOptions {
+            allow_globs: &[],
+            deny_globs: &["Test_*".to_string()],
+            skip_dirs: &[],
+            scene_consts: true,
+            scene_const_kinds: &[SceneConstKind::Str],
+            scene_consts_grouped: false,
+            scene_consts_use_uid: false,
+            scene_actions: false,
+            scene_actions_impl_for: "Node",
+            custom_class_paths: &BTreeMap::new(),
+            current_scene_predicates: false,
+            scene_nodes: false,
+            scene_dependencies: false,
+            scene_node_accessors: false,
+            scene_spawners: false,
+            const_prefix: "",
+            strict_lints: false,
+        },
+    )
+    .unwrap();
+
+    let content = fs::read_to_string(&modules[0].path).unwrap();
+    assert!(content.contains("pub const LEVEL: &'static str = \"res://levels/Level.tscn\";"));
+    assert!(!content.contains("Test_Level"));
+}
+#[test]
+fn test_find_scenes_skips_godot_cache_dirs() {
+    let dir = crate::utils::TestDir::new("find_scenes_skips_godot_cache_dirs");
+    fs::create_dir_all(dir.join(".godot").join("editor")).unwrap();
+    fs::create_dir_all(dir.join(".import")).unwrap();
+    fs::create_dir_all(dir.join("levels")).unwrap();
+    fs::write(dir.join("levels").join("Level.tscn"), "").unwrap();
+    fs::write(dir.join(".godot").join("editor").join("Cached.tscn"), "").unwrap();
+    fs::write(dir.join(".import").join("Imported.tscn"), "").unwrap();
+    let resource_path = dir.to_str().unwrap();
+
+    assert_eq!(
+        find_scenes(resource_path, &[], &[], crate::utils::GODOT_CACHE_DIRS),
+        vec![("Level".to_string(), "res://levels/Level.tscn".to_string())]
+    );
+    assert_eq!(find_scenes(resource_path, &[], &[], &[]).len(), 3);
+}
+#[test]
+fn test_find_scenes_case_collisions() {
+    let dir = crate::utils::TestDir::new("find_scenes_case_collisions");
+    fs::create_dir_all(dir.join("levels")).unwrap();
+    fs::write(dir.join("levels").join("main.tscn"), "").unwrap();
+    fs::write(dir.join("levels").join("Main.tscn"), "").unwrap();
+    fs::write(dir.join("levels").join("2d-level.tscn"), "").unwrap();
 
-    generated_modules
+    let scenes = find_scenes(dir.to_str().unwrap(), &[], &[], &[]);
+    let mut const_names = scenes
+        .iter()
+        .map(|(name, _)| pascal_to_snake_case(name).to_uppercase())
+        .collect::<Vec<String>>();
+    const_names.sort();
+    const_names.dedup();
+    assert_eq!(const_names.len(), 3);
+    assert!(scenes.contains(&(
+        "2dLevel".to_string(),
+        "res://levels/2d-level.tscn".to_string()
+    )));
+}
+#[test]
+fn test_find_scenes_collisions_ignore_resource_path() {
+    let dir = crate::utils::TestDir::new("find_scenes_collisions_ignore_resource_path");
+    let resource_dir = dir.join("game");
+    fs::create_dir_all(&resource_dir).unwrap();
+    fs::write(resource_dir.join("main.tscn"), "").unwrap();
+    fs::write(resource_dir.join("Main.tscn"), "").unwrap();
+
+    // with no folders within the resource directory to prepend, the second scene is indexed rather than named `gameMain`
+    let mut scene_names = find_scenes(resource_dir.to_str().unwrap(), &[], &[], &[])
+        .into_iter()
+        .map(|(name, _)| name.to_lowercase())
+        .collect::<Vec<String>>();
+    scene_names.sort();
+    assert_eq!(scene_names, vec!["main", "main2"]);
+}
+
+/// Reads the content of each scene, skipping (with a warning) any that cannot be read.
+fn read_scene_files<'a>(
+    resource_path: &str,
+    scenes_and_paths: &'a [(String, String)],
+) -> Vec<(&'a String, &'a String, String)> {
+    scenes_and_paths
+        .iter()
+        .filter_map(|(name, path)| {
+            let file_path = Path::new(resource_path).join(path.trim_start_matches("res://"));
+
+            match fs::read_to_string(&file_path) {
+                Ok(content) => Some((name, path, content)),
+                Err(e) => {
+                    println!(
+                        "cargo::warning=Failed to read scene {}: {}, skipping",
+                        file_path.display(),
+                        e
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Reads the `uid` of each scene from its `[gd_scene]` header, by scene path. Scenes without a uid, e.g. saved by Godot
+/// before 4.0, are left out so their consts fall back to the path.
+fn read_scene_uids(
+    resource_path: &str,
+    scenes_and_paths: &[(String, String)],
+) -> HashMap<String, String> {
+    read_scene_files(resource_path, scenes_and_paths)
+        .iter()
+        .filter_map(|(_, path, content)| {
+            Tscn::parse_from_str(content)
+                .uid
+                .map(|uid| (path.to_string(), uid.to_string()))
+        })
+        .collect()
+}
+#[test]
+fn test_read_scene_uids() {
+    let dir = crate::utils::TestDir::new("read_scene_uids");
+    fs::write(
+        dir.join("Main.tscn"),
+        "[gd_scene load_steps=2 format=3 uid=\"uid://c3385nybf0m1\"]\n\n[node name=\"Main\" type=\"Node2D\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Old.tscn"),
+        "[gd_scene format=2]\n\n[node name=\"Old\" type=\"Node2D\"]\n",
+    )
+    .unwrap();
+
+    let scenes_and_paths = vec![
+        ("Main".to_string(), "res://Main.tscn".to_string()),
+        ("Old".to_string(), "res://Old.tscn".to_string()),
+    ];
+    let uids = read_scene_uids(dir.to_str().unwrap(), &scenes_and_paths);
+    assert_eq!(
+        uids,
+        HashMap::from([(
+            "res://Main.tscn".to_string(),
+            "uid://c3385nybf0m1".to_string()
+        )])
+    );
+    assert!(
+        format_scenes_to_consts(&scenes_and_paths, &[SceneConstKind::Str], &uids, "", false)
+            .ends_with("pub const MAIN: &'static str = \"uid://c3385nybf0m1\";\n/// `res://Old.tscn`\npub const OLD: &'static str = \"res://Old.tscn\";")
+    );
 }
 
 fn mod_name(output: &str) -> String {
@@ -129,12 +618,26 @@ fn test_least_directories_then_alphabetical() {
     assert_eq!(paths, expected);
 }
 
-fn format_scenes_to_consts(scenes_and_paths: &Vec<(String, String)>) -> String {
+fn format_scenes_to_consts(
+    scenes_and_paths: &[(String, String)],
+    kinds: &[SceneConstKind],
+    uids: &HashMap<String, String>,
+    const_prefix: &str,
+    strict_lints: bool,
+) -> String {
+    let header = format_scene_consts_header(kinds, strict_lints);
+
     format!(
-        "#![allow(dead_code)]\n{}",
+        "{}{}",
+        header,
         scenes_and_paths
             .iter()
-            .map(|(name, path)| format_scene_to_const(name, path))
+            .flat_map(|(name, path)| {
+                let uid = uids.get(path).map(String::as_str);
+                kinds.iter().map(move |kind| {
+                    format_scene_to_const(name, path, uid, *kind, const_prefix, strict_lints)
+                })
+            })
             .collect::<Vec<String>>()
             .join("\n")
     )
@@ -151,67 +654,606 @@ fn test_format_scenes_to_consts() {
 
     let expected = "#![allow(dead_code)]\n/// `res://scenes/Main.tscn`\npub const MAIN: &'static str = \"res://scenes/Main.tscn\";\n/// `res://scenes/LevelOne.tscn`\npub const LEVEL_ONE: &'static str = \"res://scenes/LevelOne.tscn\";";
 
-    let result = format_scenes_to_consts(&scenes_and_paths);
+    let result = format_scenes_to_consts(
+        &scenes_and_paths,
+        &[SceneConstKind::Str],
+        &HashMap::new(),
+        "",
+        false,
+    );
     assert_eq!(result, expected);
 }
+#[test]
+fn test_format_scenes_to_consts_kinds() {
+    let scenes_and_paths = vec![("Main".to_string(), "res://scenes/Main.tscn".to_string())];
+
+    assert_eq!(
+        format_scenes_to_consts(
+            &scenes_and_paths,
+            &[
+                SceneConstKind::Str,
+                SceneConstKind::StringName,
+                SceneConstKind::NodePath
+            ],
+            &HashMap::new(),
+            "",
+            false
+        ),
+        r#"#![allow(dead_code)]
+#![allow(non_snake_case)]
+use godot::builtin::{NodePath, StringName};
+
+/// `res://scenes/Main.tscn`
+pub const MAIN: &'static str = "res://scenes/Main.tscn";
+/// `res://scenes/Main.tscn`
+pub fn MAIN_NAME() -> StringName { StringName::from("res://scenes/Main.tscn") }
+/// `res://scenes/Main.tscn`
+pub fn MAIN_NODE_PATH() -> NodePath { NodePath::from("res://scenes/Main.tscn") }"#
+    );
+    assert_eq!(
+        format_scenes_to_consts(
+            &scenes_and_paths,
+            &[SceneConstKind::StringName],
+            &HashMap::new(),
+            "",
+            false
+        ),
+        r#"#![allow(dead_code)]
+#![allow(non_snake_case)]
+use godot::builtin::StringName;
+
+/// `res://scenes/Main.tscn`
+pub fn MAIN_NAME() -> StringName { StringName::from("res://scenes/Main.tscn") }"#
+    );
+}
+#[test]
+fn test_format_scenes_to_consts_strict_lints() {
+    let scenes_and_paths = vec![("Main".to_string(), "res://scenes/Main.tscn".to_string())];
+
+    assert_eq!(
+        format_scenes_to_consts(
+            &scenes_and_paths,
+            &[SceneConstKind::Str, SceneConstKind::StringName],
+            &HashMap::new(),
+            "",
+            true
+        ),
+        r#"use godot::builtin::StringName;
+
+/// `res://scenes/Main.tscn`
+pub const MAIN: &'static str = "res://scenes/Main.tscn";
+/// `res://scenes/Main.tscn`
+#[allow(non_snake_case)]
+pub fn MAIN_NAME() -> StringName { StringName::from("res://scenes/Main.tscn") }"#
+    );
+    assert!(
+        format_scenes_to_actions(&scenes_and_paths, "Node", &BTreeMap::new(), false, true)
+            .starts_with("use godot::{")
+    );
+}
+
+fn format_scene_consts_header(kinds: &[SceneConstKind], strict_lints: bool) -> String {
+    let mut imports = vec![];
+    if kinds.contains(&SceneConstKind::NodePath) {
+        imports.push("NodePath");
+    }
+    if kinds.contains(&SceneConstKind::StringName) {
+        imports.push("StringName");
+    }
+
+    match imports.as_slice() {
+        [] => format_lint_allows(strict_lints, false).to_string(),
+        [import] => format!(
+            "{}use godot::builtin::{};\n\n",
+            format_lint_allows(strict_lints, true),
+            import
+        ),
+        _ => format!(
+            "{}use godot::builtin::{{{}}};\n\n",
+            format_lint_allows(strict_lints, true),
+            imports.join(", ")
+        ),
+    }
+}
+
+fn format_scenes_to_grouped_consts(
+    scenes_and_paths: &[(String, String)],
+    kinds: &[SceneConstKind],
+    uids: &HashMap<String, String>,
+    const_prefix: &str,
+    strict_lints: bool,
+) -> String {
+    let format_consts = |scenes: &[(String, String)], indent: &str| {
+        scenes
+            .iter()
+            .flat_map(|(name, path)| {
+                let uid = uids.get(path).map(String::as_str);
+                kinds.iter().map(move |kind| {
+                    format_scene_to_const(name, path, uid, *kind, const_prefix, strict_lints)
+                })
+            })
+            .flat_map(|c| {
+                c.lines()
+                    .map(|line| format!("{}{}", indent, line))
+                    .collect::<Vec<String>>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+    let uses_godot_types = kinds.iter().any(|kind| *kind != SceneConstKind::Str);
+
+    format!(
+        "{}{}",
+        format_scene_consts_header(kinds, strict_lints),
+        group_scenes_by_folder(scenes_and_paths)
+            .iter()
+            .map(|(group, scenes)| match group {
+                None => format_consts(scenes, ""),
+                Some(group) => format!(
+                    "pub mod {} {{\n{}{}\n}}",
+                    to_module_name(&to_scene_name(group)),
+                    if uses_godot_types {
+                        "    use super::*;\n\n"
+                    } else {
+                        ""
+                    },
+                    format_consts(scenes, "    ")
+                ),
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    )
+}
+#[test]
+fn test_format_scenes_to_grouped_consts() {
+    let scenes_and_paths = vec![
+        ("Main".to_string(), "res://Main.tscn".to_string()),
+        ("Menu".to_string(), "res://ui/Menu.tscn".to_string()),
+        ("Level".to_string(), "res://world/Level.tscn".to_string()),
+        ("uiMenu".to_string(), "res://ui/pause/Menu.tscn".to_string()),
+    ];
+
+    assert_eq!(
+        format_scenes_to_grouped_consts(
+            &scenes_and_paths,
+            &[SceneConstKind::Str],
+            &HashMap::new(),
+            "",
+            false
+        ),
+        r#"#![allow(dead_code)]
+/// `res://Main.tscn`
+pub const MAIN: &'static str = "res://Main.tscn";
+
+pub mod ui {
+    /// `res://ui/Menu.tscn`
+    pub const MENU: &'static str = "res://ui/Menu.tscn";
+    /// `res://ui/pause/Menu.tscn`
+    pub const PAUSE_MENU: &'static str = "res://ui/pause/Menu.tscn";
+}
+
+pub mod world {
+    /// `res://world/Level.tscn`
+    pub const LEVEL: &'static str = "res://world/Level.tscn";
+}"#
+    );
+    assert_eq!(
+        format_scenes_to_grouped_consts(
+            &scenes_and_paths[1..2],
+            &[SceneConstKind::StringName],
+            &HashMap::new(),
+            "",
+            false
+        ),
+        r#"#![allow(dead_code)]
+#![allow(non_snake_case)]
+use godot::builtin::StringName;
+
+pub mod ui {
+    use super::*;
+
+    /// `res://ui/Menu.tscn`
+    pub fn MENU_NAME() -> StringName { StringName::from("res://ui/Menu.tscn") }
+}"#
+    );
+    // folder names which aren't valid identifiers as written
+    assert_eq!(
+        format_scenes_to_grouped_consts(
+            &[
+                (
+                    "Level".to_string(),
+                    "res://2d-levels/Level.tscn".to_string()
+                ),
+                ("Menu".to_string(), "res://type/Menu.tscn".to_string()),
+            ],
+            &[SceneConstKind::Str],
+            &HashMap::new(),
+            "",
+            false
+        ),
+        r#"#![allow(dead_code)]
+pub mod _2d_levels {
+    /// `res://2d-levels/Level.tscn`
+    pub const LEVEL: &'static str = "res://2d-levels/Level.tscn";
+}
+
+pub mod r#type {
+    /// `res://type/Menu.tscn`
+    pub const MENU: &'static str = "res://type/Menu.tscn";
+}"#
+    );
+}
+
+/// Names a scene by its file name, prepending its parent `folders`, innermost first, while the name is taken.
+///
+/// If the name is still taken with every folder prepended, an index is appended to it, e.g. `abMenu2`, and the taken name is
+/// returned alongside it to warn about.
+fn unique_scene_name(
+    file_stem: &str,
+    folders: &[&str],
+    is_taken: impl Fn(&str) -> bool,
+) -> (String, Option<String>) {
+    let mut scene_name = file_stem.to_string();
+    let mut folders = folders;
+    while is_taken(&scene_name) {
+        let Some((folder, rest)) = folders.split_last() else {
+            let index = (2..)
+                .find(|index| !is_taken(&format!("{}{}", scene_name, index)))
+                .unwrap();
+            return (format!("{}{}", scene_name, index), Some(scene_name));
+        };
+        scene_name = format!("{}{}", folder, scene_name);
+        folders = rest;
+    }
+
+    (scene_name, None)
+}
+#[test]
+fn test_unique_scene_name() {
+    let taken = ["Menu", "bMenu", "abMenu", "abMenu2"];
+    let is_taken = |name: &str| taken.contains(&name);
+
+    assert_eq!(
+        unique_scene_name("Level", &["a", "b"], is_taken),
+        ("Level".to_string(), None)
+    );
+    assert_eq!(
+        unique_scene_name("Menu", &["c", "b"], is_taken),
+        ("cbMenu".to_string(), None)
+    );
+    // prepending every folder is not enough, so an index is appended
+    assert_eq!(
+        unique_scene_name("Menu", &["a", "b"], is_taken),
+        ("abMenu3".to_string(), Some("abMenu".to_string()))
+    );
+    assert_eq!(
+        unique_scene_name("Menu", &[], is_taken),
+        ("Menu2".to_string(), Some("Menu".to_string()))
+    );
+}
+
+/// Groups scenes by the top-level folder of their resource path, with `None` for scenes at the root.
+///
+/// Scenes are named by their file name, prepending parent folders below the top-level folder only while names collide.
+fn group_scenes_by_folder(
+    scenes_and_paths: &[(String, String)],
+) -> BTreeMap<Option<String>, Vec<(String, String)>> {
+    let mut groups: BTreeMap<Option<String>, Vec<(String, String)>> = BTreeMap::new();
+
+    for (_, path) in scenes_and_paths {
+        let components = path
+            .trim_start_matches("res://")
+            .split('/')
+            .collect::<Vec<&str>>();
+        let (folders, file_name) = components.split_at(components.len() - 1);
+        let (group, folders) = match folders.split_first() {
+            Some((group, rest)) => (Some(group.to_string()), rest),
+            None => (None, folders),
+        };
+
+        let folders = folders
+            .iter()
+            .map(|folder| to_scene_name(folder))
+            .collect::<Vec<String>>();
+        let folders = folders.iter().map(String::as_str).collect::<Vec<&str>>();
+
+        let scenes = groups.entry(group).or_default();
+        let (scene_name, _) = unique_scene_name(
+            &to_scene_name(file_name[0].trim_end_matches(".tscn")),
+            &folders,
+            |name| {
+                scenes
+                    .iter()
+                    .any(|(scene, _)| pascal_to_snake_case(scene) == pascal_to_snake_case(name))
+            },
+        );
+
+        scenes.push((scene_name, path.clone()));
+    }
+
+    groups
+}
+#[test]
+fn test_group_scenes_by_folder() {
+    let groups = group_scenes_by_folder(&[
+        ("Main".to_string(), "res://Main.tscn".to_string()),
+        ("Menu".to_string(), "res://ui/Menu.tscn".to_string()),
+        ("Level".to_string(), "res://world/Level.tscn".to_string()),
+        ("uiMenu".to_string(), "res://ui/pause/Menu.tscn".to_string()),
+    ]);
+
+    assert_eq!(
+        groups.keys().collect::<Vec<_>>(),
+        vec![&None, &Some("ui".to_string()), &Some("world".to_string())]
+    );
+    assert_eq!(
+        groups[&Some("ui".to_string())],
+        vec![
+            ("Menu".to_string(), "res://ui/Menu.tscn".to_string()),
+            (
+                "pauseMenu".to_string(),
+                "res://ui/pause/Menu.tscn".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        groups[&Some("world".to_string())],
+        vec![("Level".to_string(), "res://world/Level.tscn".to_string())]
+    );
+}
+
+/// Formats a const for a scene of the given kind, with the scene's `uid` as its value if given, or its path otherwise.
+fn format_scene_to_const(
+    scene_name: &str,
+    scene_path: &str,
+    uid: Option<&str>,
+    kind: SceneConstKind,
+    const_prefix: &str,
+    strict_lints: bool,
+) -> String {
+    let const_name = prefix_leading_digit(format!(
+        "{}{}",
+        const_prefix,
+        pascal_to_snake_case(scene_name).to_uppercase()
+    ));
+    let value = uid.unwrap_or(scene_path);
+    let lint_allow = match kind {
+        SceneConstKind::Str => String::new(),
+        SceneConstKind::StringName | SceneConstKind::NodePath => {
+            format_item_allow(strict_lints, "non_snake_case")
+        }
+    };
 
-fn format_scene_to_const(scene_name: &str, scene_path: &str) -> String {
     format!(
-        "{}\npub const {}: &'static str = \"{}\";",
+        "{}\n{}{}",
         format_scene_to_doc_comment(scene_path),
-        pascal_to_snake_case(scene_name).to_uppercase(),
-        scene_path
+        lint_allow,
+        match kind {
+            SceneConstKind::Str =>
+                format!("pub const {}: &'static str = \"{}\";", const_name, value),
+            SceneConstKind::StringName => format!(
+                "pub fn {}_NAME() -> StringName {{ StringName::from(\"{}\") }}",
+                const_name, value
+            ),
+            SceneConstKind::NodePath => format!(
+                "pub fn {}_NODE_PATH() -> NodePath {{ NodePath::from(\"{}\") }}",
+                const_name, value
+            ),
+        }
     )
 }
 #[test]
 fn test_format_scene_to_const() {
     assert_eq!(
-        format_scene_to_const("Main", "res://scenes/Main.tscn"),
+        format_scene_to_const(
+            "Main",
+            "res://scenes/Main.tscn",
+            None,
+            SceneConstKind::Str,
+            "",
+            false
+        ),
         "/// `res://scenes/Main.tscn`\npub const MAIN: &'static str = \"res://scenes/Main.tscn\";"
     );
     assert_eq!(
-        format_scene_to_const("LevelOne", "res://scenes/LevelOne.tscn"),
+        format_scene_to_const(
+            "LevelOne",
+            "res://scenes/LevelOne.tscn",
+            None,
+            SceneConstKind::Str,
+            "",
+            false
+        ),
         "/// `res://scenes/LevelOne.tscn`\npub const LEVEL_ONE: &'static str = \"res://scenes/LevelOne.tscn\";"
     );
+    assert_eq!(
+        format_scene_to_const(
+            "LevelOne",
+            "res://scenes/LevelOne.tscn",
+            None,
+            SceneConstKind::StringName,
+            "",
+            false
+        ),
+        "/// `res://scenes/LevelOne.tscn`\npub fn LEVEL_ONE_NAME() -> StringName { StringName::from(\"res://scenes/LevelOne.tscn\") }"
+    );
+    assert_eq!(
+        format_scene_to_const(
+            "LevelOne",
+            "res://scenes/LevelOne.tscn",
+            None,
+            SceneConstKind::NodePath,
+            "",
+            false
+        ),
+        "/// `res://scenes/LevelOne.tscn`\npub fn LEVEL_ONE_NODE_PATH() -> NodePath { NodePath::from(\"res://scenes/LevelOne.tscn\") }"
+    );
+    assert_eq!(
+        format_scene_to_const(
+            "Main",
+            "res://scenes/Main.tscn",
+            Some("uid://c3385nybf0m1"),
+            SceneConstKind::Str,
+            "",
+            false
+        ),
+        "/// `res://scenes/Main.tscn`\npub const MAIN: &'static str = \"uid://c3385nybf0m1\";"
+    );
+    assert_eq!(
+        format_scene_to_const(
+            "2dLevel",
+            "res://scenes/2d-level.tscn",
+            None,
+            SceneConstKind::Str,
+            "",
+            false
+        ),
+        "/// `res://scenes/2d-level.tscn`\npub const _2D_LEVEL: &'static str = \"res://scenes/2d-level.tscn\";"
+    );
 }
+#[test]
+fn test_format_scene_to_const_prefix() {
+    assert_eq!(
+        format_scene_to_const(
+            "Main",
+            "res://scenes/Main.tscn",
+            None,
+            SceneConstKind::Str,
+            "SCENE_",
+            false
+        ),
+        "/// `res://scenes/Main.tscn`\npub const SCENE_MAIN: &'static str = \"res://scenes/Main.tscn\";"
+    );
+    assert_eq!(
+        format_scene_to_const(
+            "LevelOne",
+            "res://scenes/LevelOne.tscn",
+            None,
+            SceneConstKind::StringName,
+            "SCENE_",
+            false
+        ),
+        "/// `res://scenes/LevelOne.tscn`\npub fn SCENE_LEVEL_ONE_NAME() -> StringName { StringName::from(\"res://scenes/LevelOne.tscn\") }"
+    );
+}
+
+/// Formats the `SceneActions` trait and its impl for `impl_for`, which is `Node` by default.
+///
+/// As `SceneTree` has no `get_tree()`, the trait is implemented for `Gd<SceneTree>` and changes scene directly.
+/// Engine classes are imported from `godot::classes`, while other types are user classes, named by their path or their
+/// entry in `custom_class_paths`, which reach the tree through `self.base()`.
+///
+/// With `current_scene_predicates`, an `is_current_scene_<name>()` predicate is also generated for each scene.
+fn format_scenes_to_actions(
+    scenes_and_paths: &[(String, String)],
+    impl_for: &str,
+    custom_class_paths: &BTreeMap<String, String>,
+    current_scene_predicates: bool,
+    strict_lints: bool,
+) -> String {
+    let (imports, impl_type, scene_tree, maybe_scene_tree) = match impl_for {
+        "Node" => (
+            "    prelude::Node,\n".to_string(),
+            impl_for.to_string(),
+            "self.get_tree().ok_or(SceneChangeError::NoSceneTree)?",
+            "self.get_tree()",
+        ),
+        "SceneTree" => (
+            "    classes::SceneTree,\n    obj::Gd,\n".to_string(),
+            "Gd<SceneTree>".to_string(),
+            "self.clone()",
+            "Some(self.clone())",
+        ),
+        _ if is_engine_class(impl_for) => (
+            format!("    classes::{},\n", impl_for),
+            impl_for.to_string(),
+            "self.get_tree().ok_or(SceneChangeError::NoSceneTree)?",
+            "self.get_tree()",
+        ),
+        _ => (
+            "    obj::WithBaseField,\n".to_string(),
+            custom_class_paths
+                .get(impl_for)
+                .map_or(impl_for, String::as_str)
+                .to_string(),
+            "self.base().get_tree().ok_or(SceneChangeError::NoSceneTree)?",
+            "self.base().get_tree()",
+        ),
+    };
+
+    let (predicate_traits, predicate_impls) = if current_scene_predicates {
+        (
+            format!(
+                "\n    /// Whether the current scene of the tree was loaded from `scene_path`.\n    fn is_current_scene(&self, scene_path: &str) -> bool;\n{}",
+                scenes_and_paths
+                    .iter()
+                    .map(|(name, path)| format_scene_to_predicate_trait(name, path))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+            format!(
+                "\n\n    fn is_current_scene(&self, scene_path: &str) -> bool {{\n        {}\n            .and_then(|st| st.get_current_scene())\n            .is_some_and(|scene| scene.get_scene_file_path() == GString::from(scene_path))\n    }}\n\n{}",
+                maybe_scene_tree,
+                scenes_and_paths
+                    .iter()
+                    .map(|(name, path)| format_scene_to_predicate_impl(name, path))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+        )
+    } else {
+        (String::new(), String::new())
+    };
 
-fn format_scenes_to_actions(scenes_and_paths: &Vec<(String, String)>) -> String {
     format!(
-        r#"#![allow(dead_code)]
-use godot::{{
-    prelude::Node,
-    global::Error
+        r#"{}use godot::{{
+    builtin::GString,
+{}    global::Error
 }};
 
+/// The reason a scene change could not be started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneChangeError {{
+    /// The node is not inside a `SceneTree`.
+    NoSceneTree,
+    /// Godot returned an error from `change_scene_to_file`.
+    Godot(Error),
+}}
+
 pub trait SceneActions {{
-    fn change_scene_to(&self, scene_path: &str) -> Option<Error>;
-{}
+    /// Changes to the scene at `scene_path`, returning `Ok(())` when Godot reports `Error::OK`.
+    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError>;
+{}{}
 }}
 
-impl SceneActions for Node {{
-    fn change_scene_to(&self, scene_path: &str) -> Option<Error> {{
-        let st = self.get_tree();
-        let mut err = None;
+impl SceneActions for {} {{
+    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError> {{
+        let mut st = {};
 
-        if st.is_some() {{
-            err = Some(st.unwrap().change_scene_to_file(scene_path));
+        match st.change_scene_to_file(&GString::from(scene_path)) {{
+            Error::OK => Ok(()),
+            err => Err(SceneChangeError::Godot(err)),
         }}
-
-        err
     }}
 
-{}
+{}{}
 }}"#,
+        format_lint_allows(strict_lints, false),
+        imports,
         scenes_and_paths
             .iter()
             .map(|(name, path)| format_scene_to_action_trait(name, path))
             .collect::<Vec<String>>()
             .join("\n"),
+        predicate_traits,
+        impl_type,
+        scene_tree,
         scenes_and_paths
             .iter()
             .map(|(name, path)| format_scene_to_action_impl(name, path))
             .collect::<Vec<String>>()
-            .join("\n")
+            .join("\n"),
+        predicate_impls
     )
 }
 #[test]
@@ -226,41 +1268,156 @@ fn test_format_scenes_to_actions() {
 
     let expected = r#"#![allow(dead_code)]
 use godot::{
+    builtin::GString,
     prelude::Node,
     global::Error
 };
 
+/// The reason a scene change could not be started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneChangeError {
+    /// The node is not inside a `SceneTree`.
+    NoSceneTree,
+    /// Godot returned an error from `change_scene_to_file`.
+    Godot(Error),
+}
+
 pub trait SceneActions {
-    fn change_scene_to(&self, scene_path: &str) -> Option<Error>;
+    /// Changes to the scene at `scene_path`, returning `Ok(())` when Godot reports `Error::OK`.
+    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError>;
     /// `res://scenes/Main.tscn`
-    fn change_scene_to_main(&self) -> Option<Error>;
+    fn change_scene_to_main(&self) -> Result<(), SceneChangeError>;
     /// `res://scenes/LevelOne.tscn`
-    fn change_scene_to_level_one(&self) -> Option<Error>;
+    fn change_scene_to_level_one(&self) -> Result<(), SceneChangeError>;
 }
 
 impl SceneActions for Node {
-    fn change_scene_to(&self, scene_path: &str) -> Option<Error> {
-        let st = self.get_tree();
-        let mut err = None;
+    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError> {
+        let mut st = self.get_tree().ok_or(SceneChangeError::NoSceneTree)?;
 
-        if st.is_some() {
-            err = Some(st.unwrap().change_scene_to_file(scene_path));
+        match st.change_scene_to_file(&GString::from(scene_path)) {
+            Error::OK => Ok(()),
+            err => Err(SceneChangeError::Godot(err)),
         }
-
-        err
     }
 
-    fn change_scene_to_main(&self) -> Option<Error> { self.change_scene_to("res://scenes/Main.tscn") }
-    fn change_scene_to_level_one(&self) -> Option<Error> { self.change_scene_to("res://scenes/LevelOne.tscn") }
+    fn change_scene_to_main(&self) -> Result<(), SceneChangeError> { self.change_scene_to("res://scenes/Main.tscn") }
+    fn change_scene_to_level_one(&self) -> Result<(), SceneChangeError> { self.change_scene_to("res://scenes/LevelOne.tscn") }
 }"#;
 
-    let result = format_scenes_to_actions(&scenes_and_paths);
+    let result =
+        format_scenes_to_actions(&scenes_and_paths, "Node", &BTreeMap::new(), false, false);
     assert_eq!(result, expected);
 }
+#[test]
+fn test_format_scenes_to_actions_impl_for_scene_tree() {
+    let scenes_and_paths = vec![("Main".to_string(), "res://scenes/Main.tscn".to_string())];
+
+    let result = format_scenes_to_actions(
+        &scenes_and_paths,
+        "SceneTree",
+        &BTreeMap::new(),
+        false,
+        false,
+    );
+
+    assert!(
+        result.contains(
+            "use godot::{\n    builtin::GString,\n    classes::SceneTree,\n    obj::Gd,\n    global::Error\n};\n"
+        )
+    );
+    assert!(result.contains(
+        "impl SceneActions for Gd<SceneTree> {\n    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError> {\n        let mut st = self.clone();\n\n        match st.change_scene_to_file(&GString::from(scene_path)) {"
+    ));
+    assert!(!result.contains("get_tree()"));
+}
+#[test]
+fn test_format_scenes_to_actions_impl_for_class() {
+    let scenes_and_paths = vec![("Main".to_string(), "res://scenes/Main.tscn".to_string())];
+
+    // engine classes are imported
+    let result =
+        format_scenes_to_actions(&scenes_and_paths, "Control", &BTreeMap::new(), false, false);
+
+    assert!(result.contains(
+        "use godot::{\n    builtin::GString,\n    classes::Control,\n    global::Error\n};\n"
+    ));
+    assert!(result.contains(
+        "impl SceneActions for Control {\n    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError> {\n        let mut st = self.get_tree().ok_or(SceneChangeError::NoSceneTree)?;\n"
+    ));
+
+    // user classes reach the tree through their base
+    let result = format_scenes_to_actions(
+        &scenes_and_paths,
+        "crate::SceneManager",
+        &BTreeMap::new(),
+        true,
+        false,
+    );
+
+    assert!(result.contains(
+        "use godot::{\n    builtin::GString,\n    obj::WithBaseField,\n    global::Error\n};\n"
+    ));
+    assert!(result.contains(
+        "impl SceneActions for crate::SceneManager {\n    fn change_scene_to(&self, scene_path: &str) -> Result<(), SceneChangeError> {\n        let mut st = self.base().get_tree().ok_or(SceneChangeError::NoSceneTree)?;\n"
+    ));
+    assert!(result.contains(
+        "        self.base().get_tree()\n            .and_then(|st| st.get_current_scene())"
+    ));
+
+    // and a bare user class is named by its path in `custom_class_paths`
+    let result = format_scenes_to_actions(
+        &scenes_and_paths,
+        "SceneManager",
+        &BTreeMap::from([(
+            "SceneManager".to_string(),
+            "crate::SceneManager".to_string(),
+        )]),
+        false,
+        false,
+    );
+
+    assert!(result.contains("impl SceneActions for crate::SceneManager {\n"));
+}
+#[test]
+fn test_format_scenes_to_actions_current_scene_predicates() {
+    let scenes_and_paths = vec![("Main".to_string(), "res://scenes/Main.tscn".to_string())];
+
+    let result = format_scenes_to_actions(&scenes_and_paths, "Node", &BTreeMap::new(), true, false);
+
+    assert!(result.contains(
+        "    fn change_scene_to_main(&self) -> Result<(), SceneChangeError>;\n    /// Whether the current scene of the tree was loaded from `scene_path`.\n    fn is_current_scene(&self, scene_path: &str) -> bool;\n    /// `res://scenes/Main.tscn`\n    fn is_current_scene_main(&self) -> bool;\n}"
+    ));
+    assert!(result.contains(
+        "    fn is_current_scene(&self, scene_path: &str) -> bool {\n        self.get_tree()\n            .and_then(|st| st.get_current_scene())\n            .is_some_and(|scene| scene.get_scene_file_path() == GString::from(scene_path))\n    }\n\n    fn is_current_scene_main(&self) -> bool { self.is_current_scene(\"res://scenes/Main.tscn\") }\n}"
+    ));
+
+    let result = format_scenes_to_actions(
+        &scenes_and_paths,
+        "SceneTree",
+        &BTreeMap::new(),
+        true,
+        false,
+    );
+
+    assert!(result.contains(
+        "        Some(self.clone())\n            .and_then(|st| st.get_current_scene())"
+    ));
+    assert!(!result.contains("get_tree()"));
+}
+#[test]
+fn test_format_scenes_to_actions_ok_is_success() {
+    let result = format_scenes_to_actions(&[], "Node", &BTreeMap::new(), false, false);
+
+    // Godot reports a successful change with `Error::OK`, which must not be surfaced as an error
+    assert!(result.contains("Error::OK => Ok(()),"));
+    assert!(result.contains("err => Err(SceneChangeError::Godot(err)),"));
+    assert!(!result.contains("Some("));
+}
 
 fn format_scene_to_action_trait(scene_name: &str, scene_path: &str) -> String {
     format!(
-        "    {}\n    fn change_scene_to_{}(&self) -> Option<Error>;",
+        "    {}\n    fn change_scene_to_{}(&self) -> Result<(), SceneChangeError>;",
         format_scene_to_doc_comment(scene_path),
         pascal_to_snake_case(scene_name)
     )
@@ -269,17 +1426,17 @@ fn format_scene_to_action_trait(scene_name: &str, scene_path: &str) -> String {
 fn test_format_scene_to_action_trait() {
     assert_eq!(
         format_scene_to_action_trait("Main", "res://scenes/Main.tscn"),
-        "    /// `res://scenes/Main.tscn`\n    fn change_scene_to_main(&self) -> Option<Error>;"
+        "    /// `res://scenes/Main.tscn`\n    fn change_scene_to_main(&self) -> Result<(), SceneChangeError>;"
     );
     assert_eq!(
         format_scene_to_action_trait("LevelOne", "res://scenes/LevelOne.tscn"),
-        "    /// `res://scenes/LevelOne.tscn`\n    fn change_scene_to_level_one(&self) -> Option<Error>;"
+        "    /// `res://scenes/LevelOne.tscn`\n    fn change_scene_to_level_one(&self) -> Result<(), SceneChangeError>;"
     );
 }
 
 fn format_scene_to_action_impl(scene_name: &str, scene_path: &str) -> String {
     format!(
-        "    fn change_scene_to_{}(&self) -> Option<Error> {{ self.change_scene_to(\"{}\") }}",
+        "    fn change_scene_to_{}(&self) -> Result<(), SceneChangeError> {{ self.change_scene_to(\"{}\") }}",
         pascal_to_snake_case(scene_name),
         scene_path
     )
@@ -288,14 +1445,468 @@ fn format_scene_to_action_impl(scene_name: &str, scene_path: &str) -> String {
 fn test_format_scene_to_action_impl() {
     assert_eq!(
         format_scene_to_action_impl("Main", "res://scenes/Main.tscn"),
-        "    fn change_scene_to_main(&self) -> Option<Error> { self.change_scene_to(\"res://scenes/Main.tscn\") }"
+        "    fn change_scene_to_main(&self) -> Result<(), SceneChangeError> { self.change_scene_to(\"res://scenes/Main.tscn\") }"
     );
     assert_eq!(
         format_scene_to_action_impl("LevelOne", "res://scenes/LevelOne.tscn"),
-        "    fn change_scene_to_level_one(&self) -> Option<Error> { self.change_scene_to(\"res://scenes/LevelOne.tscn\") }"
+        "    fn change_scene_to_level_one(&self) -> Result<(), SceneChangeError> { self.change_scene_to(\"res://scenes/LevelOne.tscn\") }"
+    );
+}
+
+fn format_scene_to_predicate_trait(scene_name: &str, scene_path: &str) -> String {
+    format!(
+        "    {}\n    fn is_current_scene_{}(&self) -> bool;",
+        format_scene_to_doc_comment(scene_path),
+        pascal_to_snake_case(scene_name)
+    )
+}
+
+fn format_scene_to_predicate_impl(scene_name: &str, scene_path: &str) -> String {
+    format!(
+        "    fn is_current_scene_{}(&self) -> bool {{ self.is_current_scene(\"{}\") }}",
+        pascal_to_snake_case(scene_name),
+        scene_path
+    )
+}
+#[test]
+fn test_format_scene_to_predicate() {
+    assert_eq!(
+        format_scene_to_predicate_trait("LevelOne", "res://scenes/LevelOne.tscn"),
+        "    /// `res://scenes/LevelOne.tscn`\n    fn is_current_scene_level_one(&self) -> bool;"
+    );
+    assert_eq!(
+        format_scene_to_predicate_impl("LevelOne", "res://scenes/LevelOne.tscn"),
+        "    fn is_current_scene_level_one(&self) -> bool { self.is_current_scene(\"res://scenes/LevelOne.tscn\") }"
     );
 }
 
 fn format_scene_to_doc_comment(scene_path: &str) -> String {
     format!("/// `{}`", scene_path)
 }
+
+fn format_scenes_to_nodes(
+    scenes: &[(&String, &String, Vec<String>)],
+    strict_lints: bool,
+) -> String {
+    format!(
+        "{}{}",
+        format_lint_allows(strict_lints, false),
+        scenes
+            .iter()
+            .map(|(name, path, nodes)| format_scene_to_nodes_module(name, path, nodes))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+
+fn format_scene_to_nodes_module(scene_name: &str, scene_path: &str, nodes: &[String]) -> String {
+    format!(
+        "{}\npub mod {} {{\n{}}}",
+        format_scene_to_doc_comment(scene_path),
+        to_module_name(scene_name),
+        nodes
+            .iter()
+            .map(|node| format!(
+                "    pub const {}: &'static str = \"{}\";\n",
+                node_path_to_const_name(node),
+                node
+            ))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_scene_to_nodes_module() {
+    assert_eq!(
+        format_scene_to_nodes_module(
+            "Player",
+            "res://scenes/Player.tscn",
+            &["HealthBar".into(), "UI".into(), "UI/Score Label".into()]
+        ),
+        "/// `res://scenes/Player.tscn`\npub mod player {\n    pub const HEALTH_BAR: &'static str = \"HealthBar\";\n    pub const UI: &'static str = \"UI\";\n    pub const UI_SCORE_LABEL: &'static str = \"UI/Score Label\";\n}"
+    );
+    assert_eq!(
+        format_scene_to_nodes_module("Main", "res://scenes/Main.tscn", &[]),
+        "/// `res://scenes/Main.tscn`\npub mod main {\n}"
+    );
+}
+
+/// Converts a node path relative to the scene root into a const name.
+///
+/// e.g. `"UI/HealthBar"` -> `"UI_HEALTH_BAR"`
+fn node_path_to_const_name(node_path: &str) -> String {
+    prefix_leading_digit(
+        node_path
+            .split('/')
+            .map(|segment| {
+                pascal_to_snake_case_acronyms(
+                    &segment.replace(|c: char| !c.is_ascii_alphanumeric(), ""),
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("_")
+            .to_uppercase(),
+    )
+}
+#[test]
+fn test_node_path_to_const_name() {
+    assert_eq!(node_path_to_const_name("2DSprite"), "_2_D_SPRITE");
+    assert_eq!(node_path_to_const_name("HealthBar"), "HEALTH_BAR");
+    assert_eq!(node_path_to_const_name("UI/HealthBar"), "UI_HEALTH_BAR");
+    assert_eq!(
+        node_path_to_const_name("Hud/Score Label"),
+        "HUD_SCORE_LABEL"
+    );
+}
+
+fn format_scenes_to_dependencies(
+    scenes: &[(&String, &String, Vec<&str>)],
+    strict_lints: bool,
+) -> String {
+    format!(
+        "{}{}",
+        format_lint_allows(strict_lints, false),
+        scenes
+            .iter()
+            .map(|(name, path, dependencies)| {
+                format_scene_to_dependencies_module(name, path, dependencies)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+
+fn format_scene_to_dependencies_module(
+    scene_name: &str,
+    scene_path: &str,
+    dependencies: &[&str],
+) -> String {
+    format!(
+        "{}\npub mod {} {{\n    pub const DEPENDENCIES: &[&'static str] = &[\n{}    ];\n}}",
+        format_scene_to_doc_comment(scene_path),
+        to_module_name(scene_name),
+        dependencies
+            .iter()
+            .map(|dependency| format!("        \"{}\",\n", dependency))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_scene_to_dependencies_module() {
+    assert_eq!(
+        format_scene_to_dependencies_module(
+            "Player",
+            "res://scenes/Player.tscn",
+            &["res://scenes/Enemy.tscn", "res://scripts/player.gd"]
+        ),
+        "/// `res://scenes/Player.tscn`\npub mod player {\n    pub const DEPENDENCIES: &[&'static str] = &[\n        \"res://scenes/Enemy.tscn\",\n        \"res://scripts/player.gd\",\n    ];\n}"
+    );
+}
+
+fn format_scenes_to_node_accessors(
+    scenes: &[(&String, &String, &Tscn)],
+    custom_class_paths: &BTreeMap<String, String>,
+    strict_lints: bool,
+) -> String {
+    format!(
+        "{}{}",
+        format_lint_allows(strict_lints, false),
+        scenes
+            .iter()
+            .filter_map(|(name, path, tscn)| format_scene_to_node_accessors_module(
+                name,
+                path,
+                tscn,
+                custom_class_paths
+            ))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+
+/// Formats a module with an extension trait on `Gd<T>` for the type `T` of the scene root, with an accessor for each node in the scene.
+///
+/// Nodes are fetched with `try_get_node_as`, so each accessor returns `None` if the node is missing or of another type.
+/// Nodes without a type, e.g. instanced scenes, are accessed as `Node`, and scenes whose root has no type are skipped.
+/// User classes are named by their path in `custom_class_paths`, and as `Gd<T>` of a user class has no `Node` methods,
+/// a user class root is upcast to fetch its nodes.
+fn format_scene_to_node_accessors_module(
+    scene_name: &str,
+    scene_path: &str,
+    tscn: &Tscn,
+    custom_class_paths: &BTreeMap<String, String>,
+) -> Option<String> {
+    let root_type = resolve_class_type(tscn.root()?.node_type?, custom_class_paths, scene_path);
+
+    let mut accessors: Vec<(String, String, String)> = vec![];
+    for node in &tscn.nodes {
+        let Some(node_path) = node.path() else {
+            continue;
+        };
+        // the `get_` prefix keeps the name a valid identifier, even for keywords or a leading digit
+        let method = format!(
+            "get_{}",
+            node_path_to_const_name(&node_path)
+                .trim_start_matches('_')
+                .to_lowercase()
+        );
+        if accessors.iter().any(|(m, _, _)| *m == method) {
+            println!(
+                "cargo::warning=Node {} in {} has the same accessor {} as another node, skipping",
+                node_path, scene_path, method
+            );
+            continue;
+        }
+        let node_type = node.node_type.map_or_else(
+            || "Node".to_string(),
+            |class| resolve_class_type(class, custom_class_paths, scene_path),
+        );
+        accessors.push((method, node_path, node_type));
+    }
+
+    let (get_node, root_import) = if is_engine_class(&root_type) {
+        ("self", root_type.as_str())
+    } else {
+        ("self.upcast_ref::<Node>()", "Node")
+    };
+    // user classes are named by their path rather than imported
+    let mut types = accessors
+        .iter()
+        .map(|(_, _, node_type)| node_type.as_str())
+        .filter(|node_type| is_engine_class(node_type))
+        .chain([root_import])
+        .collect::<Vec<&str>>();
+    types.sort();
+    types.dedup();
+    let trait_name = prefix_leading_digit(format!("{}Nodes", to_upper_camel_case(scene_name)));
+
+    Some(format!(
+        "{}\npub mod {} {{\n    use godot::classes::{};\n    use godot::obj::Gd;\n\n    pub trait {} {{\n{}    }}\n\n    impl {} for Gd<{}> {{\n{}    }}\n}}",
+        format_scene_to_doc_comment(scene_path),
+        to_module_name(scene_name),
+        match types.as_slice() {
+            [node_type] => node_type.to_string(),
+            _ => format!("{{{}}}", types.join(", ")),
+        },
+        trait_name,
+        accessors
+            .iter()
+            .map(|(method, node_path, node_type)| format!(
+                "        /// `{}`\n        fn {}(&self) -> Option<Gd<{}>>;\n",
+                node_path, method, node_type
+            ))
+            .collect::<String>(),
+        trait_name,
+        root_type,
+        accessors
+            .iter()
+            .map(|(method, node_path, node_type)| format!(
+                "        fn {}(&self) -> Option<Gd<{}>> {{ {}.try_get_node_as::<{}>(\"{}\") }}\n",
+                method, node_type, get_node, node_type, node_path
+            ))
+            .collect::<String>()
+    ))
+}
+#[test]
+fn test_format_scene_to_node_accessors_module() {
+    let tscn = Tscn::parse_from_str(
+        r#"[gd_scene format=3]
+
+[node name="Hud" type="Control"]
+
+[node name="HealthBar" type="ProgressBar" parent="."]
+
+[node name="Score Label" type="Label" parent="HealthBar"]
+"#,
+    );
+
+    assert_eq!(
+        format_scene_to_node_accessors_module("Hud", "res://ui/Hud.tscn", &tscn, &BTreeMap::new())
+            .unwrap(),
+        r#"/// `res://ui/Hud.tscn`
+pub mod hud {
+    use godot::classes::{Control, Label, ProgressBar};
+    use godot::obj::Gd;
+
+    pub trait HudNodes {
+        /// `HealthBar`
+        fn get_health_bar(&self) -> Option<Gd<ProgressBar>>;
+        /// `HealthBar/Score Label`
+        fn get_health_bar_score_label(&self) -> Option<Gd<Label>>;
+    }
+
+    impl HudNodes for Gd<Control> {
+        fn get_health_bar(&self) -> Option<Gd<ProgressBar>> { self.try_get_node_as::<ProgressBar>("HealthBar") }
+        fn get_health_bar_score_label(&self) -> Option<Gd<Label>> { self.try_get_node_as::<Label>("HealthBar/Score Label") }
+    }
+}"#
+    );
+
+    let instanced = Tscn::parse_from_str(
+        "[node name=\"Level\" instance=ExtResource(\"1_abc\")]\n\n[node name=\"Spawn\" parent=\".\"]\n",
+    );
+    assert_eq!(
+        format_scene_to_node_accessors_module(
+            "Level",
+            "res://Level.tscn",
+            &instanced,
+            &BTreeMap::new()
+        ),
+        None
+    );
+}
+#[test]
+fn test_format_scene_to_node_accessors_module_custom_classes() {
+    let tscn = Tscn::parse_from_str(
+        r#"[gd_scene format=3]
+
+[node name="Player" type="Player"]
+
+[node name="2D Sprite" type="Sprite2D" parent="."]
+
+[node name="Inventory" type="Inventory" parent="."]
+
+[node name="Brain" type="EnemyBrain" parent="."]
+"#,
+    );
+    let custom_class_paths = BTreeMap::from([
+        ("Player".to_string(), "crate::Player".to_string()),
+        (
+            "Inventory".to_string(),
+            "crate::items::Inventory".to_string(),
+        ),
+    ]);
+
+    assert_eq!(
+        format_scene_to_node_accessors_module(
+            "Player",
+            "res://Player.tscn",
+            &tscn,
+            &custom_class_paths
+        )
+        .unwrap(),
+        r#"/// `res://Player.tscn`
+pub mod player {
+    use godot::classes::{Node, Sprite2D};
+    use godot::obj::Gd;
+
+    pub trait PlayerNodes {
+        /// `2D Sprite`
+        fn get_2_d_sprite(&self) -> Option<Gd<Sprite2D>>;
+        /// `Inventory`
+        fn get_inventory(&self) -> Option<Gd<crate::items::Inventory>>;
+        /// `Brain`
+        fn get_brain(&self) -> Option<Gd<Node>>;
+    }
+
+    impl PlayerNodes for Gd<crate::Player> {
+        fn get_2_d_sprite(&self) -> Option<Gd<Sprite2D>> { self.upcast_ref::<Node>().try_get_node_as::<Sprite2D>("2D Sprite") }
+        fn get_inventory(&self) -> Option<Gd<crate::items::Inventory>> { self.upcast_ref::<Node>().try_get_node_as::<crate::items::Inventory>("Inventory") }
+        fn get_brain(&self) -> Option<Gd<Node>> { self.upcast_ref::<Node>().try_get_node_as::<Node>("Brain") }
+    }
+}"#
+    );
+}
+
+/// Formats an `instantiate_<scene>()` function for each scene, loading the scene and instantiating it as the type of its root,
+/// or `Node` for scenes whose root has no type, e.g. inherited scenes. User classes are named by their path rather than imported.
+///
+/// Each function logs a Godot error and returns `None` if the scene can't be loaded or instantiated, or its root is of another type.
+fn format_scenes_to_spawners(scenes: &[(&String, &String, String)], strict_lints: bool) -> String {
+    let mut types = scenes
+        .iter()
+        .map(|(_, _, root_type)| root_type.as_str())
+        .filter(|root_type| is_engine_class(root_type))
+        .chain(["Node", "PackedScene"])
+        .collect::<Vec<&str>>();
+    types.sort();
+    types.dedup();
+
+    format!(
+        r#"{}use godot::classes::{{{}}};
+use godot::global::godot_error;
+use godot::obj::{{Gd, Inherits}};
+use godot::tools::try_load;
+
+/// Loads and instantiates the scene at `scene_path` as a `T`, logging a Godot error and returning `None` if it can't be
+/// loaded or instantiated, or its root is not a `T`.
+pub fn instantiate<T: Inherits<Node>>(scene_path: &str) -> Option<Gd<T>> {{
+    let scene = match try_load::<PackedScene>(scene_path) {{
+        Ok(scene) => scene,
+        Err(e) => {{
+            godot_error!("Failed to load scene {{}}: {{}}", scene_path, e);
+            return None;
+        }}
+    }};
+
+    let Some(node) = scene.instantiate() else {{
+        godot_error!("Failed to instantiate scene {{}}", scene_path);
+        return None;
+    }};
+
+    match node.try_cast::<T>() {{
+        Ok(node) => Some(node),
+        Err(node) => {{
+            godot_error!("The root of scene {{}} is not of the expected type", scene_path);
+            node.free();
+            None
+        }}
+    }}
+}}
+{}"#,
+        format_lint_allows(strict_lints, false),
+        types.join(", "),
+        scenes
+            .iter()
+            .map(|(name, path, root_type)| format!(
+                "\n{}\npub fn instantiate_{}() -> Option<Gd<{}>> {{\n    instantiate(\"{}\")\n}}\n",
+                format_scene_to_doc_comment(path),
+                pascal_to_snake_case(name),
+                root_type,
+                path
+            ))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_scenes_to_spawners() {
+    let (player, player_path) = ("Player".to_string(), "res://scenes/Player.tscn".to_string());
+    let (level, level_path) = (
+        "LevelOne".to_string(),
+        "res://scenes/LevelOne.tscn".to_string(),
+    );
+    let (enemy, enemy_path) = ("Enemy".to_string(), "res://scenes/Enemy.tscn".to_string());
+
+    let result = format_scenes_to_spawners(
+        &[
+            (&player, &player_path, "CharacterBody2D".to_string()),
+            (&level, &level_path, "Node".to_string()),
+            (&enemy, &enemy_path, "crate::Enemy".to_string()),
+        ],
+        false,
+    );
+
+    assert!(result.starts_with(
+        "#![allow(dead_code)]\nuse godot::classes::{CharacterBody2D, Node, PackedScene};\n"
+    ));
+    assert!(result.contains(
+        "    let scene = match try_load::<PackedScene>(scene_path) {\n        Ok(scene) => scene,\n        Err(e) => {\n            godot_error!(\"Failed to load scene {}: {}\", scene_path, e);\n            return None;\n        }\n    };\n"
+    ));
+    assert!(result.ends_with(
+        r#"
+/// `res://scenes/Player.tscn`
+pub fn instantiate_player() -> Option<Gd<CharacterBody2D>> {
+    instantiate("res://scenes/Player.tscn")
+}
+
+/// `res://scenes/LevelOne.tscn`
+pub fn instantiate_level_one() -> Option<Gd<Node>> {
+    instantiate("res://scenes/LevelOne.tscn")
+}
+
+/// `res://scenes/Enemy.tscn`
+pub fn instantiate_enemy() -> Option<Gd<crate::Enemy>> {
+    instantiate("res://scenes/Enemy.tscn")
+}
+"#
+    ));
+}
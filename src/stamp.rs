@@ -0,0 +1,147 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const STAMP_FILE: &str = ".zgrcg_stamp";
+const WRITTEN_PREFIX: &str = "written=";
+
+/// The hash of the inputs after the last run, with the files it wrote and the cargo lines it printed, kept in the output
+/// directory by `skip_unchanged` so an unchanged run can be skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stamp {
+    pub hash: u64,
+    pub written_paths: Vec<PathBuf>,
+    /// e.g. `cargo:rerun-if-changed=./project.godot`, printed again when a run is skipped, as cargo forgets them otherwise.
+    pub cargo_lines: Vec<String>,
+}
+
+impl Stamp {
+    /// Whether the stamp is for `hash`, and every file written by the last run is still there.
+    pub fn is_current(&self, hash: u64) -> bool {
+        self.hash == hash && self.written_paths.iter().all(|path| path.exists())
+    }
+}
+
+/// Hashes the configuration along with the path and content of each input file, and the version of the generator.
+///
+/// A missing file hashes as empty, so e.g. creating an `override.cfg` changes the hash. The hash is FNV-1a over the bytes
+/// of each, rather than `DefaultHasher`, whose output may change between Rust releases and would invalidate every stamp.
+pub fn hash_inputs(config: &str, input_files: &[PathBuf]) -> u64 {
+    let mut hasher = Fnv1a::new();
+    hasher.write_field(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.write_field(config.as_bytes());
+
+    for path in input_files {
+        hasher.write_field(path.to_string_lossy().as_bytes());
+        hasher.write_field(&fs::read(path).unwrap_or_default());
+    }
+
+    hasher.0
+}
+#[test]
+fn test_hash_inputs() {
+    let dir = crate::utils::TestDir::new("hash_inputs");
+    let file = dir.join("project.godot");
+    fs::write(&file, "config_version=5\n").unwrap();
+
+    let files = vec![file.clone()];
+    let hash = hash_inputs("config", &files);
+    assert_eq!(hash_inputs("config", &files), hash);
+    assert_ne!(hash_inputs("other config", &files), hash);
+
+    fs::write(&file, "config_version=4\n").unwrap();
+    assert_ne!(hash_inputs("config", &files), hash);
+}
+
+/// A 64-bit FNV-1a hasher, which hashes the same bytes to the same value on every platform and Rust release.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Writes the length of `bytes` before them, so e.g. `("ab", "c")` and `("a", "bc")` hash differently.
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+}
+#[test]
+fn test_fnv1a() {
+    let hash = |bytes: &[u8]| {
+        let mut hasher = Fnv1a::new();
+        hasher.write(bytes);
+        hasher.0
+    };
+    // the published FNV-1a test vectors
+    assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+}
+
+/// Reads the stamp from the output directory, if there is one and it can be parsed.
+pub fn read_stamp(output_dir: &str) -> Option<Stamp> {
+    let content = fs::read_to_string(Path::new(output_dir).join(STAMP_FILE)).ok()?;
+    let mut lines = content.lines();
+    let hash = u64::from_str_radix(lines.next()?, 16).ok()?;
+
+    let mut stamp = Stamp {
+        hash,
+        written_paths: vec![],
+        cargo_lines: vec![],
+    };
+    for line in lines {
+        match line.strip_prefix(WRITTEN_PREFIX) {
+            Some(path) => stamp.written_paths.push(PathBuf::from(path)),
+            None => stamp.cargo_lines.push(line.to_string()),
+        }
+    }
+
+    Some(stamp)
+}
+
+/// Writes the stamp to the output directory, replacing any previous stamp.
+pub fn write_stamp(output_dir: &str, stamp: &Stamp) -> io::Result<()> {
+    let mut content = format!("{:016x}\n", stamp.hash);
+    for path in &stamp.written_paths {
+        content.push_str(&format!("{}{}\n", WRITTEN_PREFIX, path.display()));
+    }
+    for line in &stamp.cargo_lines {
+        content.push_str(&format!("{}\n", line));
+    }
+
+    fs::write(Path::new(output_dir).join(STAMP_FILE), content)
+}
+#[test]
+fn test_read_write_stamp() {
+    let dir = crate::utils::TestDir::new("read_write_stamp");
+    let output_dir = dir.to_str().unwrap();
+    assert_eq!(read_stamp(output_dir), None);
+
+    let written = dir.join("layer_consts.rs");
+    fs::write(&written, "").unwrap();
+    let stamp = Stamp {
+        hash: 0xabc,
+        written_paths: vec![written.clone()],
+        cargo_lines: vec!["cargo:rerun-if-changed=./project.godot".to_string()],
+    };
+    write_stamp(output_dir, &stamp).unwrap();
+
+    let read = read_stamp(output_dir).unwrap();
+    assert_eq!(read, stamp);
+    assert!(read.is_current(0xabc));
+    assert!(!read.is_current(0xabd));
+
+    // a deleted output means the run can't be skipped
+    fs::remove_file(&written).unwrap();
+    assert!(!read.is_current(0xabc));
+}
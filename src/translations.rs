@@ -0,0 +1,301 @@
+use std::{fs, io, path::Path};
+
+use crate::{
+    mod_file::GeneratedModule,
+    projectgodot::ProjectGodot,
+    utils::{format_lint_allows, to_const_name, write_generated_file},
+};
+
+const MOD_TR_KEYS: &str = "tr_keys";
+
+/// Generates a const for each translation key in the translations listed in the `[internationalization]` section of `project.godot`.
+///
+/// Keys are read from the `.csv` and `.po` sources of the translations, relative to `resource_path`.
+pub fn generate_translation_keys(
+    output_dir: &str,
+    resource_path: &str,
+    godot_project: &ProjectGodot,
+    strict_lints: bool,
+) -> io::Result<Vec<GeneratedModule>> {
+    let Some(internationalization) = godot_project
+        .internationalization
+        .as_ref()
+        .filter(|i| !i.translations.is_empty())
+    else {
+        println!(
+            "cargo::warning=No translations found in the project settings, skipping tr_keys.rs generation"
+        );
+        return Ok(vec![]);
+    };
+
+    let mut sources = internationalization
+        .translations
+        .iter()
+        .map(|translation| translation_source_path(translation))
+        .collect::<Vec<String>>();
+    sources.sort();
+    sources.dedup();
+
+    let mut keys = sources
+        .iter()
+        .flat_map(|source| {
+            let file_path = Path::new(resource_path).join(source.trim_start_matches("res://"));
+
+            match fs::read_to_string(&file_path) {
+                Ok(content) if source.ends_with(".po") => parse_po_keys(&content),
+                Ok(content) => parse_csv_keys(&content),
+                Err(e) => {
+                    println!(
+                        "cargo::warning=Failed to read translation {}: {}, skipping",
+                        file_path.display(),
+                        e
+                    );
+                    vec![]
+                }
+            }
+        })
+        .collect::<Vec<String>>();
+    keys.sort();
+    keys.dedup();
+
+    let mut const_names: Vec<String> = vec![];
+    let mut consts: Vec<(String, String)> = vec![];
+    for key in keys {
+        let const_name = to_const_name(&key);
+        if const_names.contains(&const_name) {
+            println!(
+                "cargo::warning=Translation key '{}' has the same const name {} as another key, skipping",
+                key, const_name
+            );
+            continue;
+        }
+        const_names.push(const_name.clone());
+        consts.push((const_name, key));
+    }
+
+    let tr_keys_path = Path::new(output_dir).join(format!("{}.rs", MOD_TR_KEYS));
+
+    write_generated_file(&tr_keys_path, &format_keys_to_consts(&consts, strict_lints))?;
+
+    Ok(vec![GeneratedModule {
+        name: MOD_TR_KEYS.to_string(),
+        path: tr_keys_path,
+    }])
+}
+#[test]
+fn test_generate_translation_keys() {
+    let dir = crate::utils::TestDir::new("generate_translation_keys");
+    fs::create_dir_all(dir.join("godot").join("locale")).unwrap();
+    fs::write(
+        dir.join("godot").join("locale").join("menu.csv"),
+        "keys,en,fr\nMENU_START,Start,Commencer\nMENU_QUIT,Quit,Quitter\n",
+    )
+    .unwrap();
+
+    let project = ProjectGodot::parse_from_str(
+        r#"[internationalization]
+
+locale/translations=PackedStringArray("res://locale/menu.en.translation", "res://locale/menu.fr.translation", "res://locale/missing.po")
+"#,
+    );
+
+    let output_dir = dir.join("generated");
+    let modules = generate_translation_keys(
+        output_dir.to_str().unwrap(),
+        dir.join("godot").to_str().unwrap(),
+        &project,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        modules,
+        vec![GeneratedModule {
+            name: MOD_TR_KEYS.to_string(),
+            path: output_dir.join("tr_keys.rs"),
+        }]
+    );
+    assert_eq!(
+        fs::read_to_string(output_dir.join("tr_keys.rs")).unwrap(),
+        crate::utils::with_generated_header(
+            "#![allow(dead_code)]\n\npub const MENU_QUIT: &str = \"MENU_QUIT\";\npub const MENU_START: &str = \"MENU_START\";\n"
+        )
+    );
+}
+
+/// Returns the path of the source a translation is imported from.
+///
+/// Godot imports each locale column of a `.csv` into its own `.translation` file, e.g. `res://locale/menu.en.translation` is imported
+/// from `res://locale/menu.csv`. Other translations, e.g. `.po` files, are their own source.
+fn translation_source_path(translation: &str) -> String {
+    match translation.strip_suffix(".translation") {
+        Some(stem) => {
+            // strip the locale, e.g. `menu.en` -> `menu`
+            let stem = match stem.rsplit_once('.') {
+                Some((name, locale)) if !locale.contains('/') && !name.ends_with('/') => name,
+                _ => stem,
+            };
+            format!("{}.csv", stem)
+        }
+        None => translation.to_string(),
+    }
+}
+#[test]
+fn test_translation_source_path() {
+    assert_eq!(
+        translation_source_path("res://locale/menu.en.translation"),
+        "res://locale/menu.csv"
+    );
+    assert_eq!(
+        translation_source_path("res://locale/menu.translation"),
+        "res://locale/menu.csv"
+    );
+    assert_eq!(
+        translation_source_path("res://locale/fr.po"),
+        "res://locale/fr.po"
+    );
+}
+
+/// Parses the keys from the first column of a translation `.csv`, skipping the header row.
+///
+/// Quoted fields may contain commas, escaped `""` quotes, and line breaks.
+fn parse_csv_keys(content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut key = String::new();
+    let mut in_quotes = false;
+    let mut in_first_field = true;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                if in_first_field {
+                    key.push('"');
+                }
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => in_first_field = false,
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                keys.push(std::mem::take(&mut key));
+                in_first_field = true;
+            }
+            c if in_first_field => key.push(c),
+            _ => {}
+        }
+    }
+    if !key.is_empty() {
+        keys.push(key);
+    }
+
+    keys.into_iter()
+        .skip(1)
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+        .collect()
+}
+#[test]
+fn test_parse_csv_keys() {
+    let content = "keys,en,fr\r\nMENU_START,Start,Commencer\r\n\"MENU_QUIT\",\"Quit, now\",\"Quitter\nmaintenant\"\n\n\"SAY_\"\"HI\"\"\",Hi,Salut";
+
+    assert_eq!(
+        parse_csv_keys(content),
+        vec!["MENU_START", "MENU_QUIT", "SAY_\"HI\""]
+    );
+    assert_eq!(parse_csv_keys("keys,en\n"), Vec::<String>::new());
+}
+
+/// Parses the `msgid` of each message in a `.po` file, skipping the header entry with an empty `msgid`.
+///
+/// Multi-line ids are joined, e.g. `msgid ""` followed by `"Hello "` and `"world"` is `Hello world`.
+fn parse_po_keys(content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(id) = line.strip_prefix("msgid ") {
+            if let Some(key) = current.take() {
+                keys.push(key);
+            }
+            current = Some(unquote_po_string(id));
+        } else if line.starts_with('"')
+            && let Some(key) = current.as_mut()
+        {
+            key.push_str(&unquote_po_string(line));
+        } else if let Some(key) = current.take() {
+            keys.push(key);
+        }
+    }
+    if let Some(key) = current {
+        keys.push(key);
+    }
+
+    keys.into_iter().filter(|key| !key.is_empty()).collect()
+}
+#[test]
+fn test_parse_po_keys() {
+    let content = r#"msgid ""
+msgstr ""
+"Language: fr\n"
+
+#: main.tscn
+msgid "Start"
+msgstr "Commencer"
+
+msgid ""
+"Press \"A\" "
+"to jump"
+msgstr "Appuyez sur A pour sauter"
+"#;
+
+    assert_eq!(parse_po_keys(content), vec!["Start", "Press \"A\" to jump"]);
+}
+
+/// Strips the surrounding quotes from a `.po` string and unescapes it.
+fn unquote_po_string(s: &str) -> String {
+    let s = s.trim();
+    let s = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s);
+
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Formats `(const name, key)` pairs into the content of the translation keys file.
+fn format_keys_to_consts(consts: &[(String, String)], strict_lints: bool) -> String {
+    format!(
+        "{}\n{}",
+        format_lint_allows(strict_lints, false),
+        consts
+            .iter()
+            .map(|(const_name, key)| format!("pub const {}: &str = {:?};\n", const_name, key))
+            .collect::<String>()
+    )
+}
+#[test]
+fn test_format_keys_to_consts() {
+    assert_eq!(
+        format_keys_to_consts(
+            &[("MENU_START".to_string(), "MENU_START".to_string())],
+            false
+        ),
+        "#![allow(dead_code)]\n\npub const MENU_START: &str = \"MENU_START\";\n"
+    );
+}
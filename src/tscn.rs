@@ -0,0 +1,265 @@
+// Allow dead code because to better represent the structure of the file, even if some fields are not used.
+#![allow(dead_code)]
+
+/// Parsed representation of a `.tscn` scene file
+///
+/// Only the headers of the file are parsed, e.g:
+/// ```text
+/// [gd_scene format=3 uid="uid://c3385nybf0m1"]
+///
+/// [node name="Player" type="CharacterBody2D"]
+///
+/// [node name="HealthBar" type="ProgressBar" parent="."]
+/// ```
+pub struct Tscn<'a> {
+    /// The `uid` of the scene from its `[gd_scene]` header, which Godot 4 uses to find the scene if it is moved.
+    pub uid: Option<&'a str>,
+    pub ext_resources: Vec<TscnExtResource<'a>>,
+    pub nodes: Vec<TscnNode<'a>>,
+}
+
+impl Tscn<'_> {
+    pub fn parse_from_str<'a>(content: &'a str) -> Tscn<'a> {
+        let mut uid = None;
+        let mut ext_resources = Vec::new();
+        let mut nodes = Vec::new();
+
+        for line in content.lines() {
+            match parse_header(line) {
+                Some(("gd_scene", attributes)) => {
+                    uid = attributes
+                        .iter()
+                        .find(|(key, _)| *key == "uid")
+                        .map(|(_, value)| *value);
+                }
+                Some(("ext_resource", attributes)) => {
+                    if let Some(resource) = TscnExtResource::from_attributes(&attributes) {
+                        ext_resources.push(resource);
+                    }
+                }
+                Some(("node", attributes)) => {
+                    if let Some(node) = TscnNode::from_attributes(&attributes) {
+                        nodes.push(node);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Tscn {
+            uid,
+            ext_resources,
+            nodes,
+        }
+    }
+
+    /// The root node of the scene, which is the only node without a parent.
+    pub fn root(&self) -> Option<&TscnNode<'_>> {
+        self.nodes.iter().find(|n| n.parent.is_none())
+    }
+
+    /// The paths of all external resources the scene depends on, sorted and deduplicated.
+    pub fn dependencies(&self) -> Vec<&str> {
+        let mut paths = self
+            .ext_resources
+            .iter()
+            .map(|r| r.path)
+            .collect::<Vec<&str>>();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+}
+
+#[test]
+fn test_tscn_parse_from_str() {
+    let content = r#"[gd_scene load_steps=2 format=3 uid="uid://c3385nybf0m1"]
+
+[ext_resource type="PackedScene" uid="uid://b1" path="res://scenes/Enemy.tscn" id="1_abc"]
+
+[node name="Player" type="CharacterBody2D"]
+position = Vector2(10, 20)
+
+[node name="HealthBar" type="ProgressBar" parent="."]
+
+[node name="UI" type="Control" parent="."]
+
+[node name="Score Label" type="Label" parent="UI"]
+text = "[node name=\"Fake\"]"
+
+[node name="Enemy" parent="." instance=ExtResource("1_abc")]
+"#;
+
+    let tscn = Tscn::parse_from_str(content);
+    assert_eq!(tscn.uid, Some("uid://c3385nybf0m1"));
+    assert_eq!(tscn.nodes.len(), 5);
+
+    let root = tscn.root().unwrap();
+    assert_eq!(root.name, "Player");
+    assert_eq!(root.node_type, Some("CharacterBody2D"));
+    assert_eq!(root.path(), None);
+
+    let paths = tscn
+        .nodes
+        .iter()
+        .filter_map(|n| n.path())
+        .collect::<Vec<String>>();
+    assert_eq!(paths, vec!["HealthBar", "UI", "UI/Score Label", "Enemy"]);
+
+    assert_eq!(tscn.nodes[4].node_type, None);
+}
+
+#[test]
+fn test_tscn_ext_resources() {
+    let content = r#"[gd_scene load_steps=3 format=3 uid="uid://c3385nybf0m1"]
+
+[ext_resource type="Script" path="res://scripts/player.gd" id="2_def"]
+[ext_resource type="PackedScene" uid="uid://b1" path="res://scenes/Enemy.tscn" id="1_abc"]
+[ext_resource type="PackedScene" path="res://scenes/Enemy.tscn" id="3_ghi"]
+
+[node name="Player" type="Node2D"]
+"#;
+
+    let tscn = Tscn::parse_from_str(content);
+    assert_eq!(tscn.ext_resources.len(), 3);
+
+    let enemy = &tscn.ext_resources[1];
+    assert_eq!(enemy.resource_type, Some("PackedScene"));
+    assert_eq!(enemy.path, "res://scenes/Enemy.tscn");
+    assert_eq!(enemy.id, Some("1_abc"));
+    assert_eq!(enemy.uid, Some("uid://b1"));
+
+    assert_eq!(
+        tscn.dependencies(),
+        vec!["res://scenes/Enemy.tscn", "res://scripts/player.gd"]
+    );
+}
+
+/// An `[ext_resource ...]` entry of a `.tscn` file
+pub struct TscnExtResource<'a> {
+    pub resource_type: Option<&'a str>,
+    pub path: &'a str,
+    pub id: Option<&'a str>,
+    pub uid: Option<&'a str>,
+}
+
+impl TscnExtResource<'_> {
+    fn from_attributes<'a>(attributes: &[(&'a str, &'a str)]) -> Option<TscnExtResource<'a>> {
+        let get = |key: &str| attributes.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+
+        Some(TscnExtResource {
+            resource_type: get("type"),
+            path: get("path")?,
+            id: get("id"),
+            uid: get("uid"),
+        })
+    }
+}
+
+/// A `[node ...]` entry of a `.tscn` file
+pub struct TscnNode<'a> {
+    pub name: &'a str,
+    pub node_type: Option<&'a str>,
+    /// `None` for the scene root, `"."` for direct children of the root, otherwise the path to the parent from the root.
+    pub parent: Option<&'a str>,
+}
+
+impl TscnNode<'_> {
+    fn from_attributes<'a>(attributes: &[(&'a str, &'a str)]) -> Option<TscnNode<'a>> {
+        let get = |key: &str| attributes.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+
+        Some(TscnNode {
+            name: get("name")?,
+            node_type: get("type"),
+            parent: get("parent"),
+        })
+    }
+
+    /// Path to this node relative to the scene root, as used with `get_node`.
+    ///
+    /// Returns `None` for the scene root.
+    pub fn path(&self) -> Option<String> {
+        match self.parent {
+            None => None,
+            Some(".") => Some(self.name.to_string()),
+            Some(parent) => Some(format!("{}/{}", parent, self.name)),
+        }
+    }
+}
+
+/// Parses a `.tscn` header line into its tag and attributes.
+///
+/// e.g. `[node name="HealthBar" parent="."]` -> `("node", [("name", "HealthBar"), ("parent", ".")])`
+///
+/// Quoted values are unquoted, other values (e.g. `ExtResource("1_abc")`) are returned as-is.
+pub fn parse_header(line: &str) -> Option<(&str, Vec<(&str, &str)>)> {
+    let line = line.trim();
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (tag, rest) = inner.split_once(' ').unwrap_or((inner, ""));
+
+    let mut attributes = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut in_parentheses = 0;
+    let mut last_split = 0;
+
+    for (i, c) in rest.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => in_parentheses += 1,
+            ')' if !in_quotes && in_parentheses > 0 => in_parentheses -= 1,
+            ' ' if !in_quotes && in_parentheses == 0 => {
+                push_attribute(&mut attributes, &rest[last_split..i]);
+                last_split = i + 1;
+            }
+            _ => {}
+        }
+    }
+    push_attribute(&mut attributes, &rest[last_split..]);
+
+    Some((tag, attributes))
+}
+
+fn push_attribute<'a>(attributes: &mut Vec<(&'a str, &'a str)>, part: &'a str) {
+    if let Some((key, value)) = part.split_once('=') {
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        attributes.push((key.trim(), value));
+    }
+}
+
+#[test]
+fn test_parse_header() {
+    assert_eq!(
+        parse_header(r#"[node name="HealthBar" type="ProgressBar" parent="."]"#),
+        Some((
+            "node",
+            vec![
+                ("name", "HealthBar"),
+                ("type", "ProgressBar"),
+                ("parent", ".")
+            ]
+        ))
+    );
+    assert_eq!(
+        parse_header(r#"[node name="Two Words" parent="." instance=ExtResource("1_a b")]"#),
+        Some((
+            "node",
+            vec![
+                ("name", "Two Words"),
+                ("parent", "."),
+                ("instance", "ExtResource(\"1_a b\")")
+            ]
+        ))
+    );
+    assert_eq!(
+        parse_header("[gd_scene format=3]"),
+        Some(("gd_scene", vec![("format", "3")]))
+    );
+    assert_eq!(parse_header("text = \"[not a header\""), None);
+}
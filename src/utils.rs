@@ -1,4 +1,221 @@
-use std::fs;
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// Marks the first line of each file written by the generator, so that `clean_orphans` only ever removes its own files.
+pub const GENERATED_MARKER: &str = "// @generated by zgrcg";
+
+/// Prefixes generated file content with the `@generated` marker.
+pub fn with_generated_header(content: &str) -> String {
+    format!("{}, do not edit by hand\n{}", GENERATED_MARKER, content)
+}
+#[test]
+fn test_with_generated_header() {
+    let content = with_generated_header("#![allow(dead_code)]\n");
+    assert_eq!(
+        content,
+        "// @generated by zgrcg, do not edit by hand\n#![allow(dead_code)]\n"
+    );
+    assert!(content.starts_with(GENERATED_MARKER));
+}
+
+/// Formats the inner attributes at the top of a generated file, allowing unused items and, if `non_snake_case`, `UPPER_SNAKE_CASE`
+/// function names.
+///
+/// With `strict_lints` nothing is allowed file-wide, see `format_item_allow`.
+pub fn format_lint_allows(strict_lints: bool, non_snake_case: bool) -> &'static str {
+    match (strict_lints, non_snake_case) {
+        (true, _) => "",
+        (false, true) => "#![allow(dead_code)]\n#![allow(non_snake_case)]\n",
+        (false, false) => "#![allow(dead_code)]\n",
+    }
+}
+#[test]
+fn test_format_lint_allows() {
+    assert_eq!(format_lint_allows(false, false), "#![allow(dead_code)]\n");
+    assert_eq!(
+        format_lint_allows(false, true),
+        "#![allow(dead_code)]\n#![allow(non_snake_case)]\n"
+    );
+    assert_eq!(format_lint_allows(true, true), "");
+}
+
+/// Formats an attribute allowing `lint` on the next generated item with `strict_lints`, for items whose naming requires it, e.g.
+/// `UPPER_SNAKE_CASE` function names.
+pub fn format_item_allow(strict_lints: bool, lint: &str) -> String {
+    if strict_lints {
+        format!("#[allow({})]\n", lint)
+    } else {
+        String::new()
+    }
+}
+#[test]
+fn test_format_item_allow() {
+    assert_eq!(
+        format_item_allow(true, "non_snake_case"),
+        "#[allow(non_snake_case)]\n"
+    );
+    assert_eq!(format_item_allow(false, "non_snake_case"), "");
+}
+
+/// The indentation the formatters generate code with, restyled by `apply_code_style`.
+pub const DEFAULT_INDENT: &str = "    ";
+
+/// The line ending of generated files, named `"lf"` or `"crlf"` in a `zgrcg.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Replaces each level of `DEFAULT_INDENT` at the start of a line with `indent`, and each `\n` with `line_ending`.
+///
+/// Lines continuing a string literal, e.g. in a multi-line raw string, are left as they are, as their indentation is part of
+/// the string.
+pub fn apply_code_style(content: &str, indent: &str, line_ending: LineEnding) -> String {
+    let mut state = LiteralState::Code;
+    content
+        .split('\n')
+        .map(|line| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let styled = if state == LiteralState::Code {
+                let trimmed = line.trim_start_matches(DEFAULT_INDENT);
+                let levels = (line.len() - trimmed.len()) / DEFAULT_INDENT.len();
+                format!("{}{}", indent.repeat(levels), trimmed)
+            } else {
+                line.to_string()
+            };
+            state = state.after_line(line);
+            styled
+        })
+        .collect::<Vec<String>>()
+        .join(line_ending.as_str())
+}
+
+/// Whether the end of a line of Rust is in code, or inside a string literal continuing onto the next line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LiteralState {
+    Code,
+    Str,
+    /// A raw string, closed by a `"` and this many `#`.
+    RawStr(usize),
+}
+
+impl LiteralState {
+    /// Scans `line` from this state, skipping comments and char literals, returning the state at its end.
+    pub fn after_line(self, line: &str) -> LiteralState {
+        let chars = line.chars().collect::<Vec<char>>();
+        let mut state = self;
+        let mut i = 0;
+
+        while i < chars.len() {
+            match (state, chars[i]) {
+                (LiteralState::Code, '/') if chars.get(i + 1) == Some(&'/') => break,
+                (LiteralState::Code, '"') => {
+                    let hashes = chars[..i].iter().rev().take_while(|c| **c == '#').count();
+                    let prefix = &chars[..i - hashes];
+                    let is_raw = match prefix {
+                        ['r'] | ['b', 'r'] => true,
+                        [.., before, 'b', 'r'] | [.., before, 'r'] => {
+                            !before.is_alphanumeric() && *before != '_'
+                        }
+                        _ => false,
+                    };
+                    state = if is_raw {
+                        LiteralState::RawStr(hashes)
+                    } else {
+                        LiteralState::Str
+                    };
+                }
+                (LiteralState::Code, '\'') => {
+                    // skip char literals like '"' or '\'', leaving lifetimes like 'a
+                    if chars.get(i + 1) == Some(&'\\') {
+                        // the escaped char may itself be a quote, so the closing quote is searched for after it
+                        i += chars
+                            .get(i + 3..)
+                            .and_then(|rest| rest.iter().position(|c| *c == '\''))
+                            .map_or(chars.len(), |p| p + 3);
+                    } else if chars.get(i + 2) == Some(&'\'') {
+                        i += 2;
+                    }
+                }
+                (LiteralState::Str, '\\') => i += 1,
+                (LiteralState::Str, '"') => state = LiteralState::Code,
+                (LiteralState::RawStr(hashes), '"')
+                    if chars[i + 1..].iter().take_while(|c| **c == '#').count() >= hashes =>
+                {
+                    state = LiteralState::Code;
+                    i += hashes;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        state
+    }
+}
+#[test]
+fn test_apply_code_style() {
+    let content =
+        "pub mod ui {\n    pub mod menu {\n        pub const X: &str = \"a    b\";\n      }\n}\n";
+
+    assert_eq!(
+        apply_code_style(content, DEFAULT_INDENT, LineEnding::Lf),
+        content
+    );
+    assert_eq!(
+        apply_code_style(content, "\t", LineEnding::CrLf),
+        "pub mod ui {\r\n\tpub mod menu {\r\n\t\tpub const X: &str = \"a    b\";\r\n\t  }\r\n}\r\n"
+    );
+}
+#[test]
+fn test_apply_code_style_string_literals() {
+    let content = concat!(
+        "    /// Says \"hi\n",
+        "    const RAW: &str = r#\"\n",
+        "    kept \"quoted\"\n",
+        "    \"#;\n",
+        "    const STR: &str = \"a \\\" \\\\\n",
+        "    kept\";\n",
+        "    const C: char = '\"';\n",
+        "    const Q: [char; 2] = ['\\'', '\"'];\n",
+        "    fn f<'a>(s: &'a str) {}\n",
+    );
+
+    assert_eq!(
+        apply_code_style(content, "\t", LineEnding::Lf),
+        concat!(
+            "\t/// Says \"hi\n",
+            "\tconst RAW: &str = r#\"\n",
+            "    kept \"quoted\"\n",
+            "    \"#;\n",
+            "\tconst STR: &str = \"a \\\" \\\\\n",
+            "    kept\";\n",
+            "\tconst C: char = '\"';\n",
+            "\tconst Q: [char; 2] = ['\\'', '\"'];\n",
+            "\tfn f<'a>(s: &'a str) {}\n",
+        )
+    );
+}
 
 pub fn pascal_to_snake_case(s: &str) -> String {
     let mut result = String::new();
@@ -18,6 +235,35 @@ fn test_pascal_to_snake_case() {
     assert_eq!(pascal_to_snake_case(""), "");
 }
 
+/// Converts a PascalCase string to snake_case, treating runs of uppercase letters as a single word.
+///
+/// e.g. "HTTPServer" -> "http_server", "UIButton" -> "ui_button"
+pub fn pascal_to_snake_case_acronyms(s: &str) -> String {
+    let chars = s.chars().collect::<Vec<char>>();
+    let mut result = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev != '_' && (prev.is_lowercase() || prev.is_ascii_digit() || next_is_lower) {
+                result.push('_');
+            }
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+    result
+}
+#[test]
+fn test_pascal_to_snake_case_acronyms() {
+    assert_eq!(pascal_to_snake_case_acronyms("HTTPServer"), "http_server");
+    assert_eq!(pascal_to_snake_case_acronyms("UIButton"), "ui_button");
+    assert_eq!(pascal_to_snake_case_acronyms("XMLParser"), "xml_parser");
+    assert_eq!(pascal_to_snake_case_acronyms("JumpAction"), "jump_action");
+    assert_eq!(pascal_to_snake_case_acronyms("PlayerUI"), "player_ui");
+    assert_eq!(pascal_to_snake_case_acronyms("A"), "a");
+    assert_eq!(pascal_to_snake_case_acronyms(""), "");
+}
+
 /// Converts a string to UpperCamelCase.
 ///
 /// e.g. "example_string" -> "ExampleString"
@@ -45,8 +291,382 @@ fn test_to_upper_camel_case() {
     assert_eq!(to_upper_camel_case(""), "");
 }
 
+/// Converts an arbitrary name, e.g. a group name or translation key, into a valid SCREAMING_SNAKE_CASE const name.
+///
+/// e.g. `"enemies"` -> `"ENEMIES"`, `"PlayerAllies"` -> `"PLAYER_ALLIES"`, `"2d-pickups"` -> `"_2D_PICKUPS"`
+pub fn to_const_name(name: &str) -> String {
+    let words = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(pascal_to_snake_case_acronyms)
+        .collect::<Vec<String>>()
+        .join("_")
+        .to_uppercase();
+
+    if words.is_empty() || words.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", words)
+    } else {
+        words
+    }
+}
+#[test]
+fn test_to_const_name() {
+    assert_eq!(to_const_name("enemies"), "ENEMIES");
+    assert_eq!(to_const_name("PlayerAllies"), "PLAYER_ALLIES");
+    assert_eq!(to_const_name("player allies"), "PLAYER_ALLIES");
+    assert_eq!(to_const_name("2d-pickups"), "_2D_PICKUPS");
+    assert_eq!(to_const_name("UI/HUD"), "UI_HUD");
+    assert_eq!(to_const_name("menu.start"), "MENU_START");
+    assert_eq!(to_const_name("MENU_START"), "MENU_START");
+}
+
+/// The engine classes inheriting `Node`, as listed in the Godot 4.5 `extension_api.json`, which godot-rust exposes in
+/// `godot::classes`. Sorted, for `is_engine_class`.
+pub const GODOT_NODE_CLASSES: &[&str] = &[
+    "AcceptDialog",
+    "AimModifier3D",
+    "AnimatableBody2D",
+    "AnimatableBody3D",
+    "AnimatedSprite2D",
+    "AnimatedSprite3D",
+    "AnimationMixer",
+    "AnimationPlayer",
+    "AnimationTree",
+    "Area2D",
+    "Area3D",
+    "AspectRatioContainer",
+    "AudioListener2D",
+    "AudioListener3D",
+    "AudioStreamPlayer",
+    "AudioStreamPlayer2D",
+    "AudioStreamPlayer3D",
+    "BackBufferCopy",
+    "BaseButton",
+    "Bone2D",
+    "BoneAttachment3D",
+    "BoneConstraint3D",
+    "BoxContainer",
+    "Button",
+    "CPUParticles2D",
+    "CPUParticles3D",
+    "CSGBox3D",
+    "CSGCombiner3D",
+    "CSGCylinder3D",
+    "CSGMesh3D",
+    "CSGPolygon3D",
+    "CSGPrimitive3D",
+    "CSGShape3D",
+    "CSGSphere3D",
+    "CSGTorus3D",
+    "Camera2D",
+    "Camera3D",
+    "CanvasGroup",
+    "CanvasItem",
+    "CanvasLayer",
+    "CanvasModulate",
+    "CenterContainer",
+    "CharacterBody2D",
+    "CharacterBody3D",
+    "CheckBox",
+    "CheckButton",
+    "CodeEdit",
+    "CollisionObject2D",
+    "CollisionObject3D",
+    "CollisionPolygon2D",
+    "CollisionPolygon3D",
+    "CollisionShape2D",
+    "CollisionShape3D",
+    "ColorPicker",
+    "ColorPickerButton",
+    "ColorRect",
+    "ConeTwistJoint3D",
+    "ConfirmationDialog",
+    "Container",
+    "Control",
+    "ConvertTransformModifier3D",
+    "CopyTransformModifier3D",
+    "DampedSpringJoint2D",
+    "Decal",
+    "DirectionalLight2D",
+    "DirectionalLight3D",
+    "FileDialog",
+    "FlowContainer",
+    "FogVolume",
+    "FoldableContainer",
+    "GPUParticles2D",
+    "GPUParticles3D",
+    "GPUParticlesAttractor3D",
+    "GPUParticlesAttractorBox3D",
+    "GPUParticlesAttractorSphere3D",
+    "GPUParticlesAttractorVectorField3D",
+    "GPUParticlesCollision3D",
+    "GPUParticlesCollisionBox3D",
+    "GPUParticlesCollisionHeightField3D",
+    "GPUParticlesCollisionSDF3D",
+    "GPUParticlesCollisionSphere3D",
+    "Generic6DOFJoint3D",
+    "GeometryInstance3D",
+    "GraphEdit",
+    "GraphElement",
+    "GraphFrame",
+    "GraphNode",
+    "GridContainer",
+    "GridMap",
+    "GrooveJoint2D",
+    "HBoxContainer",
+    "HFlowContainer",
+    "HScrollBar",
+    "HSeparator",
+    "HSlider",
+    "HSplitContainer",
+    "HTTPRequest",
+    "HingeJoint3D",
+    "ImporterMeshInstance3D",
+    "InstancePlaceholder",
+    "ItemList",
+    "Joint2D",
+    "Joint3D",
+    "Label",
+    "Label3D",
+    "Light2D",
+    "Light3D",
+    "LightOccluder2D",
+    "LightmapGI",
+    "LightmapProbe",
+    "Line2D",
+    "LineEdit",
+    "LinkButton",
+    "LookAtModifier3D",
+    "MarginContainer",
+    "Marker2D",
+    "Marker3D",
+    "MenuBar",
+    "MenuButton",
+    "MeshInstance2D",
+    "MeshInstance3D",
+    "MissingNode",
+    "ModifierBoneTarget3D",
+    "MultiMeshInstance2D",
+    "MultiMeshInstance3D",
+    "MultiplayerSpawner",
+    "MultiplayerSynchronizer",
+    "NavigationAgent2D",
+    "NavigationAgent3D",
+    "NavigationLink2D",
+    "NavigationLink3D",
+    "NavigationObstacle2D",
+    "NavigationObstacle3D",
+    "NavigationRegion2D",
+    "NavigationRegion3D",
+    "NinePatchRect",
+    "Node",
+    "Node2D",
+    "Node3D",
+    "OccluderInstance3D",
+    "OmniLight3D",
+    "OpenXRCompositionLayer",
+    "OpenXRCompositionLayerCylinder",
+    "OpenXRCompositionLayerEquirect",
+    "OpenXRCompositionLayerQuad",
+    "OpenXRHand",
+    "OpenXRRenderModel",
+    "OpenXRRenderModelManager",
+    "OpenXRVisibilityMask",
+    "OptionButton",
+    "Panel",
+    "PanelContainer",
+    "Parallax2D",
+    "ParallaxBackground",
+    "ParallaxLayer",
+    "Path2D",
+    "Path3D",
+    "PathFollow2D",
+    "PathFollow3D",
+    "PhysicalBone2D",
+    "PhysicalBone3D",
+    "PhysicalBoneSimulator3D",
+    "PhysicsBody2D",
+    "PhysicsBody3D",
+    "PinJoint2D",
+    "PinJoint3D",
+    "PointLight2D",
+    "Polygon2D",
+    "Popup",
+    "PopupMenu",
+    "PopupPanel",
+    "ProgressBar",
+    "Range",
+    "RayCast2D",
+    "RayCast3D",
+    "ReferenceRect",
+    "ReflectionProbe",
+    "RemoteTransform2D",
+    "RemoteTransform3D",
+    "ResourcePreloader",
+    "RetargetModifier3D",
+    "RichTextLabel",
+    "RigidBody2D",
+    "RigidBody3D",
+    "RootMotionView",
+    "ScrollBar",
+    "ScrollContainer",
+    "Separator",
+    "ShaderGlobalsOverride",
+    "ShapeCast2D",
+    "ShapeCast3D",
+    "Skeleton2D",
+    "Skeleton3D",
+    "SkeletonIK3D",
+    "SkeletonModifier3D",
+    "Slider",
+    "SliderJoint3D",
+    "SoftBody3D",
+    "SpinBox",
+    "SplitContainer",
+    "SpotLight3D",
+    "SpringArm3D",
+    "SpringBoneCollision3D",
+    "SpringBoneCollisionCapsule3D",
+    "SpringBoneCollisionPlane3D",
+    "SpringBoneCollisionSphere3D",
+    "SpringBoneSimulator3D",
+    "Sprite2D",
+    "Sprite3D",
+    "SpriteBase3D",
+    "StaticBody2D",
+    "StaticBody3D",
+    "StatusIndicator",
+    "SubViewport",
+    "SubViewportContainer",
+    "TabBar",
+    "TabContainer",
+    "TextEdit",
+    "TextureButton",
+    "TextureProgressBar",
+    "TextureRect",
+    "TileMap",
+    "TileMapLayer",
+    "Timer",
+    "TouchScreenButton",
+    "Tree",
+    "VBoxContainer",
+    "VFlowContainer",
+    "VScrollBar",
+    "VSeparator",
+    "VSlider",
+    "VSplitContainer",
+    "VehicleBody3D",
+    "VehicleWheel3D",
+    "VideoStreamPlayer",
+    "Viewport",
+    "VisibleOnScreenEnabler2D",
+    "VisibleOnScreenEnabler3D",
+    "VisibleOnScreenNotifier2D",
+    "VisibleOnScreenNotifier3D",
+    "VisualInstance3D",
+    "VoxelGI",
+    "Window",
+    "WorldEnvironment",
+    "XRAnchor3D",
+    "XRBodyModifier3D",
+    "XRCamera3D",
+    "XRController3D",
+    "XRFaceModifier3D",
+    "XRHandModifier3D",
+    "XRNode3D",
+    "XROrigin3D",
+];
+
+/// Whether `class` is a `Node` class of the engine, rather than one registered by an extension or script.
+pub fn is_engine_class(class: &str) -> bool {
+    GODOT_NODE_CLASSES.binary_search(&class).is_ok()
+}
+#[test]
+fn test_is_engine_class() {
+    assert!(GODOT_NODE_CLASSES.is_sorted());
+    assert!(is_engine_class("Node"));
+    assert!(is_engine_class("CharacterBody2D"));
+    assert!(!is_engine_class("GameState"));
+    assert!(!is_engine_class("crate::GameState"));
+}
+
+/// Resolves a class read from a scene to the type named in generated code: engine classes as is, for importing from
+/// `godot::classes`, and user classes as their path in `custom_class_paths`.
+///
+/// Other classes, e.g. from GDScript or an extension without a configured path, fall back to `Node` with a warning naming `source`.
+pub fn resolve_class_type(
+    class: &str,
+    custom_class_paths: &BTreeMap<String, String>,
+    source: &str,
+) -> String {
+    if let Some(path) = custom_class_paths.get(class) {
+        path.clone()
+    } else if is_engine_class(class) {
+        class.to_string()
+    } else {
+        println!(
+            "cargo::warning=Class {} in {} is not an engine class, typing it as Node; add its path with `custom_class_paths` to use it",
+            class, source
+        );
+        "Node".to_string()
+    }
+}
+#[test]
+fn test_resolve_class_type() {
+    let custom_class_paths = BTreeMap::from([(
+        "GameState".to_string(),
+        "crate::game::GameState".to_string(),
+    )]);
+
+    assert_eq!(
+        resolve_class_type("Node2D", &custom_class_paths, "res://Main.tscn"),
+        "Node2D"
+    );
+    assert_eq!(
+        resolve_class_type("GameState", &custom_class_paths, "res://Main.tscn"),
+        "crate::game::GameState"
+    );
+    assert_eq!(
+        resolve_class_type("Player", &custom_class_paths, "res://Main.tscn"),
+        "Node"
+    );
+}
+
+/// Converts a filesystem path under `resource_path` into a godot `res://` path.
+///
+/// Both paths are normalized to forward slashes, and trailing separators on `resource_path` are ignored. If `path` does not
+/// start with `resource_path` as written, e.g. `./gd/scenes/Main.tscn` and `gd`, both are canonicalized to absolute paths and compared again.
+/// Paths outside of `resource_path` are returned normalized, but otherwise unchanged.
 pub fn to_resource_path(path: &str, resource_path: &str) -> String {
-    path.replace(resource_path, "res:/")
+    if let Some(res_path) = strip_resource_path(path, resource_path) {
+        return res_path;
+    }
+
+    if let (Ok(canonical_path), Ok(canonical_resource_path)) =
+        (fs::canonicalize(path), fs::canonicalize(resource_path))
+        && let Some(res_path) = strip_resource_path(
+            &canonical_path.to_string_lossy(),
+            &canonical_resource_path.to_string_lossy(),
+        )
+    {
+        return res_path;
+    }
+
+    path.replace('\\', "/")
+}
+
+/// Strips `resource_path` from the start of `path` as a `res://` path, if `path` is under it as written.
+fn strip_resource_path(path: &str, resource_path: &str) -> Option<String> {
+    let path = path.replace('\\', "/");
+    let resource_path = resource_path.replace('\\', "/");
+    let resource_path = resource_path.trim_end_matches('/');
+
+    match path.strip_prefix(resource_path) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            Some(format!("res://{}", rest.trim_start_matches('/')))
+        }
+        _ => None,
+    }
 }
 #[test]
 fn test_to_resource_path() {
@@ -55,15 +675,517 @@ fn test_to_resource_path() {
     let expected = "res://scenes/Main.tscn";
     assert_eq!(to_resource_path(path, resource_path), expected);
 }
+#[test]
+fn test_to_resource_path_trailing_slash() {
+    assert_eq!(
+        to_resource_path("./gd/scenes/Main.tscn", "./gd/"),
+        "res://scenes/Main.tscn"
+    );
+    assert_eq!(
+        to_resource_path("./gd//scenes/Main.tscn", "./gd/"),
+        "res://scenes/Main.tscn"
+    );
+}
+#[test]
+fn test_to_resource_path_mixed_separators() {
+    assert_eq!(
+        to_resource_path(
+            "C:\\Projects\\MyGame\\gd\\scenes\\Main.tscn",
+            "C:/Projects/MyGame/gd"
+        ),
+        "res://scenes/Main.tscn"
+    );
+    assert_eq!(
+        to_resource_path(
+            "C:/Projects/MyGame/gd/scenes/Main.tscn",
+            "C:\\Projects\\MyGame\\gd\\"
+        ),
+        "res://scenes/Main.tscn"
+    );
+}
+#[test]
+fn test_to_resource_path_canonicalized() {
+    let dir = TestDir::new("to_resource_path_canonicalized");
+    fs::create_dir_all(dir.join("gd/scenes")).unwrap();
+    fs::create_dir_all(dir.join("rust")).unwrap();
+    fs::write(dir.join("gd/scenes/Main.tscn"), "").unwrap();
+    let dir = dir.to_string_lossy().to_string();
+
+    // mixing `.`, `..` and canonical paths, which only match as written once canonicalized
+    let absolute = fs::canonicalize(format!("{}/gd", dir)).unwrap();
+    for resource_path in [
+        format!("{}/gd", dir),
+        format!("{}/rust/../gd", dir),
+        absolute.to_string_lossy().to_string(),
+    ] {
+        for path in [
+            format!("{}/gd/scenes/Main.tscn", dir),
+            format!("{}/./gd/scenes/Main.tscn", dir),
+        ] {
+            assert_eq!(
+                to_resource_path(&path, &resource_path),
+                "res://scenes/Main.tscn",
+                "{} in {}",
+                path,
+                resource_path
+            );
+        }
+    }
+}
+#[test]
+fn test_to_resource_path_outside_resource_path() {
+    assert_eq!(
+        to_resource_path("./gdx/scenes/Main.tscn", "./gd"),
+        "./gdx/scenes/Main.tscn"
+    );
+}
+
+/// Normalizes a configured resource path, stripping trailing separators and canonicalizing it to an absolute path.
+///
+/// If the path cannot be canonicalized (e.g. it does not exist), the trimmed path is returned.
+pub fn normalize_resource_path(path: &str) -> String {
+    let trimmed = match path.trim_end_matches(['/', '\\']) {
+        "" => path,
+        trimmed => trimmed,
+    };
+
+    fs::canonicalize(trimmed)
+        .map(|p| strip_verbatim_prefix(&p.to_string_lossy()))
+        .unwrap_or_else(|_| trimmed.to_string())
+}
+#[test]
+fn test_normalize_resource_path() {
+    let dir = TestDir::new("normalize_resource_path");
+    fs::create_dir_all(dir.join("scenes")).unwrap();
+    let canonical = fs::canonicalize(&dir).unwrap();
+
+    let with_slash = format!("{}/", dir.display());
+    assert_eq!(
+        normalize_resource_path(&with_slash),
+        canonical.to_string_lossy()
+    );
+    assert_eq!(
+        to_resource_path(
+            canonical.join("scenes").join("Main.tscn").to_str().unwrap(),
+            &normalize_resource_path(&with_slash)
+        ),
+        "res://scenes/Main.tscn"
+    );
+
+    // missing paths are only trimmed
+    assert_eq!(normalize_resource_path("./missing/gd//"), "./missing/gd");
+}
+
+/// Strips the `\\?\` verbatim prefix `fs::canonicalize` adds on Windows, so the path matches those found by walking the
+/// resource path, e.g. `\\?\C:\game\gd` -> `C:\game\gd` and `\\?\UNC\server\game` -> `\\server\game`.
+fn strip_verbatim_prefix(path: &str) -> String {
+    if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", unc)
+    } else {
+        path.strip_prefix(r"\\?\").unwrap_or(path).to_string()
+    }
+}
+#[test]
+fn test_strip_verbatim_prefix() {
+    assert_eq!(strip_verbatim_prefix(r"\\?\C:\game\gd"), r"C:\game\gd");
+    assert_eq!(
+        strip_verbatim_prefix(r"\\?\UNC\server\game\gd"),
+        r"\\server\game\gd"
+    );
+    assert_eq!(strip_verbatim_prefix("/home/game/gd"), "/home/game/gd");
+}
+
+/// An empty `zgrcg_test_<name>` directory in the system temp directory for a test, removed with its content when dropped.
+#[cfg(test)]
+pub(crate) struct TestDir(PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    /// Creates the directory, replacing any left behind by a failed run of the test.
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("zgrcg_test_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TestDir(dir)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
 
-pub fn make_path_if_not_exists(path: &str) {
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+#[test]
+fn test_test_dir() {
+    let dir = TestDir::new("test_dir");
+    let path = dir.to_path_buf();
+    fs::write(dir.join("file.rs"), "").unwrap();
+    assert!(path.join("file.rs").exists());
+
+    drop(dir);
+    assert!(!path.exists());
+}
+
+/// Creates an empty file at `path`, along with any missing parent directories, if it does not already exist.
+pub fn make_path_if_not_exists(path: &str) -> io::Result<()> {
     let path_obj = std::path::Path::new(path);
     if !path_obj.exists() {
         if let Some(parent) = path_obj.parent() {
             if !parent.exists() {
-                fs::create_dir_all(parent).unwrap();
+                fs::create_dir_all(parent)?;
             }
         }
-        fs::File::create(path_obj).unwrap();
+        fs::File::create(path_obj)?;
+    }
+    Ok(())
+}
+#[test]
+fn test_make_path_if_not_exists() {
+    let dir = TestDir::new("make_path_if_not_exists");
+
+    let path = dir.join("nested").join("file.rs");
+    assert!(make_path_if_not_exists(path.to_str().unwrap()).is_ok());
+    assert!(path.exists());
+
+    // a parent that is a file cannot be created as a directory
+    let blocked = path.join("child.rs");
+    assert!(make_path_if_not_exists(blocked.to_str().unwrap()).is_err());
+}
+
+/// Writes generated `content` to `path` with the generated header, creating the file's missing parent directories.
+///
+/// Any error names the path, as cargo reports it with no other context.
+pub fn write_generated_file(path: &Path, content: &str) -> io::Result<()> {
+    make_path_if_not_exists(&path.to_string_lossy())
+        .and_then(|_| fs::write(path, with_generated_header(content)))
+        .map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Failed to write {}: {}", path.display(), e),
+            )
+        })
+}
+#[test]
+fn test_write_generated_file() {
+    let dir = TestDir::new("write_generated_file");
+
+    let path = dir.join("nested").join("consts.rs");
+    write_generated_file(&path, "pub const A: i32 = 1;\n").unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        with_generated_header("pub const A: i32 = 1;\n")
+    );
+
+    // a parent that is a file cannot be created as a directory
+    let blocked = path.join("child.rs");
+    let error = write_generated_file(&blocked, "").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with(&format!("Failed to write {}: ", blocked.display()))
+    );
+}
+
+/// Replaces the content of the file at `path` atomically, by writing to a temporary file in the same directory
+/// and renaming it over the original, so an interrupted build can't leave a truncated file behind.
+///
+/// The permissions of the original file are kept where possible.
+pub fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let temp_path = path.with_file_name(format!(".{}.zgrcg.tmp", file_name.to_string_lossy()));
+
+    let result = fs::write(&temp_path, content)
+        .and_then(|_| match fs::metadata(path) {
+            Ok(metadata) => fs::set_permissions(&temp_path, metadata.permissions()),
+            Err(_) => Ok(()),
+        })
+        .and_then(|_| fs::rename(&temp_path, path));
+
+    if result.is_err() && temp_path.is_file() {
+        let _ = fs::remove_file(&temp_path);
     }
+
+    result
+}
+#[test]
+fn test_write_atomically() {
+    let dir = TestDir::new("write_atomically");
+
+    let path = dir.join("rust.gdextension");
+    fs::write(&path, "original").unwrap();
+
+    assert!(write_atomically(&path, "replaced").is_ok());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "replaced");
+    assert!(!dir.join(".rust.gdextension.zgrcg.tmp").exists());
+
+    // a directory in place of the temporary file makes the write fail, leaving the original intact
+    fs::create_dir(dir.join(".rust.gdextension.zgrcg.tmp")).unwrap();
+    assert!(write_atomically(&path, "truncat").is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "replaced");
+}
+
+/// The directories Godot keeps its editor and import caches in, which are skipped when scanning the resource path for scenes.
+pub const GODOT_CACHE_DIRS: &[&str] = &[".godot", ".import"];
+
+/// Recursively finds all files under `path` with the given extension, e.g. `"rs"`, without descending into any directory named
+/// in `skip_dirs`, e.g. `GODOT_CACHE_DIRS`.
+///
+/// Files are returned sorted by path, so the output is stable across platforms and runs.
+///
+/// Entries that cannot be read (e.g. permission-restricted folders) are skipped with a warning.
+pub fn find_files_with_extension(path: &Path, extension: &str, skip_dirs: &[&str]) -> Vec<PathBuf> {
+    let mut files = vec![];
+
+    let walker = walkdir::WalkDir::new(path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !skip_dirs.iter().any(|dir| entry.file_name() == *dir)
+        });
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                println!(
+                    "cargo::warning=Failed to read {}: {}, skipping",
+                    e.path().unwrap_or(path).display(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        if entry.file_type().is_file()
+            && entry.path().extension().is_some_and(|ext| ext == extension)
+        {
+            files.push(entry.into_path());
+        }
+    }
+
+    files
+}
+/// Recursively finds the `.rs` files in `path`, except those whose path relative to `path` matches any of the `ignore_globs`.
+pub fn find_source_files(path: &Path, ignore_globs: &[String]) -> Vec<PathBuf> {
+    let ignore = ignore_globs
+        .iter()
+        .map(|glob| glob_to_regex(glob))
+        .collect::<Vec<Regex>>();
+
+    find_files_with_extension(path, "rs", &[])
+        .into_iter()
+        .filter(|file| {
+            let relative = file
+                .strip_prefix(path)
+                .unwrap_or(file)
+                .to_string_lossy()
+                .replace('\\', "/");
+            !ignore.iter().any(|glob| glob.is_match(&relative))
+        })
+        .collect()
+}
+#[test]
+fn test_find_source_files() {
+    let dir = TestDir::new("find_source_files");
+    fs::create_dir_all(dir.join("generated")).unwrap();
+    fs::write(dir.join("lib.rs"), "").unwrap();
+    fs::write(dir.join("menu.gen.rs"), "").unwrap();
+    fs::write(dir.join("generated").join("mod.rs"), "").unwrap();
+
+    assert_eq!(
+        find_source_files(&dir, &["generated/**".into(), "**/*.gen.rs".into()]),
+        vec![dir.join("lib.rs")]
+    );
+    assert_eq!(find_source_files(&dir, &[]).len(), 3);
+}
+
+/// Whether `path` matches `glob`, with the same syntax as `find_source_files`, e.g. `Test_*` or `demo/**`.
+pub fn matches_glob(glob: &str, path: &str) -> bool {
+    glob_to_regex(glob).is_match(path)
+}
+
+/// Converts a glob into a regex matching a whole `/` separated path.
+///
+/// `*` and `?` match within a path segment, and `**` matches across segments, e.g. `generated/**` or `**/*.gen.rs`.
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern).unwrap()
+}
+#[test]
+fn test_glob_to_regex() {
+    assert!(glob_to_regex("generated/**").is_match("generated/mod.rs"));
+    assert!(glob_to_regex("**/*.gen.rs").is_match("menu.gen.rs"));
+    assert!(glob_to_regex("**/*.gen.rs").is_match("ui/menu.gen.rs"));
+    assert!(!glob_to_regex("*.rs").is_match("ui/menu.rs"));
+    assert!(glob_to_regex("ui/menu?.rs").is_match("ui/menu2.rs"));
+    assert!(!glob_to_regex("menu.rs").is_match("menu_rs"));
+}
+
+#[test]
+fn test_find_files_with_extension() {
+    let dir = TestDir::new("find_files_with_extension");
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("lib.rs"), "").unwrap();
+    fs::write(dir.join("nested").join("menu.rs"), "").unwrap();
+    fs::write(dir.join("nested").join("menu.rs.bk"), "").unwrap();
+
+    let found = find_files_with_extension(&dir, "rs", &[]);
+    assert_eq!(
+        found,
+        vec![dir.join("lib.rs"), dir.join("nested").join("menu.rs")]
+    );
+
+    let found = find_files_with_extension(&dir, "rs", &["nested"]);
+    assert_eq!(found, vec![dir.join("lib.rs")]);
+}
+
+/// A raw value from a Godot config file, e.g. `project.godot` or a `.gdextension`, classified by its type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GdValue<'a> {
+    /// `true` or `false`, in any case.
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    /// The content of a quoted string, without its quotes.
+    Str(&'a str),
+    /// The components of e.g. `Vector2(0, 0)` or `Vector3i(1, 2, 3)`.
+    Vector(Vec<f64>),
+    /// Anything else, e.g. `null` or `Object(...)`, as it was written.
+    Other(&'a str),
+}
+
+impl<'a> GdValue<'a> {
+    /// Classifies `raw`, ignoring surrounding whitespace.
+    pub fn parse(raw: &'a str) -> GdValue<'a> {
+        let value = raw.trim();
+
+        if value.eq_ignore_ascii_case("true") {
+            GdValue::Bool(true)
+        } else if value.eq_ignore_ascii_case("false") {
+            GdValue::Bool(false)
+        } else if let Ok(int) = value.parse::<i64>() {
+            GdValue::Int(int)
+        } else if let Ok(float) = value.parse::<f64>() {
+            GdValue::Float(float)
+        } else if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+            GdValue::Str(&value[1..value.len() - 1])
+        } else if let Some(components) = parse_vector(value) {
+            GdValue::Vector(components)
+        } else {
+            GdValue::Other(value)
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            GdValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            GdValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a float, or an int as a float, e.g. a deadzone of `1`.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            GdValue::Int(i) => Some(*i as f64),
+            GdValue::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the components of a `Vector2`, `Vector3`, `Vector4` or their `i` variants, if every component is a number.
+fn parse_vector(value: &str) -> Option<Vec<f64>> {
+    let (name, rest) = value.split_once('(')?;
+    if !name.starts_with("Vector") {
+        return None;
+    }
+
+    rest.strip_suffix(')')?
+        .split(',')
+        .map(|component| component.trim().parse::<f64>().ok())
+        .collect()
+}
+#[test]
+fn test_gd_value_parse() {
+    assert_eq!(GdValue::parse("true"), GdValue::Bool(true));
+    assert_eq!(GdValue::parse(" TRUE "), GdValue::Bool(true));
+    assert_eq!(GdValue::parse("False"), GdValue::Bool(false));
+    assert_eq!(GdValue::parse("-1"), GdValue::Int(-1));
+    assert_eq!(GdValue::parse("\t4194305\n"), GdValue::Int(4194305));
+    assert_eq!(GdValue::parse("0.5"), GdValue::Float(0.5));
+    assert_eq!(GdValue::parse(" 1e3"), GdValue::Float(1000.0));
+    assert_eq!(GdValue::parse("\"Example\""), GdValue::Str("Example"));
+    assert_eq!(GdValue::parse(" \"\" "), GdValue::Str(""));
+    assert_eq!(GdValue::parse("\"true\""), GdValue::Str("true"));
+    assert_eq!(
+        GdValue::parse("Vector2(0, 1.5)"),
+        GdValue::Vector(vec![0.0, 1.5])
+    );
+    assert_eq!(
+        GdValue::parse("Vector3i(1,2,3)"),
+        GdValue::Vector(vec![1.0, 2.0, 3.0])
+    );
+    assert_eq!(
+        GdValue::parse("Vector2(x, 1)"),
+        GdValue::Other("Vector2(x, 1)")
+    );
+    assert_eq!(GdValue::parse(" null "), GdValue::Other("null"));
+    assert_eq!(GdValue::parse("\""), GdValue::Other("\""));
+}
+#[test]
+fn test_gd_value_as() {
+    assert_eq!(GdValue::parse("true").as_bool(), Some(true));
+    assert_eq!(GdValue::parse("1").as_bool(), None);
+    assert_eq!(GdValue::parse("7").as_int(), Some(7));
+    assert_eq!(GdValue::parse("7.0").as_int(), None);
+    assert_eq!(GdValue::parse("1").as_float(), Some(1.0));
+    assert_eq!(GdValue::parse("0.25").as_float(), Some(0.25));
+    assert_eq!(GdValue::parse("\"0.25\"").as_float(), None);
 }